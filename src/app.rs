@@ -1,2 +1,6 @@
 #[cfg(any(feature = "alloc", feature = "std"))]
 pub mod client;
+#[cfg(any(feature = "alloc", feature = "std"))]
+pub mod server;
+#[cfg(all(feature = "tokio", any(feature = "alloc", feature = "std")))]
+pub mod shared;
@@ -1,73 +1,567 @@
-use crate::error::{ModbusError, ModbusTransportError};
+use crate::error::{
+    ModbusApplicationError, ModbusError, ModbusFrameError, ModbusPduError, ModbusTransportError,
+};
+use crate::frame::pdu::fcode::{DiagnosticSubFunction, ExceptionCode, PublicFunctionCode};
 use crate::frame::pdu::function::Response;
+use crate::frame::pdu::types::{FileRecord, WordOrder};
 use crate::frame::pdu::Pdu;
-use crate::transport::Transport;
+use crate::lib::*;
+#[cfg(feature = "tokio")]
+use crate::transport::multibus::MultiBusTransport;
+use crate::transport::{TransactionTiming, Transport};
+use core::ops::RangeInclusive;
+use core::time::Duration;
 
 use crate::frame::pdu::function::request::*;
 use crate::frame::pdu::function::response::*;
 use crate::Result;
 
+/// Maximum number of registers read per Read Holding Registers request
+const MAX_READ_REGISTERS: u16 = 125;
+
+/// Maximum number of registers written per Write Multiple Registers request
+const MAX_WRITE_REGISTERS: usize = 123;
+
+/// Default number of unwanted registers bridged by a scattered read
+const DEFAULT_SCATTER_MAX_GAP: u16 = 8;
+
+/// Re-sending of requests lost on the line
+///
+/// A request is sent again when no complete response arrived, i.e. on `Timeout` or
+/// `FrameIncomplete`. Exceptions and undecodable responses are returned at once, as sending
+/// the same request again would not help.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RetryPolicy {
+    /// Number of times a request is sent again after the first attempt
+    pub max_retries: u8,
+    /// Delay before each retry, only observed with the `tokio` feature
+    pub backoff: Duration,
+}
+
+/// Device id every slave acts on without answering
+const BROADCAST_DEVICE_ID: u8 = 0;
+
+/// Snapshot of the diagnostic counters of a server (Diagnostics sub-functions `0x000B`-`0x0012`)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DiagnosticCounters {
+    pub bus_message: u16,
+    pub bus_comm_error: u16,
+    pub bus_exception: u16,
+    pub slave_message: u16,
+    pub slave_no_response: u16,
+    pub slave_nak: u16,
+    pub slave_busy: u16,
+    pub bus_char_overrun: u16,
+}
+
+/// Identification objects of a device, collected by Read Device Identification
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceIdentification {
+    conformity_level: u8,
+    objects: Vec<(u8, Vec<u8>)>,
+}
+
+impl DeviceIdentification {
+    pub fn conformity_level(&self) -> u8 {
+        self.conformity_level
+    }
+
+    /// Raw value of any object
+    pub fn object(&self, object_id: u8) -> Option<&[u8]> {
+        self.objects
+            .iter()
+            .find(|(id, _)| *id == object_id)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    /// Iterate over the `(object_id, value)` pairs in the order they were received
+    pub fn objects(&self) -> impl Iterator<Item = (u8, &[u8])> {
+        self.objects
+            .iter()
+            .map(|(id, value)| (*id, value.as_slice()))
+    }
+
+    /// Standard object as text, `None` if missing or not ASCII
+    fn text(&self, object_id: u8) -> Option<&str> {
+        let value = self.object(object_id)?;
+
+        if value.is_ascii() {
+            core::str::from_utf8(value).ok()
+        } else {
+            None
+        }
+    }
+
+    pub fn vendor_name(&self) -> Option<&str> {
+        self.text(0x00)
+    }
+
+    pub fn product_code(&self) -> Option<&str> {
+        self.text(0x01)
+    }
+
+    pub fn major_minor_revision(&self) -> Option<&str> {
+        self.text(0x02)
+    }
+
+    pub fn vendor_url(&self) -> Option<&str> {
+        self.text(0x03)
+    }
+
+    pub fn product_name(&self) -> Option<&str> {
+        self.text(0x04)
+    }
+
+    pub fn model_name(&self) -> Option<&str> {
+        self.text(0x05)
+    }
+
+    pub fn user_application_name(&self) -> Option<&str> {
+        self.text(0x06)
+    }
+
+    /// Vendor specific object (`0x80`-`0xFF`)
+    pub fn extended(&self, object_id: u8) -> Option<&[u8]> {
+        if object_id < 0x80 {
+            return None;
+        }
+
+        self.object(object_id)
+    }
+}
+
+/// Request polled by `poll_once` from the given device
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollItem {
+    pub device_id: u8,
+    pub request: RequestPdu,
+}
+
+impl PollItem {
+    pub fn new(device_id: u8, request: impl Into<Pdu>) -> Self {
+        Self {
+            device_id,
+            request: RequestPdu::from(request.into()),
+        }
+    }
+}
+
+/// Outcome of a polled request, classified for reporting the health of each point
+#[derive(Debug)]
+pub enum PollOutcome {
+    /// Response decoded by function
+    Ok(Box<ResponsePdu>),
+    /// No response, the device may be offline
+    Timeout,
+    /// The device answered with an exception
+    Exception(ExceptionCode),
+    /// A response failed its CRC, or its LRC on ASCII, hinting at a noisy line
+    CrcError,
+    /// Any other error
+    Other(ModbusError),
+}
+
+impl PollOutcome {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok(_))
+    }
+}
+
+impl From<ModbusError> for PollOutcome {
+    fn from(err: ModbusError) -> Self {
+        match err {
+            ModbusError::TransportError(ModbusTransportError::Timeout) => Self::Timeout,
+            ModbusError::Exception(code) => Self::Exception(code),
+            ModbusError::FrameError(ref frame_err) if is_checksum_failure(frame_err) => {
+                Self::CrcError
+            }
+            // Transports report framing errors boxed
+            ModbusError::TransportError(ModbusTransportError::TransportError(ref inner))
+                if inner
+                    .downcast_ref::<ModbusFrameError>()
+                    .is_some_and(is_checksum_failure) =>
+            {
+                Self::CrcError
+            }
+            err => Self::Other(err),
+        }
+    }
+}
+
+/// Outcome of `Client::await_long_operation`
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub enum LongOperationOutcome {
+    /// The server answered the request right away, with this response
+    Completed(Pdu),
+    /// The server acknowledged the request and later finished it, as last reported by Get
+    /// Comm Event Counter
+    Finished(GetCommEventCounterResponse),
+}
+
+/// Response of a read request together with the addresses it covers
+///
+/// A read response does not carry its starting address, so the client keeps it from the
+/// request. Dereferences to the response itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadResult<R> {
+    response: R,
+    starting_address: u16,
+    quantity: u16,
+}
+
+impl<R> Deref for ReadResult<R> {
+    type Target = R;
+
+    fn deref(&self) -> &Self::Target {
+        &self.response
+    }
+}
+
+impl<R> ReadResult<R> {
+    pub fn new(response: R, starting_address: u16, quantity: u16) -> Self {
+        Self {
+            response,
+            starting_address,
+            quantity,
+        }
+    }
+
+    pub fn starting_address(&self) -> u16 {
+        self.starting_address
+    }
+
+    pub fn quantity(&self) -> u16 {
+        self.quantity
+    }
+
+    /// Address of the `index`-th value of the response
+    pub fn address_of(&self, index: usize) -> u16 {
+        self.starting_address.wrapping_add(index as u16)
+    }
+
+    /// Addresses covered by the response
+    pub fn range(&self) -> RangeInclusive<u16> {
+        self.starting_address..=self.address_of(self.quantity.saturating_sub(1) as usize)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.response
+    }
+}
+
+/// Client addressing one device, obtained from `Client::for_device`
+///
+/// Dereferences to the client, so that any request goes to this device. The previously
+/// addressed device is restored when dropped.
+pub struct DeviceClient<'a, T: Transport> {
+    client: &'a mut Client<T>,
+    previous: Option<u8>,
+}
+
+impl<T: Transport> Deref for DeviceClient<'_, T> {
+    type Target = Client<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.client
+    }
+}
+
+impl<T: Transport> DerefMut for DeviceClient<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.client
+    }
+}
+
+impl<T: Transport> Drop for DeviceClient<'_, T> {
+    fn drop(&mut self) {
+        if let Some(device_id) = self.previous {
+            self.client.transport.set_device_id(device_id);
+        }
+    }
+}
+
+/// Application level check applied to every response
+type ResponseValidator = Box<dyn Fn(&Pdu) -> Result<()> + Send + Sync>;
+
 /// Modbus client handler
 pub struct Client<T: Transport> {
     transport: T,
+    scatter_max_gap: u16,
+    response_validator: Option<ResponseValidator>,
+    idle_bus_check: Option<Duration>,
+    purge_on_request: bool,
+    /// Event count of the latest `comm_event_delta` per addressed device
+    last_event_counts: Vec<(Option<u8>, u16)>,
+    strict_validation: bool,
+    retry: RetryPolicy,
+    /// Retries made by the latest request
+    retry_count: u8,
 }
 
 impl<T: Transport> Client<T> {
     pub fn new(transport: T) -> Self {
-        Self { transport }
+        Self {
+            transport,
+            scatter_max_gap: DEFAULT_SCATTER_MAX_GAP,
+            response_validator: None,
+            idle_bus_check: None,
+            purge_on_request: false,
+            last_event_counts: Vec::new(),
+            strict_validation: false,
+            retry: RetryPolicy::default(),
+            retry_count: 0,
+        }
+    }
+
+    /// Retry requests lost on the line according to `policy`
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Number of retries the latest request took, a measure of the line quality
+    pub fn retry_count(&self) -> u8 {
+        self.retry_count
+    }
+
+    /// Close the underlying transport gracefully
+    pub async fn close(self) -> Result<()> {
+        self.transport.close().await.map_err(transport_error)?;
+
+        Ok(())
+    }
+
+    /// Set the device addressed by the following requests
+    ///
+    /// This is the slave address on RTU and the unit identifier on TCP.
+    pub fn set_device_id(&mut self, device_id: u8) {
+        self.transport.set_device_id(device_id);
+
+        let device_id = self.transport.device_id();
+        self.last_event_counts.retain(|(id, _)| *id != device_id);
+    }
+
+    /// Address `device_id` through the returned handle only
+    ///
+    /// e.g. `client.for_device(0x11).read_holding_registers(0x0000, 2)` reads from device
+    /// `0x11` and leaves the device addressed by `client` itself unchanged, which is handy
+    /// when scanning a bus or talking to several devices through one port. The previous
+    /// device can only be restored when the transport reports it.
+    ///
+    /// `Transport::send` carries no address, so the device is switched on the transport for
+    /// the lifetime of the handle instead of being passed with each frame. The handle borrows
+    /// the client mutably, so no other request goes out to the wrong device meanwhile. Unlike
+    /// `set_device_id`, the `comm_event_delta` baseline of the device is kept.
+    pub fn for_device(&mut self, device_id: u8) -> DeviceClient<'_, T> {
+        let previous = self.transport.device_id();
+        self.transport.set_device_id(device_id);
+
+        DeviceClient {
+            client: self,
+            previous,
+        }
+    }
+
+    /// Transaction id of the latest request, e.g. the MBAP transaction id on TCP
+    ///
+    /// `None` before the first request or when the transport carries no transaction id.
+    pub fn last_transaction_id(&self) -> Option<u16> {
+        self.transport.last_transaction_id()
+    }
+
+    /// Set a plausibility check run on every response before it is returned
+    ///
+    /// The validator sees the response PDU once framing and exception checks passed. An error
+    /// it returns is handed to the caller instead of the response.
+    pub fn set_response_validator(
+        &mut self,
+        validator: impl Fn(&Pdu) -> Result<()> + Send + Sync + 'static,
+    ) {
+        self.response_validator = Some(Box::new(validator));
+    }
+
+    /// Check that read responses carry exactly the requested quantity
+    ///
+    /// When strict, a response whose byte count or data is shorter or longer than the
+    /// requested quantity takes is refused with `ResponseMismatch`, e.g. from firmware that
+    /// sends extra registers. Off by default.
+    pub fn set_strict_validation(&mut self, strict: bool) {
+        self.strict_validation = strict;
+    }
+
+    /// Require the bus to be silent for `within` before each request
+    ///
+    /// A request is refused with `BusBusy` instead of being sent into the traffic of a
+    /// babbling slave. `None`, the default, sends without checking.
+    pub fn set_idle_bus_check(&mut self, within: Option<Duration>) {
+        self.idle_bus_check = within;
+    }
+
+    /// Discard stale received bytes before each request
+    ///
+    /// On a bus shared with other masters, bytes of a foreign transaction may be waiting in the
+    /// receive buffer and would be prepended to the response. Off by default.
+    pub fn set_purge_on_request(&mut self, purge: bool) {
+        self.purge_on_request = purge;
+    }
+
+    /// Set the largest gap of unwanted registers bridged by `read_holding_registers_scattered`
+    ///
+    /// Addresses further apart than this are read by separate requests.
+    pub fn set_scatter_max_gap(&mut self, max_gap: u16) {
+        self.scatter_max_gap = max_gap;
     }
 
     pub async fn read_coils(
         &mut self,
         starting_address: u16,
         quantity_of_coils: u16,
-    ) -> Result<ReadCoilsResponse> {
+    ) -> Result<ReadResult<ReadCoilsResponse>> {
         let read_coils = ReadCoilsRequest::new(starting_address, quantity_of_coils)?;
-        let response = self.send_request(&read_coils.into_inner()).await?;
+        let response: ReadCoilsResponse = self.execute(read_coils).await?;
+        self.validate_byte_count(response.as_pdu(), quantity_of_coils.div_ceil(8) as usize)?;
 
-        Response::try_from(response).map_err(|e| ModbusError::FrameError(e.into()))
+        Ok(ReadResult::new(
+            response,
+            starting_address,
+            quantity_of_coils,
+        ))
     }
 
     pub async fn read_discrete_inputs(
         &mut self,
         starting_address: u16,
         quantity_of_inputs: u16,
-    ) -> Result<ReadDiscreteInputsResponse> {
+    ) -> Result<ReadResult<ReadDiscreteInputsResponse>> {
         let read_discrete_inputs =
             ReadDiscreteInputsRequest::new(starting_address, quantity_of_inputs)?;
-        let response = self
-            .send_request(&read_discrete_inputs.into_inner())
-            .await?;
+        let response: ReadDiscreteInputsResponse = self.execute(read_discrete_inputs).await?;
+        self.validate_byte_count(response.as_pdu(), quantity_of_inputs.div_ceil(8) as usize)?;
 
-        Response::try_from(response).map_err(|e| ModbusError::FrameError(e.into()))
+        Ok(ReadResult::new(
+            response,
+            starting_address,
+            quantity_of_inputs,
+        ))
     }
 
     pub async fn read_holding_registers(
         &mut self,
         starting_address: u16,
         quantity_of_registers: u16,
-    ) -> Result<ReadHoldingRegistersResponse> {
+    ) -> Result<ReadResult<ReadHoldingRegistersResponse>> {
         let read_holding_registers =
             ReadHoldingRegistersRequest::new(starting_address, quantity_of_registers)?;
+        let response: ReadHoldingRegistersResponse = self.execute(read_holding_registers).await?;
+        self.validate_byte_count(response.as_pdu(), quantity_of_registers as usize * 2)?;
+
+        Ok(ReadResult::new(
+            response,
+            starting_address,
+            quantity_of_registers,
+        ))
+    }
+
+    /// Read holding registers, also returning the timing of the transaction
+    pub async fn read_holding_registers_timed(
+        &mut self,
+        starting_address: u16,
+        quantity_of_registers: u16,
+    ) -> Result<(
+        ReadResult<ReadHoldingRegistersResponse>,
+        Option<TransactionTiming>,
+    )> {
         let response = self
-            .send_request(&read_holding_registers.into_inner())
+            .read_holding_registers(starting_address, quantity_of_registers)
             .await?;
 
-        Response::try_from(response).map_err(|e| ModbusError::FrameError(e.into()))
+        Ok((response, self.transport.last_timing()))
+    }
+
+    /// Read holding registers at arbitrary addresses
+    ///
+    /// Nearby addresses are grouped into as few contiguous reads as possible, and the values
+    /// are returned in the order of `addresses`.
+    pub async fn read_holding_registers_scattered(
+        &mut self,
+        addresses: &[u16],
+    ) -> Result<Vec<u16>> {
+        let blocks = group_addresses(addresses, self.scatter_max_gap);
+
+        let mut values = Vec::with_capacity(blocks.len());
+        for &(starting_address, quantity_of_registers) in &blocks {
+            let response = self
+                .read_holding_registers(starting_address, quantity_of_registers)
+                .await?;
+            values.push(response);
+        }
+
+        addresses
+            .iter()
+            .map(|&address| {
+                let index = blocks.partition_point(|&(start, _)| start <= address) - 1;
+                let offset = address - blocks[index].0;
+                values[index]
+                    .register(offset as usize)
+                    .ok_or(ModbusApplicationError::ResponseMismatch.into())
+            })
+            .collect()
+    }
+
+    /// Find the end of the holding register map of a device that does not document it
+    ///
+    /// Reads `step` registers at a time from `start` upward, until the server answers with
+    /// `IllegalDataAddress` or `max` is reached, and returns the highest address read
+    /// successfully. Once a block is refused, its registers are probed one by one. Other
+    /// exceptions and errors are returned as is, as is `IllegalDataAddress` when not even
+    /// `start` can be read.
+    pub async fn probe_register_range(&mut self, start: u16, step: u16, max: u16) -> Result<u16> {
+        let mut step = step.clamp(1, MAX_READ_REGISTERS);
+        let mut highest = None;
+        let mut address = start;
+
+        while address <= max {
+            let quantity = step.min((max - address).saturating_add(1));
+            match self.read_holding_registers(address, quantity).await {
+                Ok(_) => {
+                    let last = address + (quantity - 1);
+                    highest = Some(last);
+                    if last == max {
+                        break;
+                    }
+                    address = last + 1;
+                }
+                Err(ModbusError::Exception(ExceptionCode::IllegalDataAddress)) if step > 1 => {
+                    step = 1;
+                }
+                Err(ModbusError::Exception(ExceptionCode::IllegalDataAddress))
+                    if highest.is_some() =>
+                {
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        highest.ok_or(ModbusError::Exception(ExceptionCode::IllegalDataAddress))
     }
 
     pub async fn read_input_registers(
         &mut self,
         starting_address: u16,
         quantity_of_registers: u16,
-    ) -> Result<ReadInputRegistersResponse> {
+    ) -> Result<ReadResult<ReadInputRegistersResponse>> {
         let read_input_registers =
             ReadInputRegistersRequest::new(starting_address, quantity_of_registers)?;
-        let response = self
-            .send_request(&read_input_registers.into_inner())
-            .await?;
+        let response: ReadInputRegistersResponse = self.execute(read_input_registers).await?;
+        self.validate_byte_count(response.as_pdu(), quantity_of_registers as usize * 2)?;
 
-        Response::try_from(response).map_err(|e| ModbusError::FrameError(e.into()))
+        Ok(ReadResult::new(
+            response,
+            starting_address,
+            quantity_of_registers,
+        ))
     }
 
     pub async fn write_single_coil(
@@ -76,9 +570,7 @@ impl<T: Transport> Client<T> {
         output_value: bool,
     ) -> Result<WriteSingleCoilResponse> {
         let write_single_coil = WriteSingleCoilRequest::new(output_address, output_value)?;
-        let response = self.send_request(&write_single_coil.into_inner()).await?;
-
-        Response::try_from(response).map_err(|e| ModbusError::FrameError(e.into()))
+        self.execute(write_single_coil).await
     }
 
     pub async fn write_single_register(
@@ -88,11 +580,450 @@ impl<T: Transport> Client<T> {
     ) -> Result<WriteSingleRegisterResponse> {
         let write_single_register =
             WriteSingleRegisterRequest::new(register_address, register_value)?;
+        self.execute(write_single_register).await
+    }
+
+    pub async fn write_multiple_registers(
+        &mut self,
+        starting_address: u16,
+        registers_value: &[u16],
+    ) -> Result<WriteMultipleRegistersResponse> {
+        let write_multiple_registers =
+            WriteMultipleRegistersRequest::new(starting_address, registers_value)?;
+        self.execute(write_multiple_registers).await
+    }
+
+    /// Set and clear bits of a holding register in one transaction
+    ///
+    /// The register becomes `(current AND and_mask) OR (or_mask AND (NOT and_mask))`. The
+    /// response must echo the request.
+    pub async fn mask_write_register(
+        &mut self,
+        reference_address: u16,
+        and_mask: u16,
+        or_mask: u16,
+    ) -> Result<MaskWriteRegisterResponse> {
+        let mask_write_register =
+            MaskWriteRegisterRequest::new(reference_address, and_mask, or_mask)?;
+        let response: MaskWriteRegisterResponse = self.execute(mask_write_register.clone()).await?;
+
+        if !mask_write_register.verify_echo(&response) {
+            return Err(ModbusApplicationError::ResponseMismatch.into());
+        }
+
+        Ok(response)
+    }
+
+    /// Write registers and read registers in one transaction
+    ///
+    /// The server performs the write before the read.
+    pub async fn read_write_multiple_registers(
+        &mut self,
+        read_starting_address: u16,
+        quantity_to_read: u16,
+        write_starting_address: u16,
+        write_registers_value: &[u16],
+    ) -> Result<ReadWriteMultipleRegistersResponse> {
+        let read_write_multiple_registers = ReadWriteMultipleRegistersRequest::new(
+            read_starting_address,
+            quantity_to_read,
+            write_starting_address,
+            write_registers_value,
+        )?;
+        self.execute(read_write_multiple_registers).await
+    }
+
+    /// Write records into files of the server, each record a run of registers
+    ///
+    /// The response must echo the request.
+    pub async fn write_file_record(
+        &mut self,
+        records: &[FileRecord<'_>],
+    ) -> Result<WriteFileRecordResponse> {
+        let write_file_record = WriteFileRecordRequest::new(records)?;
+        let response: WriteFileRecordResponse = self.execute(write_file_record.clone()).await?;
+
+        if !write_file_record.verify_echo(&response) {
+            return Err(ModbusApplicationError::ResponseMismatch.into());
+        }
+
+        Ok(response)
+    }
+
+    /// Write an `f32` setpoint into two registers in the given word order
+    pub async fn write_f32(&mut self, address: u16, value: f32, order: WordOrder) -> Result<()> {
+        self.write_u32(address, value.to_bits(), order).await
+    }
+
+    /// Write a `u32` into two registers in the given word order
+    pub async fn write_u32(&mut self, address: u16, value: u32, order: WordOrder) -> Result<()> {
+        self.write_register_block(address, &order.to_registers_u32(value))
+            .await
+    }
+
+    /// Write an `i32` into two registers in the given word order
+    pub async fn write_i32(&mut self, address: u16, value: i32, order: WordOrder) -> Result<()> {
+        self.write_u32(address, value as u32, order).await
+    }
+
+    /// Write registers at arbitrary addresses with as few requests as possible
+    ///
+    /// The writes are sorted by address and each run of consecutive addresses is sent as one
+    /// Write Multiple Registers request, while an isolated address uses Write Single Register.
+    /// When the slave rejects a run with an exception, its registers are written one by one so
+    /// that the exception is reported against the offending writes only.
+    ///
+    /// The outcome of each write is returned in the order of `writes`. Any other error, e.g. a
+    /// timeout, aborts the remaining writes.
+    pub async fn write_registers(
+        &mut self,
+        writes: &[(u16, u16)],
+    ) -> Result<Vec<result::Result<(), ExceptionCode>>> {
+        let mut order: Vec<usize> = (0..writes.len()).collect();
+        order.sort_by_key(|&index| writes[index].0);
+
+        let mut outcomes: Vec<_> = writes.iter().map(|_| Ok(())).collect();
+        let mut run_start = 0;
+        while run_start < order.len() {
+            let mut run_end = run_start + 1;
+            while run_end < order.len()
+                && run_end - run_start < MAX_WRITE_REGISTERS
+                && writes[order[run_end - 1]].0.checked_add(1) == Some(writes[order[run_end]].0)
+            {
+                run_end += 1;
+            }
+            let run = &order[run_start..run_end];
+            run_start = run_end;
+
+            if run.len() > 1 {
+                let values: Vec<u16> = run.iter().map(|&index| writes[index].1).collect();
+                match self.write_register_block(writes[run[0]].0, &values).await {
+                    Ok(()) => continue,
+                    Err(ModbusError::Exception(_)) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+
+            for &index in run {
+                let (address, value) = writes[index];
+                match self.write_single_register(address, value).await {
+                    Ok(_) => {}
+                    Err(ModbusError::Exception(exception_code)) => {
+                        outcomes[index] = Err(exception_code)
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Return Query Data (Diagnostics sub-function `0x0000`)
+    ///
+    /// Checks the communication with the server, which must echo `data` unchanged.
+    pub async fn diagnostic_loopback(&mut self, data: u16) -> Result<()> {
+        let response = self
+            .diagnostics(DiagnosticSubFunction::ReturnQueryData, data)
+            .await?;
+
+        if response.data() != Some(data) {
+            return Err(ModbusApplicationError::ResponseMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    /// Restart Communications Option (Diagnostics sub-function `0x0001`)
+    ///
+    /// A server in Listen Only Mode restarts without replying, so a timeout is
+    /// reported as `Ok(None)` rather than as an error.
+    pub async fn restart_communications(
+        &mut self,
+        clear_log: bool,
+    ) -> Result<Option<DiagnosticsResponse>> {
+        let data = if clear_log { 0xFF00 } else { 0x0000 };
+
+        let response = match self
+            .diagnostics(DiagnosticSubFunction::RestartCommunicationsOption, data)
+            .await
+        {
+            Ok(response) => response,
+            Err(ModbusError::TransportError(ModbusTransportError::Timeout)) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        if response.data() != Some(data) {
+            return Err(ModbusApplicationError::ResponseMismatch.into());
+        }
+
+        Ok(Some(response))
+    }
+
+    /// Force the server into Listen Only Mode (Diagnostics sub-function `0x0004`)
+    ///
+    /// The server never answers this request, so it is only sent. In Listen Only Mode the
+    /// server keeps monitoring the bus but neither acts on nor answers any request, except
+    /// Restart Communications Option which is the only way out, see
+    /// `exit_listen_only_via_restart`.
+    pub async fn enter_listen_only(&mut self) -> Result<()> {
+        let diagnostics =
+            DiagnosticsRequest::new(DiagnosticSubFunction::ForceListenOnlyMode.into(), 0x0000)?;
+
+        self.transport
+            .send(diagnostics.as_pdu())
+            .await
+            .map_err(transport_error)?;
+
+        Ok(())
+    }
+
+    /// Bring the server back online with Restart Communications Option
+    ///
+    /// A server in Listen Only Mode restarts without replying, while a server already online
+    /// echoes the request before restarting. Both are a success. With `clear_log` the
+    /// communication event log is cleared as well.
+    pub async fn exit_listen_only_via_restart(&mut self, clear_log: bool) -> Result<()> {
+        self.restart_communications(clear_log).await?;
+
+        Ok(())
+    }
+
+    /// Read every diagnostic counter of the server, one sub-function at a time
+    pub async fn read_all_diagnostic_counters(&mut self) -> Result<DiagnosticCounters> {
+        Ok(DiagnosticCounters {
+            bus_message: self
+                .diagnostic_counter(DiagnosticSubFunction::ReturnBusMessageCount)
+                .await?,
+            bus_comm_error: self
+                .diagnostic_counter(DiagnosticSubFunction::ReturnBusCommunicationErrorCount)
+                .await?,
+            bus_exception: self
+                .diagnostic_counter(DiagnosticSubFunction::ReturnBusExceptionErrorCount)
+                .await?,
+            slave_message: self
+                .diagnostic_counter(DiagnosticSubFunction::ReturnServerMessageCount)
+                .await?,
+            slave_no_response: self
+                .diagnostic_counter(DiagnosticSubFunction::ReturnServerNoResponseCount)
+                .await?,
+            slave_nak: self
+                .diagnostic_counter(DiagnosticSubFunction::ReturnServerNakCount)
+                .await?,
+            slave_busy: self
+                .diagnostic_counter(DiagnosticSubFunction::ReturnServerBusyCount)
+                .await?,
+            bus_char_overrun: self
+                .diagnostic_counter(DiagnosticSubFunction::ReturnBusCharacterOverrunCount)
+                .await?,
+        })
+    }
+
+    /// Clear Counters and Diagnostic Register (Diagnostics sub-function `0x000A`)
+    pub async fn clear_counters(&mut self) -> Result<()> {
         let response = self
-            .send_request(&write_single_register.into_inner())
+            .diagnostics(
+                DiagnosticSubFunction::ClearCountersAndDiagnosticRegister,
+                0x0000,
+            )
             .await?;
 
-        Response::try_from(response).map_err(|e| ModbusError::FrameError(e.into()))
+        if response.data() != Some(0x0000) {
+            return Err(ModbusApplicationError::ResponseMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    /// Clear Overrun Counter and Flag (Diagnostics sub-function `0x0014`)
+    pub async fn clear_overrun_counter(&mut self) -> Result<()> {
+        let response = self
+            .diagnostics(DiagnosticSubFunction::ClearOverrunCounterAndFlag, 0x0000)
+            .await?;
+
+        if response.data() != Some(0x0000) {
+            return Err(ModbusApplicationError::ResponseMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    /// Read identification objects of the device in one transaction
+    ///
+    /// `read_device_id_code` selects the basic (`0x01`), regular (`0x02`) or extended (`0x03`)
+    /// stream from `object_id` on, or one specific object (`0x04`). Objects not fitting into a
+    /// single response are indicated by `more_follows()`, to be continued from
+    /// `next_object_id()`.
+    pub async fn read_device_identification(
+        &mut self,
+        read_device_id_code: u8,
+        object_id: u8,
+    ) -> Result<ReadDeviceIdentificationResponse> {
+        let read_device_identification =
+            ReadDeviceIdentificationRequest::new(read_device_id_code, object_id)?;
+        self.execute(read_device_identification).await
+    }
+
+    /// Read every identification object of the device
+    ///
+    /// Uses the extended access (Read Device ID code `0x03`) and follows the "more follows"
+    /// indication across as many transactions as the device needs.
+    pub async fn read_device_identification_all(&mut self) -> Result<DeviceIdentification> {
+        let mut identification = DeviceIdentification::default();
+        let mut object_id = 0x00;
+
+        loop {
+            let response = self.read_device_identification(0x03, object_id).await?;
+
+            let objects = response
+                .objects()
+                .ok_or(ModbusApplicationError::ResponseMismatch)?;
+            identification.conformity_level = response.conformity_level().unwrap_or_default();
+            identification
+                .objects
+                .extend(objects.map(|(id, value)| (id, value.to_vec())));
+
+            match (response.more_follows(), response.next_object_id()) {
+                // The next object must move forward, or a faulty device keeps us looping
+                (Some(true), Some(next)) if next > object_id => object_id = next,
+                (Some(true), _) => return Err(ModbusApplicationError::ResponseMismatch.into()),
+                _ => return Ok(identification),
+            }
+        }
+    }
+
+    pub async fn read_exception_status(&mut self) -> Result<ReadExceptionStatusResponse> {
+        let read_exception_status = ReadExceptionStatusRequest::new()?;
+        self.execute(read_exception_status).await
+    }
+
+    pub async fn get_comm_event_counter(&mut self) -> Result<GetCommEventCounterResponse> {
+        let get_comm_event_counter = GetCommEventCounterRequest::new()?;
+        self.execute(get_comm_event_counter).await
+    }
+
+    /// Number of events counted by the server since the previous call
+    ///
+    /// The server counts each message it completed successfully, so a delta smaller than the
+    /// requests sent meanwhile reveals requests lost on the wire. Reading the counter does not
+    /// count itself. The first call for each device, and the first after `set_device_id`, only
+    /// records the count and returns `None`. A counter wrapping past `0xFFFF` is taken into
+    /// account.
+    pub async fn comm_event_delta(&mut self) -> Result<Option<u16>> {
+        let event_count = self
+            .get_comm_event_counter()
+            .await?
+            .event_count()
+            .ok_or(ModbusApplicationError::ResponseMismatch)?;
+
+        let device_id = self.transport.device_id();
+        match self
+            .last_event_counts
+            .iter_mut()
+            .find(|(id, _)| *id == device_id)
+        {
+            Some((_, last)) => {
+                let delta = event_count.wrapping_sub(*last);
+                *last = event_count;
+                Ok(Some(delta))
+            }
+            None => {
+                self.last_event_counts.push((device_id, event_count));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Send each request in turn and classify its outcome
+    ///
+    /// Errors do not stop the poll, every item gets an outcome in the order of `items`. The
+    /// device id set before is addressed again afterwards when the transport reports it,
+    /// otherwise the device of the last item stays addressed.
+    pub async fn poll_once(&mut self, items: &[PollItem]) -> Vec<PollOutcome> {
+        let device_id = self.transport.device_id();
+        let mut outcomes = Vec::with_capacity(items.len());
+
+        for item in items {
+            self.transport.set_device_id(item.device_id);
+            let outcome = match self.request(item.request.clone()).await {
+                Ok(response) => PollOutcome::Ok(Box::new(response)),
+                Err(err) => PollOutcome::from(err),
+            };
+            outcomes.push(outcome);
+        }
+
+        if let Some(device_id) = device_id {
+            self.transport.set_device_id(device_id);
+        }
+        outcomes
+    }
+
+    /// Find the slaves answering on the bus, e.g. for commissioning
+    ///
+    /// `probe` is sent to each address of `device_ids`, typically a read of one register. A
+    /// response or an exception proves a device, while a device that stays silent for `timeout`
+    /// is taken as absent, as is a garbled response. Keep `timeout` short, as every absent
+    /// device costs that long, and consider `set_purge_on_request` so a late response is not
+    /// read as the answer of the next address.
+    #[cfg(feature = "tokio")]
+    pub async fn scan_slaves(
+        &mut self,
+        device_ids: RangeInclusive<u8>,
+        probe: impl Fn() -> RequestPdu,
+        timeout: Duration,
+    ) -> Vec<u8> {
+        let mut found = Vec::new();
+
+        for device_id in device_ids {
+            let mut device = self.for_device(device_id);
+            match tokio::time::timeout(timeout, device.request(probe())).await {
+                Ok(Ok(_)) | Ok(Err(ModbusError::Exception(_))) => found.push(device_id),
+                Ok(Err(_)) | Err(_) => {}
+            }
+        }
+
+        found
+    }
+
+    /// Send a request the server may accept as a long duration program command
+    ///
+    /// A response is returned as `Completed`. When the server answers with the `Acknowledge`
+    /// exception instead, the command is still being processed: Get Comm Event Counter is
+    /// polled every `poll_interval` until the server no longer reports busy and the event count
+    /// stayed the same since the previous poll, and that last response is returned as
+    /// `Finished`. Gives up with `LongOperationTimeout` once `deadline` has passed since the
+    /// request.
+    #[cfg(feature = "tokio")]
+    pub async fn await_long_operation<Req: Into<Pdu>>(
+        &mut self,
+        request: Req,
+        poll_interval: Duration,
+        deadline: Duration,
+    ) -> Result<LongOperationOutcome> {
+        let deadline = tokio::time::Instant::now() + deadline;
+
+        match self.send_request(&request.into()).await {
+            Err(ModbusError::Exception(ExceptionCode::Acknowledge)) => {}
+            res => return res.map(LongOperationOutcome::Completed),
+        }
+
+        let mut last_event_count = None;
+        loop {
+            if tokio::time::Instant::now() + poll_interval > deadline {
+                return Err(ModbusApplicationError::LongOperationTimeout.into());
+            }
+            tokio::time::sleep(poll_interval).await;
+
+            let response = self.get_comm_event_counter().await?;
+            let event_count = response.event_count();
+            if response.is_busy() == Some(false)
+                && event_count.is_some()
+                && event_count == last_event_count
+            {
+                return Ok(LongOperationOutcome::Finished(response));
+            }
+            last_event_count = event_count;
+        }
     }
 
     pub async fn user_defined(
@@ -106,17 +1037,1246 @@ impl<T: Transport> Client<T> {
         Response::try_from((response, function_code)).map_err(|e| ModbusError::FrameError(e.into()))
     }
 
-    async fn send_request(&mut self, pdu: &Pdu) -> Result<Pdu> {
+    /// Read a single diagnostic counter
+    async fn diagnostic_counter(&mut self, sub_function: DiagnosticSubFunction) -> Result<u16> {
+        self.diagnostics(sub_function, 0x0000)
+            .await?
+            .data()
+            .ok_or(ModbusApplicationError::ResponseMismatch.into())
+    }
+
+    /// Write contiguous registers and check that the address and quantity are echoed
+    async fn write_register_block(&mut self, starting_address: u16, values: &[u16]) -> Result<()> {
+        let write_multiple_registers =
+            WriteMultipleRegistersRequest::new(starting_address, values)?;
+        let response: WriteMultipleRegistersResponse =
+            self.execute(write_multiple_registers.clone()).await?;
+
+        if !write_multiple_registers.verify_echo(&response) {
+            return Err(ModbusApplicationError::ResponseMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    /// Send a Diagnostics request and check that the sub-function is echoed
+    async fn diagnostics(
+        &mut self,
+        sub_function: DiagnosticSubFunction,
+        data: u16,
+    ) -> Result<DiagnosticsResponse> {
+        let diagnostics = DiagnosticsRequest::new(sub_function.into(), data)?;
+        let response: DiagnosticsResponse = self.execute(diagnostics).await?;
+
+        if response.sub_function() != Some(sub_function.into()) {
+            return Err(ModbusApplicationError::ResponseMismatch.into());
+        }
+
+        Ok(response)
+    }
+
+    /// Send a request without waiting for a response
+    ///
+    /// Meant for broadcasts: with the device id set to `0` every slave acts on the request and
+    /// none answers. The RTU transport holds the bus for its broadcast turnaround delay.
+    ///
+    /// Public functions other than writes are refused with `NotBroadcastable`, as their
+    /// response is the point of the request. User defined functions are sent as is.
+    pub async fn broadcast<Req: Into<Pdu>>(&mut self, request: Req) -> Result<()> {
+        let request = request.into();
+        let function_code = request.function_code().unwrap_or_default();
+        if PublicFunctionCode::try_from(function_code).is_ok_and(|code| !code.is_write()) {
+            return Err(ModbusApplicationError::NotBroadcastable(function_code).into());
+        }
+
         self.transport
-            .send(pdu)
+            .send(&request)
             .await
-            .map_err(ModbusTransportError::TransportError)?;
-        let response = self
-            .transport
-            .recv()
+            .map_err(transport_error)?;
+
+        Ok(())
+    }
+
+    /// Write registers on every slave at once, e.g. a synchronized setpoint
+    ///
+    /// The request goes to the broadcast device id whatever the device id set, which is
+    /// restored afterwards.
+    pub async fn write_multiple_registers_broadcast(
+        &mut self,
+        starting_address: u16,
+        registers_value: &[u16],
+    ) -> Result<()> {
+        let request = WriteMultipleRegistersRequest::new(starting_address, registers_value)?;
+        self.broadcast_to_all(request.into_inner()).await
+    }
+
+    /// Write a register on every slave at once, see `write_multiple_registers_broadcast`
+    pub async fn write_single_register_broadcast(
+        &mut self,
+        register_address: u16,
+        register_value: u16,
+    ) -> Result<()> {
+        let request = WriteSingleRegisterRequest::new(register_address, register_value)?;
+        self.broadcast_to_all(request.into_inner()).await
+    }
+
+    /// Write a coil on every slave at once, see `write_multiple_registers_broadcast`
+    pub async fn write_single_coil_broadcast(
+        &mut self,
+        output_address: u16,
+        output_value: bool,
+    ) -> Result<()> {
+        let request = WriteSingleCoilRequest::new(output_address, output_value)?;
+        self.broadcast_to_all(request.into_inner()).await
+    }
+
+    /// Write coils on every slave at once, see `write_multiple_registers_broadcast`
+    pub async fn write_multiple_coils_broadcast(
+        &mut self,
+        starting_address: u16,
+        coils: &[bool],
+    ) -> Result<()> {
+        let request = WriteMultipleCoilsRequest::new(starting_address, coils)?;
+        self.broadcast_to_all(request.into_inner()).await
+    }
+
+    /// Same as `write_multiple_registers_broadcast`
+    pub async fn broadcast_write_multiple_registers(
+        &mut self,
+        starting_address: u16,
+        registers_value: &[u16],
+    ) -> Result<()> {
+        self.write_multiple_registers_broadcast(starting_address, registers_value)
+            .await
+    }
+
+    /// Same as `write_multiple_coils_broadcast`
+    pub async fn broadcast_write_multiple_coils(
+        &mut self,
+        starting_address: u16,
+        coils: &[bool],
+    ) -> Result<()> {
+        self.write_multiple_coils_broadcast(starting_address, coils)
             .await
-            .map_err(ModbusTransportError::TransportError)?;
+    }
+
+    /// Broadcast a request, then address the previous device again
+    async fn broadcast_to_all(&mut self, request: Pdu) -> Result<()> {
+        let device_id = self.transport.device_id();
+        self.transport.set_device_id(BROADCAST_DEVICE_ID);
+
+        let res = self.broadcast(request).await;
+
+        if let Some(device_id) = device_id {
+            self.transport.set_device_id(device_id);
+        }
+        res
+    }
+
+    /// Send any request and decode the response into the expected type
+    ///
+    /// Exception responses are reported as `ModbusError::Exception`, so function types defined
+    /// outside this crate get the same handling as the built-in ones.
+    pub async fn execute<Req, Rsp>(&mut self, request: Req) -> Result<Rsp>
+    where
+        Req: Into<Pdu>,
+        Rsp: TryFrom<Pdu>,
+        Rsp::Error: Into<ModbusFrameError>,
+    {
+        let response = self.send_request(&request.into()).await?;
+
+        Rsp::try_from(response).map_err(|e| ModbusError::FrameError(e.into()))
+    }
+
+    /// Send a request built at runtime and decode the response by its function code
+    ///
+    /// Exception responses are reported as `ModbusError::Exception`, and responses whose data
+    /// contradicts their byte count as `ModbusError::FrameError`, like by the typed helpers.
+    pub async fn request(&mut self, request: RequestPdu) -> Result<ResponsePdu> {
+        let response = self.send_request(&request.into()).await?;
+
+        ResponsePdu::new_validated(response).map_err(|e| ModbusError::FrameError(e.into()))
+    }
+
+    /// Like `execute`, also returning the timing of the transaction
+    ///
+    /// The timing is `None` when the transport does not measure it.
+    pub async fn execute_timed<Req, Rsp>(
+        &mut self,
+        request: Req,
+    ) -> Result<(Rsp, Option<TransactionTiming>)>
+    where
+        Req: Into<Pdu>,
+        Rsp: TryFrom<Pdu>,
+        Rsp::Error: Into<ModbusFrameError>,
+    {
+        let response = self.execute(request).await?;
+
+        Ok((response, self.transport.last_timing()))
+    }
+
+    async fn send_request(&mut self, pdu: &Pdu) -> Result<Pdu> {
+        self.retry_count = 0;
+
+        loop {
+            match self.transact(pdu).await {
+                Err(ModbusError::TransportError(
+                    ModbusTransportError::Timeout | ModbusTransportError::FrameIncomplete,
+                )) if self.retry_count < self.retry.max_retries => {
+                    self.retry_count += 1;
+                    #[cfg(feature = "tokio")]
+                    tokio::time::sleep(self.retry.backoff).await;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    /// Send a request once and receive its response
+    async fn transact(&mut self, pdu: &Pdu) -> Result<Pdu> {
+        if self.purge_on_request {
+            self.transport
+                .clear_input()
+                .await
+                .map_err(transport_error)?;
+        }
+
+        if let Some(within) = self.idle_bus_check {
+            if !self.transport.check_bus_idle(within).await {
+                return Err(ModbusTransportError::BusBusy.into());
+            }
+        }
+
+        self.transport.send(pdu).await.map_err(transport_error)?;
+        let response = self.transport.recv().await.map_err(transport_error)?;
+
+        // e.g. a late response to an earlier request
+        if response.base_function_code() != pdu.function_code() {
+            let function_code = response.function_code().unwrap_or_default();
+            return Err(ModbusError::FrameError(
+                ModbusPduError::UnexpectedCode(function_code).into(),
+            ));
+        }
+
+        if let Some(exception_code) = exception_code(&response) {
+            return Err(ModbusError::Exception(exception_code));
+        }
+
+        if let Some(validator) = &self.response_validator {
+            validator(&response)?;
+        }
 
         Ok(response)
     }
+
+    /// Check the byte count and data of a read response against the expected byte count
+    fn validate_byte_count(&self, response: &Pdu, expected: usize) -> Result<()> {
+        if !self.strict_validation {
+            return Ok(());
+        }
+
+        match response.data().split_first() {
+            Some((&byte_count, values))
+                if byte_count as usize == expected && values.len() == expected =>
+            {
+                Ok(())
+            }
+            _ => Err(ModbusApplicationError::ResponseMismatch.into()),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: Transport + Send + 'static> Client<MultiBusTransport<T>> {
+    /// Select the bus of the following requests, `set_device_id` then picks the slave on it
+    pub fn select_bus(&mut self, bus: u8) -> Result<()> {
+        self.transport.select_bus(bus)?;
+
+        Ok(())
+    }
+}
+
+/// Whether the frame error is a failed CRC or LRC check
+fn is_checksum_failure(err: &ModbusFrameError) -> bool {
+    match err {
+        #[cfg(feature = "rtu")]
+        ModbusFrameError::RtuError(crate::error::ModbusRtuError::CrcValidationFailure) => true,
+        #[cfg(feature = "ascii")]
+        ModbusFrameError::AsciiError(crate::error::ModbusAsciiError::LrcValidationFailure) => true,
+        _ => false,
+    }
+}
+
+/// Extract the exception code if the given PDU is an exception response
+fn exception_code(pdu: &Pdu) -> Option<ExceptionCode> {
+    if pdu.base_function_code()? == pdu.function_code()? {
+        return None;
+    }
+
+    let exception_code = pdu
+        .read_u8(0)
+        .and_then(|code| ExceptionCode::try_from(code).ok())
+        .unwrap_or(ExceptionCode::__Unknown);
+
+    Some(exception_code)
+}
+
+/// Group register addresses into contiguous `(starting_address, quantity)` blocks
+///
+/// Two neighbouring addresses share a block when at most `max_gap` registers lie between them
+/// and the block stays within a single read request.
+fn group_addresses(addresses: &[u16], max_gap: u16) -> Vec<(u16, u16)> {
+    let mut sorted = addresses.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut blocks: Vec<(u16, u16)> = Vec::new();
+    for address in sorted {
+        match blocks.last_mut() {
+            Some((start, quantity))
+                if address - (*start + *quantity) <= max_gap
+                    && address - *start < MAX_READ_REGISTERS =>
+            {
+                *quantity = address - *start + 1;
+            }
+            _ => blocks.push((address, 1)),
+        }
+    }
+
+    blocks
+}
+
+/// Recover a `ModbusTransportError` raised by the transport so callers can match on it
+pub(crate) fn transport_error(err: Box<dyn error::Error + Send + Sync>) -> ModbusTransportError {
+    match err.downcast::<ModbusTransportError>() {
+        Ok(err) => *err,
+        Err(err) => ModbusTransportError::TransportError(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::mock::{echo, fixed_response, MockResponse, MockServer, MockTransport};
+
+    /// Serves holding registers `0..size` and refuses any other address
+    struct RegisterMap {
+        size: u16,
+        requests: usize,
+    }
+
+    impl MockServer for RegisterMap {
+        fn respond(&mut self, _device_id: u8, request: &Pdu) -> MockResponse {
+            let request = ReadHoldingRegistersRequest::try_from(request.clone())?;
+            let start = request.starting_address().unwrap();
+            let quantity = request.quantity_of_registers().unwrap();
+            self.requests += 1;
+
+            if start as u32 + quantity as u32 > self.size as u32 {
+                let mut response = Pdu::new(0x83)?;
+                response.put_u8(ExceptionCode::IllegalDataAddress.into())?;
+                return Ok(Some(response));
+            }
+
+            let mut response = Pdu::new(0x03)?;
+            response.put_u8(quantity as u8 * 2)?;
+            for address in start..start + quantity {
+                response.put_u16(address)?;
+            }
+            Ok(Some(response))
+        }
+    }
+
+    fn register_map_client(size: u16) -> Client<MockTransport<RegisterMap>> {
+        Client::new(MockTransport::new(RegisterMap { size, requests: 0 }))
+    }
+
+    /// Answers every Diagnostics request with its sub-function code as data
+    fn diagnostics(_device_id: u8, request: &Pdu) -> MockResponse {
+        let request = DiagnosticsRequest::try_from(request.clone())?;
+        let sub_function = request.sub_function().unwrap();
+        let data = match sub_function {
+            0x000A | 0x0014 => request.data().unwrap(),
+            _ => sub_function,
+        };
+        Ok(Some(
+            DiagnosticsResponse::new(sub_function, data)?.into_inner(),
+        ))
+    }
+
+    /// Emulates the Listen Only Mode of a server, staying silent while listening only
+    struct ListenOnly {
+        listen_only: bool,
+    }
+
+    impl MockServer for ListenOnly {
+        fn respond(&mut self, _device_id: u8, request: &Pdu) -> MockResponse {
+            let was_listen_only = self.listen_only;
+            match DiagnosticsRequest::try_from(request.clone())?
+                .sub_function()
+                .unwrap()
+            {
+                0x0001 => self.listen_only = false,
+                0x0004 => self.listen_only = true,
+                _ => {}
+            }
+
+            Ok((!was_listen_only && !self.listen_only).then(|| request.clone()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_app_client_listen_only() {
+        let mut client = Client::new(MockTransport::new(ListenOnly { listen_only: false }));
+
+        client.enter_listen_only().await.unwrap();
+        assert!(client.transport.server.listen_only);
+        assert!(client.transport.pending.is_none());
+
+        // Restarted without a response
+        client.exit_listen_only_via_restart(false).await.unwrap();
+        assert!(!client.transport.server.listen_only);
+
+        // Echoed by a server that is already online
+        client.exit_listen_only_via_restart(true).await.unwrap();
+        assert!(client.transport.pending.is_none());
+    }
+
+    /// Serves the identification objects in two transactions
+    fn device_identification(_device_id: u8, request: &Pdu) -> MockResponse {
+        let request = ReadDeviceIdentificationRequest::try_from(request.clone())?;
+        let response = match request.object_id().unwrap() {
+            0x00 => ReadDeviceIdentificationResponse::new(
+                0x03,
+                0x83,
+                true,
+                0x02,
+                &[(0x00, b"Acme"), (0x01, b"AC-100")],
+            )?,
+            _ => ReadDeviceIdentificationResponse::new(
+                0x03,
+                0x83,
+                false,
+                0x00,
+                &[(0x02, b"v1.2"), (0x80, &[0xDE, 0xAD])],
+            )?,
+        };
+        Ok(Some(response.into_inner()))
+    }
+
+    /// Answers a register read with the device id in every register, and counts every
+    /// request as an event
+    struct Addressed {
+        events: u16,
+    }
+
+    impl MockServer for Addressed {
+        fn respond(&mut self, device_id: u8, request: &Pdu) -> MockResponse {
+            self.events = self.events.wrapping_add(1);
+
+            if request.function_code() == Some(PublicFunctionCode::GetCommEventCounter.into()) {
+                let response = GetCommEventCounterResponse::new(0x0000, self.events)?;
+                return Ok(Some(response.into_inner()));
+            }
+
+            let request = ReadHoldingRegistersRequest::try_from(request.clone())?;
+            let quantity = request.quantity_of_registers().unwrap() as usize;
+            let registers = [device_id as u16; 125];
+            let response = ReadHoldingRegistersResponse::from_registers(&registers[..quantity])?;
+            Ok(Some(response.into_inner()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_app_client_for_device() {
+        let mut client = Client::new(MockTransport::new(Addressed { events: 0 }));
+
+        let response = client
+            .for_device(0x11)
+            .read_holding_registers(0x0000, 2)
+            .await
+            .unwrap();
+        assert!(response.register_value().unwrap().eq([0x0011, 0x0011]));
+
+        let mut device = client.for_device(0x22);
+        assert_eq!(
+            device
+                .read_holding_registers(0x0000, 1)
+                .await
+                .unwrap()
+                .register(0),
+            Some(0x0022)
+        );
+        drop(device);
+
+        // The client itself still addresses its own device
+        let response = client.read_holding_registers(0x0000, 1).await.unwrap();
+        assert_eq!(response.register(0), Some(0x0001));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_for_device_comm_event_delta() {
+        let mut client = Client::new(MockTransport::new(Addressed { events: 0 }));
+
+        assert_eq!(client.comm_event_delta().await.unwrap(), None);
+
+        // Each device keeps its own baseline across handles
+        let delta = client.for_device(0x11).comm_event_delta().await;
+        assert_eq!(delta.unwrap(), None);
+        let delta = client.for_device(0x11).comm_event_delta().await;
+        assert_eq!(delta.unwrap(), Some(1));
+
+        // Neither touched the baseline of the client's own device
+        assert_eq!(client.comm_event_delta().await.unwrap(), Some(3));
+    }
+
+    /// Bus of a device answering at `0x02` and one refusing every request at `0x05`
+    #[cfg(feature = "tokio")]
+    fn scanned_bus(device_id: u8, request: &Pdu) -> MockResponse {
+        Ok(match device_id {
+            0x02 => Some(ReadHoldingRegistersResponse::from_registers(&[0x1234])?.into_inner()),
+            0x05 => Some(
+                ExceptionResponse::new(
+                    request.function_code().unwrap(),
+                    ExceptionCode::IllegalDataAddress,
+                )?
+                .into_inner(),
+            ),
+            _ => None,
+        })
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_app_client_scan_slaves() {
+        let mut client = Client::new(MockTransport::new(scanned_bus));
+
+        let probe = || {
+            RequestPdu::ReadHoldingRegisters(ReadHoldingRegistersRequest::new(0x0000, 1).unwrap())
+        };
+        let found = client
+            .scan_slaves(1..=8, probe, Duration::from_millis(5))
+            .await;
+        assert_eq!(found, [0x02, 0x05]);
+        assert_eq!(client.transport.device_id, 0x01);
+    }
+
+    #[tokio::test]
+    async fn test_app_client_read_device_identification() {
+        let mut client = Client::new(MockTransport::new(device_identification));
+
+        let response = client.read_device_identification(0x03, 0x00).await.unwrap();
+        assert_eq!(response.conformity_level(), Some(0x83));
+        assert_eq!(response.more_follows(), Some(true));
+        assert_eq!(response.next_object_id(), Some(0x02));
+        assert!(response
+            .objects()
+            .unwrap()
+            .eq([(0x00, &b"Acme"[..]), (0x01, &b"AC-100"[..])]));
+
+        let response = client.read_device_identification(0x03, 0x02).await.unwrap();
+        assert_eq!(response.more_follows(), Some(false));
+        assert_eq!(response.objects().unwrap().count(), 2);
+
+        assert!(client.read_device_identification(0x05, 0x00).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_app_client_read_device_identification_all() {
+        let mut client = Client::new(MockTransport::new(device_identification));
+
+        let identification = client.read_device_identification_all().await.unwrap();
+        assert_eq!(identification.conformity_level(), 0x83);
+        assert_eq!(identification.vendor_name(), Some("Acme"));
+        assert_eq!(identification.product_code(), Some("AC-100"));
+        assert_eq!(identification.major_minor_revision(), Some("v1.2"));
+        assert_eq!(identification.product_name(), None);
+        assert_eq!(identification.extended(0x80), Some(&[0xDE, 0xAD][..]));
+        assert_eq!(identification.extended(0x00), None);
+        assert_eq!(identification.objects().count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_app_client_read_all_diagnostic_counters() {
+        let mut client = Client::new(MockTransport::new(diagnostics));
+
+        assert_eq!(
+            client.read_all_diagnostic_counters().await.unwrap(),
+            DiagnosticCounters {
+                bus_message: 0x000B,
+                bus_comm_error: 0x000C,
+                bus_exception: 0x000D,
+                slave_message: 0x000E,
+                slave_no_response: 0x000F,
+                slave_nak: 0x0010,
+                slave_busy: 0x0011,
+                bus_char_overrun: 0x0012,
+            }
+        );
+        assert!(client.clear_counters().await.is_ok());
+        assert!(client.clear_overrun_counter().await.is_ok());
+    }
+
+    #[test]
+    fn test_app_client_exception_code() {
+        let mut pdu = Pdu::new(0x83).unwrap();
+        pdu.put_u8(0x02).unwrap();
+        assert!(matches!(
+            exception_code(&pdu),
+            Some(ExceptionCode::IllegalDataAddress)
+        ));
+
+        // Undefined or missing codes are still reported as an exception
+        let mut pdu = Pdu::new(0x83).unwrap();
+        pdu.put_u8(0x55).unwrap();
+        assert!(matches!(
+            exception_code(&pdu),
+            Some(ExceptionCode::__Unknown)
+        ));
+        let pdu = Pdu::new(0x83).unwrap();
+        assert!(matches!(
+            exception_code(&pdu),
+            Some(ExceptionCode::__Unknown)
+        ));
+
+        let pdu = ReadHoldingRegistersResponse::from_registers(&[0x0002])
+            .unwrap()
+            .into_inner();
+        assert!(exception_code(&pdu).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_app_client_read_helper_exception() {
+        let mut client = register_map_client(10);
+
+        assert!(matches!(
+            client.read_holding_registers(0x0008, 3).await,
+            Err(ModbusError::Exception(ExceptionCode::IllegalDataAddress))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_execute() {
+        let mut client = register_map_client(10);
+
+        let request = ReadHoldingRegistersRequest::new(0x0002, 3).unwrap();
+        let response: ReadHoldingRegistersResponse = client.execute(request).await.unwrap();
+        assert!(response.register_value().unwrap().eq([2, 3, 4]));
+
+        let request = ReadHoldingRegistersRequest::new(0x0008, 3).unwrap();
+        assert!(matches!(
+            client
+                .execute::<_, ReadHoldingRegistersResponse>(request)
+                .await,
+            Err(ModbusError::Exception(ExceptionCode::IllegalDataAddress))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_execute_timed_untimed_transport() {
+        let mut client = register_map_client(10);
+
+        let request = ReadHoldingRegistersRequest::new(0x0002, 1).unwrap();
+        let (response, timing): (ReadHoldingRegistersResponse, _) =
+            client.execute_timed(request).await.unwrap();
+        assert_eq!(response.register(0), Some(2));
+        assert!(timing.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_app_client_response_validator() {
+        let mut client = register_map_client(100);
+        client.set_response_validator(|pdu| {
+            // Reject any register value above 50
+            if pdu.data()[1..].chunks(2).any(|value| value[1] > 50) {
+                Err(ModbusApplicationError::ResponseMismatch.into())
+            } else {
+                Ok(())
+            }
+        });
+
+        assert!(client.read_holding_registers(48, 3).await.is_ok());
+        assert!(matches!(
+            client.read_holding_registers(49, 3).await,
+            Err(ModbusError::ApplicationError(
+                ModbusApplicationError::ResponseMismatch
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_strict_validation() {
+        let response = ReadHoldingRegistersResponse::from_registers(&[0x0001, 0x0002, 0x0003])
+            .unwrap()
+            .into_inner();
+        let mut client = Client::new(fixed_response(response));
+
+        // One register too many
+        assert!(client.read_holding_registers(0x0000, 2).await.is_ok());
+        client.set_strict_validation(true);
+        assert!(matches!(
+            client.read_holding_registers(0x0000, 2).await,
+            Err(ModbusError::ApplicationError(
+                ModbusApplicationError::ResponseMismatch
+            ))
+        ));
+        assert!(client.read_holding_registers(0x0000, 3).await.is_ok());
+        assert!(client.read_holding_registers(0x0000, 4).await.is_err());
+
+        // Coils are padded to whole bytes
+        let response = ReadCoilsResponse::from_coils(&[true; 9])
+            .unwrap()
+            .into_inner();
+        let mut client = Client::new(fixed_response(response));
+        client.set_strict_validation(true);
+        assert!(client.read_coils(0x0000, 16).await.is_ok());
+        assert!(client.read_coils(0x0000, 8).await.is_err());
+    }
+
+    /// Loses the response of the first `lost` requests
+    struct Lossy {
+        lost: usize,
+        sent: usize,
+    }
+
+    impl MockServer for Lossy {
+        fn respond(&mut self, _device_id: u8, _request: &Pdu) -> MockResponse {
+            self.sent += 1;
+            if self.sent <= self.lost {
+                return Ok(None);
+            }
+
+            Ok(Some(
+                ReadHoldingRegistersResponse::from_registers(&[0x1234])?.into_inner(),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_app_client_retry() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            backoff: Duration::from_millis(1),
+        };
+
+        let mut client =
+            Client::new(MockTransport::new(Lossy { lost: 2, sent: 0 })).with_retry(policy);
+        let response = client.read_holding_registers(0x0000, 1).await.unwrap();
+        assert_eq!(response.register(0), Some(0x1234));
+        assert_eq!(client.retry_count(), 2);
+
+        client.read_holding_registers(0x0000, 1).await.unwrap();
+        assert_eq!(client.retry_count(), 0);
+
+        let mut client =
+            Client::new(MockTransport::new(Lossy { lost: 3, sent: 0 })).with_retry(policy);
+        assert!(matches!(
+            client.read_holding_registers(0x0000, 1).await,
+            Err(ModbusError::TransportError(ModbusTransportError::Timeout))
+        ));
+        assert_eq!(client.retry_count(), 2);
+
+        // Exceptions are not retried
+        let mut client = register_map_client(1).with_retry(policy);
+        assert!(client.read_holding_registers(0x0001, 1).await.is_err());
+        assert_eq!(client.transport.server.requests, 1);
+        assert_eq!(client.retry_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_app_client_poll_once() {
+        let items = [
+            PollItem::new(0x01, ReadHoldingRegistersRequest::new(0x0002, 2).unwrap()),
+            PollItem::new(0x02, ReadHoldingRegistersRequest::new(0x0010, 1).unwrap()),
+        ];
+
+        let mut client = register_map_client(10);
+        let outcomes = client.poll_once(&items).await;
+        assert_eq!(outcomes.len(), 2);
+        assert!(matches!(
+            &outcomes[0],
+            PollOutcome::Ok(response) if matches!(
+                response.as_ref(),
+                ResponsePdu::ReadHoldingRegisters(response) if response.register(1) == Some(0x0003)
+            )
+        ));
+        assert!(matches!(
+            outcomes[1],
+            PollOutcome::Exception(ExceptionCode::IllegalDataAddress)
+        ));
+
+        let mut client = Client::new(MockTransport::new(Lossy { lost: 1, sent: 0 }));
+        let outcomes = client.poll_once(&items).await;
+        assert!(matches!(outcomes[0], PollOutcome::Timeout));
+        assert!(outcomes[1].is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_app_client_response_function_mismatch() {
+        let response = ReadHoldingRegistersResponse::from_registers(&[0x0001])
+            .unwrap()
+            .into_inner();
+        let mut client = Client::new(fixed_response(response));
+
+        assert!(matches!(
+            client.read_input_registers(0x0000, 1).await,
+            Err(ModbusError::FrameError(ModbusFrameError::PduError(
+                ModbusPduError::UnexpectedCode(0x03)
+            )))
+        ));
+
+        let mut response = Pdu::new(0x83).unwrap();
+        response.put_u8(0x02).unwrap();
+        let mut client = Client::new(fixed_response(response));
+        assert!(matches!(
+            client.read_holding_registers(0x0000, 1).await,
+            Err(ModbusError::Exception(ExceptionCode::IllegalDataAddress))
+        ));
+        assert!(matches!(
+            client.read_input_registers(0x0000, 1).await,
+            Err(ModbusError::FrameError(ModbusFrameError::PduError(
+                ModbusPduError::UnexpectedCode(0x83)
+            )))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_request() {
+        let mut client = register_map_client(10);
+
+        let request = ReadHoldingRegistersRequest::new(0x0004, 2).unwrap();
+        let response = client
+            .request(RequestPdu::ReadHoldingRegisters(request))
+            .await
+            .unwrap();
+        match response {
+            ResponsePdu::ReadHoldingRegisters(response) => {
+                assert!(response.register_value().unwrap().eq([0x0004, 0x0005]));
+            }
+            response => panic!("unexpected response {response:?}"),
+        }
+
+        let request = ReadHoldingRegistersRequest::new(0x0009, 2).unwrap();
+        assert!(matches!(
+            client
+                .request(RequestPdu::ReadHoldingRegisters(request))
+                .await,
+            Err(ModbusError::Exception(ExceptionCode::IllegalDataAddress))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_request_truncated_response() {
+        // Byte count of 0x04 with two bytes of data
+        let mut response = Pdu::new(0x03).unwrap();
+        response.put_slice(&[0x04, 0x12, 0x34]).unwrap();
+        let mut client = Client::new(fixed_response(response));
+
+        let request = ReadHoldingRegistersRequest::new(0x0000, 2).unwrap();
+        assert!(matches!(
+            client
+                .request(RequestPdu::ReadHoldingRegisters(request.clone()))
+                .await,
+            Err(ModbusError::FrameError(_))
+        ));
+
+        let items = [PollItem {
+            device_id: 0x01,
+            request: RequestPdu::ReadHoldingRegisters(request),
+        }];
+        assert!(!client.poll_once(&items).await[0].is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_app_client_read_result() {
+        let mut client = register_map_client(100);
+
+        let result = client.read_holding_registers(0x0010, 4).await.unwrap();
+        assert_eq!(result.range(), 0x0010..=0x0013);
+        assert_eq!(result.address_of(2), 0x0012);
+        assert_eq!(result.register(2), Some(0x0012));
+    }
+
+    /// Acknowledges a user defined program command, then stays busy for `busy_polls` polls and
+    /// counts an event on each of the first `event_polls` polls
+    #[cfg(feature = "tokio")]
+    struct LongOperation {
+        busy_polls: usize,
+        event_polls: usize,
+        polls: usize,
+    }
+
+    #[cfg(feature = "tokio")]
+    impl MockServer for LongOperation {
+        fn respond(&mut self, _device_id: u8, request: &Pdu) -> MockResponse {
+            let response = match request.function_code() {
+                Some(0x0B) => {
+                    self.polls += 1;
+                    let status = if self.polls > self.busy_polls {
+                        0x0000
+                    } else {
+                        0xFFFF
+                    };
+                    let event_count = self.polls.min(self.event_polls) as u16;
+                    GetCommEventCounterResponse::new(status, event_count)?.into_inner()
+                }
+                Some(function_code) => {
+                    ExceptionResponse::new(function_code, ExceptionCode::Acknowledge)?.into_inner()
+                }
+                None => unreachable!(),
+            };
+            Ok(Some(response))
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_app_client_await_long_operation() {
+        // No longer busy after the first poll, but still counting events until the third
+        let mut client = Client::new(MockTransport::new(LongOperation {
+            busy_polls: 1,
+            event_polls: 3,
+            polls: 0,
+        }));
+
+        let request = UserDefinedRequest::new(0x41, &[0x01]).unwrap();
+        let started = tokio::time::Instant::now();
+        let outcome = client
+            .await_long_operation(request, Duration::from_millis(5), Duration::from_secs(1))
+            .await
+            .unwrap();
+        match outcome {
+            LongOperationOutcome::Finished(response) => {
+                assert_eq!(response.event_count(), Some(3));
+            }
+            outcome => panic!("unexpected outcome {outcome:?}"),
+        }
+        assert_eq!(client.transport.server.polls, 4);
+        // Polls are spaced by the interval
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_app_client_await_long_operation_deadline() {
+        let mut client = Client::new(MockTransport::new(LongOperation {
+            busy_polls: usize::MAX,
+            event_polls: 0,
+            polls: 0,
+        }));
+
+        let request = UserDefinedRequest::new(0x41, &[0x01]).unwrap();
+        assert!(matches!(
+            client
+                .await_long_operation(request, Duration::from_millis(5), Duration::from_millis(30))
+                .await,
+            Err(ModbusError::ApplicationError(
+                ModbusApplicationError::LongOperationTimeout
+            ))
+        ));
+        assert!(client.transport.server.polls <= 6);
+    }
+
+    #[tokio::test]
+    async fn test_app_client_comm_event_delta() {
+        // Each request counts one event, wrapping from 0xFFFF to 0x0000
+        let mut client = Client::new(MockTransport::new(Addressed { events: 0xFFFE }));
+
+        assert_eq!(client.comm_event_delta().await.unwrap(), None);
+        assert_eq!(client.comm_event_delta().await.unwrap(), Some(1));
+        assert_eq!(client.comm_event_delta().await.unwrap(), Some(1));
+
+        client.set_device_id(0x02);
+        assert_eq!(client.comm_event_delta().await.unwrap(), None);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_app_client_await_long_operation_immediate() {
+        let mut client = register_map_client(10);
+
+        let request = ReadHoldingRegistersRequest::new(0x0000, 2).unwrap();
+        let outcome = client
+            .await_long_operation(request, Duration::from_millis(5), Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(matches!(
+            outcome,
+            LongOperationOutcome::Completed(response)
+                if response.data() == [0x04, 0x00, 0x00, 0x00, 0x01]
+        ));
+    }
+
+    /// Holds 16 registers, refusing any single or multiple write to `read_only`
+    struct HoldingRegisters {
+        registers: [u16; 16],
+        read_only: u16,
+        function_codes: Vec<u8>,
+    }
+
+    impl MockServer for HoldingRegisters {
+        fn respond(&mut self, _device_id: u8, pdu: &Pdu) -> MockResponse {
+            let function_code = pdu.function_code().unwrap();
+            if function_code == 0x17 {
+                let request = ReadWriteMultipleRegistersRequest::try_from(pdu.clone())?;
+                let write_start = request.write_starting_address().unwrap() as usize;
+                for (offset, value) in request.write_registers_value().unwrap().enumerate() {
+                    self.registers[write_start + offset] = value;
+                }
+
+                let read_start = request.read_starting_address().unwrap() as usize;
+                let read_end = read_start + request.quantity_to_read().unwrap() as usize;
+                let response = ReadWriteMultipleRegistersResponse::from_registers(
+                    &self.registers[read_start..read_end],
+                )?;
+                return Ok(Some(response.into_inner()));
+            }
+
+            let start = pdu.read_u16(0).unwrap();
+            let values: Vec<u16> = match function_code {
+                0x06 => Vec::from([pdu.read_u16(2).unwrap()]),
+                _ => pdu
+                    .get_u16_be_vec(6, pdu.read_u16(2).unwrap() as usize)
+                    .unwrap(),
+            };
+            self.function_codes.push(function_code);
+
+            let addresses = start..start + values.len() as u16;
+            Ok(Some(if addresses.contains(&self.read_only) {
+                let mut response = Pdu::new(function_code | 0x80)?;
+                response.put_u8(ExceptionCode::IllegalDataAddress.into())?;
+                response
+            } else {
+                for (address, value) in addresses.zip(values) {
+                    self.registers[address as usize] = value;
+                }
+                let mut response = Pdu::new(function_code)?;
+                response.put_slice(&pdu.data()[..4])?;
+                response
+            }))
+        }
+    }
+
+    fn holding_registers_client(read_only: u16) -> Client<MockTransport<HoldingRegisters>> {
+        Client::new(MockTransport::new(HoldingRegisters {
+            registers: [0; 16],
+            read_only,
+            function_codes: Vec::new(),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_app_client_read_write_multiple_registers() {
+        let mut client = holding_registers_client(15);
+
+        let response = client
+            .read_write_multiple_registers(0x0001, 3, 0x0002, &[0x1234])
+            .await
+            .unwrap();
+        assert!(response
+            .register_value()
+            .unwrap()
+            .eq([0x0000, 0x1234, 0x0000]));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_write_registers() {
+        let mut client = holding_registers_client(9);
+
+        let outcomes = client
+            .write_registers(&[(3, 0x33), (1, 0x11), (2, 0x22), (5, 0x55)])
+            .await
+            .unwrap();
+        assert!(outcomes.iter().all(|outcome| outcome.is_ok()));
+        assert_eq!(client.transport.server.function_codes, [0x10, 0x06]);
+        assert_eq!(
+            client.transport.server.registers[..6],
+            [0x00, 0x11, 0x22, 0x33, 0x00, 0x55]
+        );
+
+        // The run 8..=10 is rejected and retried register by register
+        client.transport.server.function_codes.clear();
+        let outcomes = client
+            .write_registers(&[(10, 0xAA), (9, 0x99), (8, 0x88)])
+            .await
+            .unwrap();
+        assert_eq!(
+            outcomes,
+            [Ok(()), Err(ExceptionCode::IllegalDataAddress), Ok(())]
+        );
+        assert_eq!(
+            client.transport.server.function_codes,
+            [0x10, 0x06, 0x06, 0x06]
+        );
+        assert_eq!(client.transport.server.registers[8..11], [0x88, 0x00, 0xAA]);
+    }
+
+    #[tokio::test]
+    async fn test_app_client_write_multiple_registers() {
+        let mut client = holding_registers_client(9);
+
+        let response = client
+            .write_multiple_registers(0x0002, &[0x1234, 0x5678])
+            .await
+            .unwrap();
+        assert_eq!(response.starting_address(), Some(0x0002));
+        assert_eq!(response.quantity_of_registers(), Some(2));
+        assert_eq!(client.transport.server.registers[2..4], [0x1234, 0x5678]);
+
+        assert!(matches!(
+            client.write_multiple_registers(0x0008, &[0, 0]).await,
+            Err(ModbusError::Exception(ExceptionCode::IllegalDataAddress))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_diagnostic_loopback() {
+        let mut client = Client::new(echo());
+        client.diagnostic_loopback(0xA537).await.unwrap();
+
+        // Answers with the sub-function code instead of the data
+        let mut client = Client::new(MockTransport::new(diagnostics));
+        assert!(matches!(
+            client.diagnostic_loopback(0xA537).await,
+            Err(ModbusError::ApplicationError(
+                ModbusApplicationError::ResponseMismatch
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_mask_write_register() {
+        let mut client = Client::new(echo());
+
+        let response = client
+            .mask_write_register(0x0004, 0x00F2, 0x0025)
+            .await
+            .unwrap();
+        assert_eq!(response.and_mask(), Some(0x00F2));
+        assert_eq!(response.or_mask(), Some(0x0025));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_write_file_record() {
+        let records = [FileRecord::new(0x0004, 0x0007, &[0x06AF, 0x04BE, 0x100D])];
+
+        let mut client = Client::new(echo());
+        let response = client.write_file_record(&records).await.unwrap();
+        assert_eq!(response.response_data_length(), Some(0x0D));
+
+        // Echoes another record number
+        let response = WriteFileRecordResponse::new(&[FileRecord::new(
+            0x0004,
+            0x0008,
+            &[0x06AF, 0x04BE, 0x100D],
+        )])
+        .unwrap()
+        .into_inner();
+        let mut client = Client::new(fixed_response(response));
+        assert!(matches!(
+            client.write_file_record(&records).await,
+            Err(ModbusError::ApplicationError(
+                ModbusApplicationError::ResponseMismatch
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_write_f32() {
+        let mut client = holding_registers_client(15);
+
+        // 1.5f32 is 0x3FC00000
+        client
+            .write_f32(0x0008, 1.5, WordOrder::BigEndian)
+            .await
+            .unwrap();
+        assert_eq!(client.transport.server.registers[8..10], [0x3FC0, 0x0000]);
+
+        client
+            .write_f32(0x0008, 1.5, WordOrder::LittleEndian)
+            .await
+            .unwrap();
+        assert_eq!(client.transport.server.registers[8..10], [0x0000, 0x3FC0]);
+
+        client
+            .write_i32(0x0008, -2, WordOrder::BigEndian)
+            .await
+            .unwrap();
+        assert_eq!(client.transport.server.registers[8..10], [0xFFFF, 0xFFFE]);
+    }
+
+    #[tokio::test]
+    async fn test_app_client_write_u32_quantity_mismatch() {
+        // Acknowledges one register of the two written
+        let response = WriteMultipleRegistersResponse::new(0x0010, 1)
+            .unwrap()
+            .into_inner();
+        let mut client = Client::new(fixed_response(response));
+
+        assert!(matches!(
+            client.write_u32(0x0010, 1, WordOrder::BigEndian).await,
+            Err(ModbusError::ApplicationError(
+                ModbusApplicationError::ResponseMismatch
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_app_client_probe_register_range() {
+        let mut client = register_map_client(23);
+        assert_eq!(client.probe_register_range(0, 10, 1000).await.unwrap(), 22);
+        // 0..20 in two blocks, refused 20..30, then 20, 21, 22 and refused 23
+        assert_eq!(client.transport.server.requests, 7);
+    }
+
+    #[tokio::test]
+    async fn test_app_client_probe_register_range_max() {
+        let mut client = register_map_client(100);
+        assert_eq!(client.probe_register_range(0, 30, 44).await.unwrap(), 44);
+
+        let mut client = register_map_client(u16::MAX);
+        assert_eq!(
+            client
+                .probe_register_range(0xFF00, 125, u16::MAX)
+                .await
+                .unwrap(),
+            0xFFFE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_app_client_probe_register_range_nothing_readable() {
+        let mut client = register_map_client(10);
+        assert!(matches!(
+            client.probe_register_range(10, 5, 100).await,
+            Err(ModbusError::Exception(ExceptionCode::IllegalDataAddress))
+        ));
+    }
+
+    #[test]
+    fn test_app_client_group_addresses() {
+        let blocks = group_addresses(&[0x2F, 0x00, 0x10, 0x02, 0x11], 8);
+        assert_eq!(blocks, [(0x00, 3), (0x10, 2), (0x2F, 1)]);
+    }
+
+    #[test]
+    fn test_app_client_group_addresses_bridge_gap() {
+        let blocks = group_addresses(&[0x00, 0x10, 0x2F], 30);
+        assert_eq!(blocks, [(0x00, 0x30)]);
+
+        let blocks = group_addresses(&[0x00, 0x01, 0x03], 0);
+        assert_eq!(blocks, [(0x00, 2), (0x03, 1)]);
+    }
+
+    #[test]
+    fn test_app_client_group_addresses_split_max_quantity() {
+        let blocks = group_addresses(&[0, 124, 125, 0xFFFF], u16::MAX);
+        assert_eq!(blocks, [(0, 125), (125, 1), (0xFFFF, 1)]);
+    }
+
+    #[test]
+    fn test_app_client_group_addresses_duplicates() {
+        let blocks = group_addresses(&[5, 5, 5], 8);
+        assert_eq!(blocks, [(5, 1)]);
+    }
 }
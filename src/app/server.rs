@@ -0,0 +1,462 @@
+use crate::error::ModbusFrameError;
+use crate::frame::pdu::fcode::ExceptionCode;
+use crate::frame::pdu::function::request::RequestPdu;
+use crate::frame::pdu::function::response::*;
+use crate::frame::pdu::Pdu;
+use crate::lib::*;
+use crate::transport::Transport;
+use crate::Result;
+
+use super::client::transport_error;
+
+/// Data model of a server, called once a request has been decoded and range checked
+///
+/// Every method refuses its function with `IllegalFunction` unless implemented. Addresses
+/// outside the served data are refused with `IllegalDataAddress`.
+pub trait RequestHandler {
+    fn read_coils(
+        &mut self,
+        _address: u16,
+        _quantity: u16,
+    ) -> result::Result<Vec<bool>, ExceptionCode> {
+        Err(ExceptionCode::IllegalFunction)
+    }
+
+    fn read_discrete_inputs(
+        &mut self,
+        _address: u16,
+        _quantity: u16,
+    ) -> result::Result<Vec<bool>, ExceptionCode> {
+        Err(ExceptionCode::IllegalFunction)
+    }
+
+    fn read_holding_registers(
+        &mut self,
+        _address: u16,
+        _quantity: u16,
+    ) -> result::Result<Vec<u16>, ExceptionCode> {
+        Err(ExceptionCode::IllegalFunction)
+    }
+
+    fn read_input_registers(
+        &mut self,
+        _address: u16,
+        _quantity: u16,
+    ) -> result::Result<Vec<u16>, ExceptionCode> {
+        Err(ExceptionCode::IllegalFunction)
+    }
+
+    fn write_single_coil(
+        &mut self,
+        _address: u16,
+        _value: bool,
+    ) -> result::Result<(), ExceptionCode> {
+        Err(ExceptionCode::IllegalFunction)
+    }
+
+    fn write_single_register(
+        &mut self,
+        _address: u16,
+        _value: u16,
+    ) -> result::Result<(), ExceptionCode> {
+        Err(ExceptionCode::IllegalFunction)
+    }
+
+    fn write_multiple_coils(
+        &mut self,
+        _address: u16,
+        _values: &[bool],
+    ) -> result::Result<(), ExceptionCode> {
+        Err(ExceptionCode::IllegalFunction)
+    }
+
+    fn write_multiple_registers(
+        &mut self,
+        _address: u16,
+        _values: &[u16],
+    ) -> result::Result<(), ExceptionCode> {
+        Err(ExceptionCode::IllegalFunction)
+    }
+}
+
+/// Modbus server handler
+///
+/// Decodes requests, checks their quantities and addresses, and answers with the response
+/// built from the `RequestHandler` or with an exception response.
+pub struct Server<H: RequestHandler> {
+    handler: H,
+}
+
+impl<H: RequestHandler> Server<H> {
+    pub fn new(handler: H) -> Self {
+        Self { handler }
+    }
+
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    pub fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    pub fn into_inner(self) -> H {
+        self.handler
+    }
+
+    /// Receive one request from the transport and send the answer back
+    pub async fn serve_one<T: Transport>(&mut self, transport: &mut T) -> Result<()> {
+        let request = transport.recv().await.map_err(transport_error)?;
+        let response = self.process(request)?;
+        transport.send(&response).await.map_err(transport_error)?;
+
+        Ok(())
+    }
+
+    /// Answer a request PDU
+    pub fn process(&mut self, request: Pdu) -> result::Result<Pdu, ModbusFrameError> {
+        let function_code = request.function_code().unwrap_or(0);
+
+        match self.dispatch(request) {
+            Ok(response) => Ok(response),
+            Err(exception_code) => ExceptionResponse::new(function_code, exception_code)
+                .map(ExceptionResponse::into_inner),
+        }
+    }
+
+    fn dispatch(&mut self, request: Pdu) -> result::Result<Pdu, ExceptionCode> {
+        let response = match RequestPdu::from(request) {
+            RequestPdu::ReadCoils(request) => {
+                let (address, quantity) = read_range(
+                    request.starting_address(),
+                    request.quantity_of_coils(),
+                    2000,
+                )?;
+                let coils = self.handler.read_coils(address, quantity)?;
+                check_len(&coils, quantity)?;
+                ReadCoilsResponse::from_coils(&coils).map(ReadCoilsResponse::into_inner)
+            }
+            RequestPdu::ReadDiscreteInputs(request) => {
+                let (address, quantity) = read_range(
+                    request.starting_address(),
+                    request.quantity_of_inputs(),
+                    2000,
+                )?;
+                let inputs = self.handler.read_discrete_inputs(address, quantity)?;
+                check_len(&inputs, quantity)?;
+                ReadDiscreteInputsResponse::from_inputs(&inputs)
+                    .map(ReadDiscreteInputsResponse::into_inner)
+            }
+            RequestPdu::ReadHoldingRegisters(request) => {
+                let (address, quantity) = read_range(
+                    request.starting_address(),
+                    request.quantity_of_registers(),
+                    125,
+                )?;
+                let registers = self.handler.read_holding_registers(address, quantity)?;
+                check_len(&registers, quantity)?;
+                ReadHoldingRegistersResponse::from_registers(&registers)
+                    .map(ReadHoldingRegistersResponse::into_inner)
+            }
+            RequestPdu::ReadInputRegisters(request) => {
+                let (address, quantity) = read_range(
+                    request.starting_address(),
+                    request.quantity_of_input_registers(),
+                    125,
+                )?;
+                let registers = self.handler.read_input_registers(address, quantity)?;
+                check_len(&registers, quantity)?;
+                ReadInputRegistersResponse::from_registers(&registers)
+                    .map(ReadInputRegistersResponse::into_inner)
+            }
+            RequestPdu::WriteSingleCoil(request) => {
+                let address = request
+                    .output_address()
+                    .ok_or(ExceptionCode::IllegalDataValue)?;
+                // Any value other than ON and OFF is refused instead of being taken for OFF
+                let value = match request.as_pdu().data().get(2..4) {
+                    Some([0xFF, 0x00]) => true,
+                    Some([0x00, 0x00]) => false,
+                    _ => return Err(ExceptionCode::IllegalDataValue),
+                };
+                self.handler.write_single_coil(address, value)?;
+                WriteSingleCoilResponse::new(address, value)
+                    .map(WriteSingleCoilResponse::into_inner)
+            }
+            RequestPdu::WriteSingleRegister(request) => {
+                let (Some(address), Some(value)) =
+                    (request.register_address(), request.register_value())
+                else {
+                    return Err(ExceptionCode::IllegalDataValue);
+                };
+                self.handler.write_single_register(address, value)?;
+                WriteSingleRegisterResponse::new(address, value)
+                    .map(WriteSingleRegisterResponse::into_inner)
+            }
+            RequestPdu::WriteMultipleCoils(request) => {
+                let (address, quantity) = read_range(
+                    request.starting_address(),
+                    request.quantity_of_outputs(),
+                    1968,
+                )?;
+                let byte_count = quantity.div_ceil(8);
+                if request.byte_count().map(u16::from) != Some(byte_count)
+                    || request.as_pdu().data().len() != 5 + byte_count as usize
+                {
+                    return Err(ExceptionCode::IllegalDataValue);
+                }
+                let coils: Vec<bool> = request
+                    .outputs_value()
+                    .ok_or(ExceptionCode::IllegalDataValue)?
+                    .take(quantity as usize)
+                    .collect();
+                self.handler.write_multiple_coils(address, &coils)?;
+                WriteMultipleCoilsResponse::new(address, quantity)
+                    .map(WriteMultipleCoilsResponse::into_inner)
+            }
+            RequestPdu::WriteMultipleRegisters(request) => {
+                let (address, quantity) = read_range(
+                    request.starting_address(),
+                    request.quantity_of_registers(),
+                    123,
+                )?;
+                if request.byte_count().map(u16::from) != Some(quantity * 2)
+                    || request.as_pdu().data().len() != 5 + quantity as usize * 2
+                {
+                    return Err(ExceptionCode::IllegalDataValue);
+                }
+                let registers: Vec<u16> = request
+                    .registers_value()
+                    .ok_or(ExceptionCode::IllegalDataValue)?
+                    .collect();
+                self.handler.write_multiple_registers(address, &registers)?;
+                WriteMultipleRegistersResponse::new(address, quantity)
+                    .map(WriteMultipleRegistersResponse::into_inner)
+            }
+            _ => return Err(ExceptionCode::IllegalFunction),
+        };
+
+        response.map_err(|_| ExceptionCode::ServerDeviceFailure)
+    }
+}
+
+/// Check the quantity of a request against `1..=max` and its range against the address space
+fn read_range(
+    address: Option<u16>,
+    quantity: Option<u16>,
+    max: u16,
+) -> result::Result<(u16, u16), ExceptionCode> {
+    let (Some(address), Some(quantity)) = (address, quantity) else {
+        return Err(ExceptionCode::IllegalDataValue);
+    };
+
+    if !(1..=max).contains(&quantity) {
+        return Err(ExceptionCode::IllegalDataValue);
+    }
+    if address as u32 + quantity as u32 > 0x1_0000 {
+        return Err(ExceptionCode::IllegalDataAddress);
+    }
+
+    Ok((address, quantity))
+}
+
+/// Check that a handler returned as many values as requested
+fn check_len<V>(values: &[V], quantity: u16) -> result::Result<(), ExceptionCode> {
+    if values.len() == quantity as usize {
+        Ok(())
+    } else {
+        Err(ExceptionCode::ServerDeviceFailure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{ModbusError, ModbusTransportError};
+    use crate::frame::pdu::function::request::*;
+
+    use super::*;
+
+    /// Serves coils and holding registers `0..size`
+    struct Memory {
+        coils: Vec<bool>,
+        registers: Vec<u16>,
+    }
+
+    impl Memory {
+        fn new(size: u16) -> Self {
+            Self {
+                coils: (0..size).map(|address| address % 3 == 0).collect(),
+                registers: (0..size).collect(),
+            }
+        }
+
+        fn range(
+            &self,
+            address: u16,
+            quantity: u16,
+        ) -> result::Result<core::ops::Range<usize>, ExceptionCode> {
+            let range = address as usize..address as usize + quantity as usize;
+            if range.end <= self.registers.len() {
+                Ok(range)
+            } else {
+                Err(ExceptionCode::IllegalDataAddress)
+            }
+        }
+    }
+
+    impl RequestHandler for Memory {
+        fn read_coils(
+            &mut self,
+            address: u16,
+            quantity: u16,
+        ) -> result::Result<Vec<bool>, ExceptionCode> {
+            Ok(self.coils[self.range(address, quantity)?].to_vec())
+        }
+
+        fn read_holding_registers(
+            &mut self,
+            address: u16,
+            quantity: u16,
+        ) -> result::Result<Vec<u16>, ExceptionCode> {
+            Ok(self.registers[self.range(address, quantity)?].to_vec())
+        }
+
+        fn write_single_coil(
+            &mut self,
+            address: u16,
+            value: bool,
+        ) -> result::Result<(), ExceptionCode> {
+            let range = self.range(address, 1)?;
+            self.coils[range.start] = value;
+            Ok(())
+        }
+
+        fn write_multiple_registers(
+            &mut self,
+            address: u16,
+            values: &[u16],
+        ) -> result::Result<(), ExceptionCode> {
+            let range = self.range(address, values.len() as u16)?;
+            self.registers[range].copy_from_slice(values);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_app_server_process() {
+        let mut server = Server::new(Memory::new(10));
+
+        let request = ReadHoldingRegistersRequest::new(0x0002, 3).unwrap();
+        let response = server.process(request.into_inner()).unwrap();
+        let response = ReadHoldingRegistersResponse::try_from(response).unwrap();
+        assert!(response.register_value().unwrap().eq([2, 3, 4]));
+
+        let request = ReadCoilsRequest::new(0x0000, 4).unwrap();
+        let response = server.process(request.into_inner()).unwrap();
+        let response = ReadCoilsResponse::try_from(response).unwrap();
+        assert!(response.coils(4).unwrap().eq([true, false, false, true]));
+
+        let request = WriteMultipleRegistersRequest::new(0x0008, &[0xAAAA, 0xBBBB]).unwrap();
+        let response = server.process(request.into_inner()).unwrap();
+        assert!(WriteMultipleRegistersResponse::try_from(response).is_ok());
+        assert_eq!(server.handler().registers[8..], [0xAAAA, 0xBBBB]);
+
+        let request = WriteSingleCoilRequest::new(0x0001, true).unwrap();
+        let response = server.process(request.into_inner()).unwrap();
+        let response = WriteSingleCoilResponse::try_from(response).unwrap();
+        assert_eq!(response.output_value(), Some(true));
+        assert!(server.handler().coils[1]);
+    }
+
+    #[test]
+    fn test_app_server_process_exception() {
+        let mut server = Server::new(Memory::new(10));
+        let exception = |response: Pdu| {
+            let response = ExceptionResponse::try_from(response).unwrap();
+            (response.function_code(), response.exception_code())
+        };
+
+        let request = ReadHoldingRegistersRequest::new(0x0008, 3).unwrap();
+        assert_eq!(
+            exception(server.process(request.into_inner()).unwrap()),
+            (Some(0x03), Some(ExceptionCode::IllegalDataAddress))
+        );
+
+        // Not implemented by the handler
+        let request = ReadInputRegistersRequest::new(0x0000, 1).unwrap();
+        assert_eq!(
+            exception(server.process(request.into_inner()).unwrap()),
+            (Some(0x04), Some(ExceptionCode::IllegalFunction))
+        );
+
+        // Unknown to the server
+        let request = GetCommEventCounterRequest::new().unwrap();
+        assert_eq!(
+            exception(server.process(request.into_inner()).unwrap()),
+            (Some(0x0B), Some(ExceptionCode::IllegalFunction))
+        );
+
+        let mut request = Pdu::new(0x03).unwrap();
+        request.put_slice(&[0x00, 0x00, 0x00, 0x7E]).unwrap();
+        assert_eq!(
+            exception(server.process(request).unwrap()),
+            (Some(0x03), Some(ExceptionCode::IllegalDataValue))
+        );
+
+        let mut request = Pdu::new(0x05).unwrap();
+        request.put_slice(&[0x00, 0x01, 0x12, 0x34]).unwrap();
+        assert_eq!(
+            exception(server.process(request).unwrap()),
+            (Some(0x05), Some(ExceptionCode::IllegalDataValue))
+        );
+    }
+
+    /// Delivers one request and keeps the response
+    struct OneShotTransport {
+        request: Option<Pdu>,
+        response: Option<Pdu>,
+    }
+
+    impl Transport for OneShotTransport {
+        async fn send(
+            &mut self,
+            pdu: &Pdu,
+        ) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+            self.response = Some(pdu.clone());
+            Ok(())
+        }
+
+        async fn recv(&mut self) -> result::Result<Pdu, Box<dyn error::Error + Send + Sync>> {
+            self.request
+                .take()
+                .ok_or_else(|| ModbusTransportError::Timeout.into())
+        }
+
+        async fn flush(&mut self) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_app_server_serve_one() {
+        let mut server = Server::new(Memory::new(10));
+        let mut transport = OneShotTransport {
+            request: Some(
+                ReadHoldingRegistersRequest::new(0x0000, 2)
+                    .unwrap()
+                    .into_inner(),
+            ),
+            response: None,
+        };
+
+        server.serve_one(&mut transport).await.unwrap();
+        let response =
+            ReadHoldingRegistersResponse::try_from(transport.response.take().unwrap()).unwrap();
+        assert!(response.register_value().unwrap().eq([0, 1]));
+
+        assert!(matches!(
+            server.serve_one(&mut transport).await,
+            Err(ModbusError::TransportError(ModbusTransportError::Timeout))
+        ));
+    }
+}
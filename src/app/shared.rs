@@ -0,0 +1,163 @@
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::app::client::{Client, ReadResult};
+use crate::frame::pdu::function::response::*;
+use crate::transport::Transport;
+use crate::Result;
+
+/// Modbus client handle shared between tasks
+///
+/// RTU is half-duplex, so a transport must not be driven by several tasks at once. Each method
+/// locks the underlying `Client` for a complete request/response transaction, and concurrent
+/// callers wait their turn. Use `lock` to run several transactions back to back without
+/// another task getting in between.
+///
+/// The returned futures are `Send`, and can be spawned on a multi-threaded runtime, when
+/// those of the transport are.
+pub struct SharedClient<T: Transport + Send> {
+    inner: Arc<Mutex<Client<T>>>,
+}
+
+impl<T: Transport + Send> Clone for SharedClient<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: Transport + Send> From<Client<T>> for SharedClient<T> {
+    fn from(client: Client<T>) -> Self {
+        Self::new(client)
+    }
+}
+
+impl<T: Transport + Send> SharedClient<T> {
+    pub fn new(client: Client<T>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(client)),
+        }
+    }
+
+    /// Wait for exclusive access to the underlying client
+    pub async fn lock(&self) -> MutexGuard<'_, Client<T>> {
+        self.inner.lock().await
+    }
+
+    pub async fn read_coils(
+        &self,
+        starting_address: u16,
+        quantity_of_coils: u16,
+    ) -> Result<ReadResult<ReadCoilsResponse>> {
+        self.lock()
+            .await
+            .read_coils(starting_address, quantity_of_coils)
+            .await
+    }
+
+    pub async fn read_discrete_inputs(
+        &self,
+        starting_address: u16,
+        quantity_of_inputs: u16,
+    ) -> Result<ReadResult<ReadDiscreteInputsResponse>> {
+        self.lock()
+            .await
+            .read_discrete_inputs(starting_address, quantity_of_inputs)
+            .await
+    }
+
+    pub async fn read_holding_registers(
+        &self,
+        starting_address: u16,
+        quantity_of_registers: u16,
+    ) -> Result<ReadResult<ReadHoldingRegistersResponse>> {
+        self.lock()
+            .await
+            .read_holding_registers(starting_address, quantity_of_registers)
+            .await
+    }
+
+    pub async fn read_input_registers(
+        &self,
+        starting_address: u16,
+        quantity_of_registers: u16,
+    ) -> Result<ReadResult<ReadInputRegistersResponse>> {
+        self.lock()
+            .await
+            .read_input_registers(starting_address, quantity_of_registers)
+            .await
+    }
+
+    pub async fn write_single_coil(
+        &self,
+        output_address: u16,
+        output_value: bool,
+    ) -> Result<WriteSingleCoilResponse> {
+        self.lock()
+            .await
+            .write_single_coil(output_address, output_value)
+            .await
+    }
+
+    pub async fn write_single_register(
+        &self,
+        register_address: u16,
+        register_value: u16,
+    ) -> Result<WriteSingleRegisterResponse> {
+        self.lock()
+            .await
+            .write_single_register(register_address, register_value)
+            .await
+    }
+
+    pub async fn write_multiple_registers(
+        &self,
+        starting_address: u16,
+        registers_value: &[u16],
+    ) -> Result<WriteMultipleRegistersResponse> {
+        self.lock()
+            .await
+            .write_multiple_registers(starting_address, registers_value)
+            .await
+    }
+
+    pub async fn user_defined(
+        &self,
+        function_code: u8,
+        data: &[u8],
+    ) -> Result<UserDefinedResponse> {
+        self.lock().await.user_defined(function_code, data).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::*;
+    use crate::transport::mock::echo;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_app_shared_client_serializes_transactions() {
+        let client = SharedClient::new(Client::new(echo()));
+
+        let tasks: Vec<_> = (0..16u16)
+            .map(|value| {
+                let client = client.clone();
+                tokio::spawn(async move {
+                    let response = client.write_single_register(value, value).await.unwrap();
+                    assert_eq!(response.register_address(), Some(value));
+                    assert_eq!(response.register_value(), Some(value));
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Transport independent encode/decode facade
+//!
+//! For users bringing their own IO: each `encode_*` function builds the request `Pdu` of a
+//! function code and each `decode_*` function turns the received `Pdu` into plain values.
+
+use crate::error::{ModbusError, ModbusFrameError, ModbusPduError, ResponseError};
+use crate::frame::pdu::function::request::*;
+use crate::frame::pdu::function::response::*;
+use crate::frame::pdu::function::{PublicFunction, Response};
+use crate::frame::pdu::Pdu;
+use crate::lib::*;
+use crate::Result;
+
+pub fn encode_read_coils(starting_address: u16, quantity_of_coils: u16) -> Result<Pdu> {
+    Ok(ReadCoilsRequest::new(starting_address, quantity_of_coils)?.into_inner())
+}
+
+pub fn encode_read_discrete_inputs(starting_address: u16, quantity_of_inputs: u16) -> Result<Pdu> {
+    Ok(ReadDiscreteInputsRequest::new(starting_address, quantity_of_inputs)?.into_inner())
+}
+
+pub fn encode_read_holding_registers(
+    starting_address: u16,
+    quantity_of_registers: u16,
+) -> Result<Pdu> {
+    Ok(ReadHoldingRegistersRequest::new(starting_address, quantity_of_registers)?.into_inner())
+}
+
+pub fn encode_read_input_registers(
+    starting_address: u16,
+    quantity_of_registers: u16,
+) -> Result<Pdu> {
+    Ok(ReadInputRegistersRequest::new(starting_address, quantity_of_registers)?.into_inner())
+}
+
+pub fn encode_write_single_coil(output_address: u16, output_value: bool) -> Result<Pdu> {
+    Ok(WriteSingleCoilRequest::new(output_address, output_value)?.into_inner())
+}
+
+pub fn encode_write_single_register(register_address: u16, register_value: u16) -> Result<Pdu> {
+    Ok(WriteSingleRegisterRequest::new(register_address, register_value)?.into_inner())
+}
+
+/// Decode the first `quantity_of_coils` coils of a Read Coils response
+pub fn decode_read_coils_response(pdu: Pdu, quantity_of_coils: u16) -> Result<Vec<bool>> {
+    let response: ReadCoilsResponse = decode(pdu)?;
+    take_bits(response.coil_status(), quantity_of_coils)
+}
+
+/// Decode the first `quantity_of_inputs` inputs of a Read Discrete Inputs response
+pub fn decode_read_discrete_inputs_response(
+    pdu: Pdu,
+    quantity_of_inputs: u16,
+) -> Result<Vec<bool>> {
+    let response: ReadDiscreteInputsResponse = decode(pdu)?;
+    take_bits(response.input_status(), quantity_of_inputs)
+}
+
+pub fn decode_read_holding_registers_response(pdu: Pdu) -> Result<Vec<u16>> {
+    let response: ReadHoldingRegistersResponse = decode(pdu)?;
+    Ok(response.register_value().ok_or(out_of_range())?.collect())
+}
+
+pub fn decode_read_input_registers_response(pdu: Pdu) -> Result<Vec<u16>> {
+    let response: ReadInputRegistersResponse = decode(pdu)?;
+    Ok(response.input_registers().ok_or(out_of_range())?.collect())
+}
+
+/// Decode a Write Single Coil response into `(output_address, output_value)`
+pub fn decode_write_single_coil_response(pdu: Pdu) -> Result<(u16, bool)> {
+    let response: WriteSingleCoilResponse = decode(pdu)?;
+
+    response
+        .output_address()
+        .zip(response.output_value())
+        .ok_or(out_of_range())
+}
+
+/// Decode a Write Single Register response into `(register_address, register_value)`
+pub fn decode_write_single_register_response(pdu: Pdu) -> Result<(u16, u16)> {
+    let response: WriteSingleRegisterResponse = decode(pdu)?;
+
+    response
+        .register_address()
+        .zip(response.register_value())
+        .ok_or(out_of_range())
+}
+
+/// Decode a response of function `T`, reporting an exception response by its code
+fn decode<T: PublicFunction>(pdu: Pdu) -> Result<Response<T>> {
+    if let Err(ResponseError::Exception(exception_code)) = Response::<T>::parse(pdu.clone()) {
+        return Err(ModbusError::Exception(exception_code));
+    }
+
+    Response::try_from(pdu).map_err(|err| ModbusError::FrameError(err.into()))
+}
+
+fn take_bits(bits: Option<impl Iterator<Item = bool>>, quantity: u16) -> Result<Vec<bool>> {
+    let bits: Vec<bool> = bits
+        .ok_or(out_of_range())?
+        .take(quantity as usize)
+        .collect();
+
+    if bits.len() == quantity as usize {
+        Ok(bits)
+    } else {
+        Err(out_of_range())
+    }
+}
+
+fn out_of_range() -> ModbusError {
+    ModbusFrameError::from(ModbusPduError::OutOfRange).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::pdu::fcode::ExceptionCode;
+
+    #[test]
+    fn test_codec_read_holding_registers() {
+        let request = encode_read_holding_registers(0x0010, 2).unwrap();
+        assert_eq!(request.as_slice(), &[0x03, 0x00, 0x10, 0x00, 0x02]);
+
+        let mut response = Pdu::new(0x03).unwrap();
+        response.put_slice(&[0x04, 0x12, 0x34, 0x56, 0x78]).unwrap();
+        assert_eq!(
+            decode_read_holding_registers_response(response).unwrap(),
+            [0x1234, 0x5678]
+        );
+    }
+
+    #[test]
+    fn test_codec_read_holding_registers_truncated() {
+        let mut response = Pdu::new(0x03).unwrap();
+        response.put_slice(&[0x04, 0x12, 0x34]).unwrap();
+        assert!(decode_read_holding_registers_response(response).is_err());
+    }
+
+    #[test]
+    fn test_codec_read_input_registers_odd_byte_count() {
+        let mut response = Pdu::new(0x04).unwrap();
+        response.put_slice(&[0x03, 0x12, 0x34, 0x56]).unwrap();
+        assert!(decode_read_input_registers_response(response).is_err());
+    }
+
+    #[test]
+    fn test_codec_read_coils() {
+        let mut response = Pdu::new(0x01).unwrap();
+        response.put_slice(&[0x01, 0b0000_0101]).unwrap();
+        assert_eq!(
+            decode_read_coils_response(response.clone(), 3).unwrap(),
+            [true, false, true]
+        );
+        assert!(decode_read_coils_response(response, 9).is_err());
+    }
+
+    #[test]
+    fn test_codec_write_single_coil() {
+        let request = encode_write_single_coil(0x00AC, true).unwrap();
+        assert_eq!(request.as_slice(), &[0x05, 0x00, 0xAC, 0xFF, 0x00]);
+
+        assert_eq!(
+            decode_write_single_coil_response(request).unwrap(),
+            (0x00AC, true)
+        );
+    }
+
+    #[test]
+    fn test_codec_unexpected_function_code() {
+        let response = encode_write_single_register(0x0001, 0x0003).unwrap();
+        assert!(matches!(
+            decode_read_holding_registers_response(response),
+            Err(ModbusError::FrameError(ModbusFrameError::PduError(
+                ModbusPduError::UnexpectedCode(0x03)
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_codec_exception_response() {
+        let mut response = Pdu::new(0x83).unwrap();
+        response.put_u8(0x02).unwrap();
+        assert!(matches!(
+            decode_read_holding_registers_response(response),
+            Err(ModbusError::Exception(ExceptionCode::IllegalDataAddress))
+        ));
+    }
+}
@@ -0,0 +1,408 @@
+//! Conformance checks for complete request/response exchanges
+//!
+//! Each `check_*` function applies the validity rules of the Modbus application protocol
+//! specification to a request and the PDU received for it, and reports every rule that is
+//! broken instead of stopping at the first one. The response is taken as a raw `Pdu` so that
+//! exception responses can be checked as well.
+
+use core::ops::RangeInclusive;
+
+use crate::frame::pdu::fcode::{ExceptionCode, PublicFunctionCode, EXCEPTION_FLAG};
+use crate::frame::pdu::function::request::*;
+use crate::frame::pdu::Pdu;
+use crate::lib::*;
+
+/// A violation of the Modbus specification found in an exchange
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConformanceViolation {
+    /// The request data field does not have the length defined for the function
+    RequestLength { expected: usize, actual: usize },
+    /// The requested quantity is outside the range allowed for the function
+    QuantityOutOfRange(u16),
+    /// The request carries a value that is not allowed for the function
+    IllegalRequestValue(u16),
+    /// The response function code is neither the request code nor its exception code
+    FunctionCodeMismatch { expected: u8, actual: Option<u8> },
+    /// The exception response does not carry exactly one exception code byte
+    ExceptionLength(usize),
+    /// The exception response carries an undefined exception code
+    UndefinedExceptionCode(u8),
+    /// The response data field does not have the length defined for the function
+    ResponseLength { expected: usize, actual: usize },
+    /// The byte count field disagrees with the number of bytes that follow it
+    ByteCountMismatch { byte_count: u8, actual: usize },
+    /// The byte count field does not correspond to the requested quantity
+    QuantityMismatch { expected: usize, byte_count: u8 },
+    /// A field the server must echo differs from the request
+    EchoMismatch(&'static str),
+}
+
+/// Check a Read Coils exchange
+pub fn check_read_coils(request: &ReadCoilsRequest, response: &Pdu) -> Vec<ConformanceViolation> {
+    check_read_bits(
+        PublicFunctionCode::ReadCoils,
+        request.as_pdu(),
+        request.quantity_of_coils(),
+        response,
+    )
+}
+
+/// Check a Read Discrete Inputs exchange
+pub fn check_read_discrete_inputs(
+    request: &ReadDiscreteInputsRequest,
+    response: &Pdu,
+) -> Vec<ConformanceViolation> {
+    check_read_bits(
+        PublicFunctionCode::ReadDiscreteInputs,
+        request.as_pdu(),
+        request.quantity_of_inputs(),
+        response,
+    )
+}
+
+/// Check a Read Holding Registers exchange
+pub fn check_read_holding_registers(
+    request: &ReadHoldingRegistersRequest,
+    response: &Pdu,
+) -> Vec<ConformanceViolation> {
+    check_read_registers(
+        PublicFunctionCode::ReadHoldingRegisters,
+        request.as_pdu(),
+        request.quantity_of_registers(),
+        response,
+    )
+}
+
+/// Check a Read Input Registers exchange
+pub fn check_read_input_registers(
+    request: &ReadInputRegistersRequest,
+    response: &Pdu,
+) -> Vec<ConformanceViolation> {
+    check_read_registers(
+        PublicFunctionCode::ReadInputRegisters,
+        request.as_pdu(),
+        request.quantity_of_input_registers(),
+        response,
+    )
+}
+
+/// Check a Write Single Coil exchange
+pub fn check_write_single_coil(
+    request: &WriteSingleCoilRequest,
+    response: &Pdu,
+) -> Vec<ConformanceViolation> {
+    let mut violations = Vec::new();
+    let request = request.as_pdu();
+
+    check_request_length(request, 4, &mut violations);
+    if let Some(value) = request.read_u16(2) {
+        if value != 0x0000 && value != 0xFF00 {
+            violations.push(ConformanceViolation::IllegalRequestValue(value));
+        }
+    }
+
+    if check_response_code(
+        PublicFunctionCode::WriteSingleCoil,
+        response,
+        &mut violations,
+    ) {
+        check_echo(
+            request,
+            response,
+            &["output_address", "output_value"],
+            &mut violations,
+        );
+    }
+
+    violations
+}
+
+/// Check a Write Single Register exchange
+pub fn check_write_single_register(
+    request: &WriteSingleRegisterRequest,
+    response: &Pdu,
+) -> Vec<ConformanceViolation> {
+    let mut violations = Vec::new();
+    let request = request.as_pdu();
+
+    check_request_length(request, 4, &mut violations);
+
+    if check_response_code(
+        PublicFunctionCode::WriteSingleRegister,
+        response,
+        &mut violations,
+    ) {
+        check_echo(
+            request,
+            response,
+            &["register_address", "register_value"],
+            &mut violations,
+        );
+    }
+
+    violations
+}
+
+fn check_read_bits(
+    code: PublicFunctionCode,
+    request: &Pdu,
+    quantity: Option<u16>,
+    response: &Pdu,
+) -> Vec<ConformanceViolation> {
+    let mut violations = Vec::new();
+
+    check_request_length(request, 4, &mut violations);
+    let quantity = check_quantity(quantity, 1..=2000, &mut violations);
+
+    if check_response_code(code, response, &mut violations) {
+        check_byte_count(
+            response,
+            quantity.map(|quantity| (quantity as usize).div_ceil(8)),
+            &mut violations,
+        );
+    }
+
+    violations
+}
+
+fn check_read_registers(
+    code: PublicFunctionCode,
+    request: &Pdu,
+    quantity: Option<u16>,
+    response: &Pdu,
+) -> Vec<ConformanceViolation> {
+    let mut violations = Vec::new();
+
+    check_request_length(request, 4, &mut violations);
+    let quantity = check_quantity(quantity, 1..=125, &mut violations);
+
+    if check_response_code(code, response, &mut violations) {
+        check_byte_count(
+            response,
+            quantity.map(|quantity| quantity as usize * 2),
+            &mut violations,
+        );
+    }
+
+    violations
+}
+
+/// Check the request data field length
+fn check_request_length(
+    request: &Pdu,
+    expected: usize,
+    violations: &mut Vec<ConformanceViolation>,
+) {
+    let actual = request.data().len();
+    if actual != expected {
+        violations.push(ConformanceViolation::RequestLength { expected, actual });
+    }
+}
+
+/// Check the requested quantity against the range allowed for the function
+fn check_quantity(
+    quantity: Option<u16>,
+    range: RangeInclusive<u16>,
+    violations: &mut Vec<ConformanceViolation>,
+) -> Option<u16> {
+    let quantity = quantity?;
+    if range.contains(&quantity) {
+        Some(quantity)
+    } else {
+        violations.push(ConformanceViolation::QuantityOutOfRange(quantity));
+        None
+    }
+}
+
+/// Check the response function code, validating the exception response if it is one
+///
+/// Returns `true` when the response is a normal response whose body should be checked further.
+fn check_response_code(
+    code: PublicFunctionCode,
+    response: &Pdu,
+    violations: &mut Vec<ConformanceViolation>,
+) -> bool {
+    let expected = u8::from(code);
+    let actual = response.function_code();
+
+    if actual == Some(expected) {
+        return true;
+    }
+
+    if actual == Some(expected | EXCEPTION_FLAG) {
+        match response.data() {
+            [exception_code] => {
+                if ExceptionCode::try_from(*exception_code).is_err() {
+                    violations.push(ConformanceViolation::UndefinedExceptionCode(
+                        *exception_code,
+                    ));
+                }
+            }
+            data => violations.push(ConformanceViolation::ExceptionLength(data.len())),
+        }
+    } else {
+        violations.push(ConformanceViolation::FunctionCodeMismatch { expected, actual });
+    }
+
+    false
+}
+
+/// Check the byte count of a read response against its payload and the requested quantity
+fn check_byte_count(
+    response: &Pdu,
+    expected: Option<usize>,
+    violations: &mut Vec<ConformanceViolation>,
+) {
+    let Some((&byte_count, payload)) = response.data().split_first() else {
+        violations.push(ConformanceViolation::ResponseLength {
+            expected: 1 + expected.unwrap_or_default(),
+            actual: 0,
+        });
+        return;
+    };
+
+    let actual = payload.len();
+    if byte_count as usize != actual {
+        violations.push(ConformanceViolation::ByteCountMismatch { byte_count, actual });
+    }
+
+    if let Some(expected) = expected {
+        if byte_count as usize != expected {
+            violations.push(ConformanceViolation::QuantityMismatch {
+                expected,
+                byte_count,
+            });
+        }
+    }
+}
+
+/// Check that a response echoes the `u16` fields of the request
+fn check_echo(
+    request: &Pdu,
+    response: &Pdu,
+    fields: &[&'static str],
+    violations: &mut Vec<ConformanceViolation>,
+) {
+    let expected = fields.len() * 2;
+    let actual = response.data().len();
+    if actual != expected {
+        violations.push(ConformanceViolation::ResponseLength { expected, actual });
+    }
+
+    for (index, field) in fields.iter().enumerate() {
+        if request.read_u16(index * 2) != response.read_u16(index * 2) {
+            violations.push(ConformanceViolation::EchoMismatch(field));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conformance_read_holding_registers_valid() {
+        let request = ReadHoldingRegistersRequest::new(0x0000, 2).unwrap();
+        let mut response = Pdu::new(0x03).unwrap();
+        response.put_slice(&[0x04, 0x00, 0x01, 0x00, 0x02]).unwrap();
+
+        assert!(check_read_holding_registers(&request, &response).is_empty());
+    }
+
+    #[test]
+    fn test_conformance_read_holding_registers_byte_count() {
+        let request = ReadHoldingRegistersRequest::new(0x0000, 2).unwrap();
+        let mut response = Pdu::new(0x03).unwrap();
+        response.put_slice(&[0x04, 0x00, 0x01]).unwrap();
+
+        assert_eq!(
+            check_read_holding_registers(&request, &response),
+            [ConformanceViolation::ByteCountMismatch {
+                byte_count: 4,
+                actual: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_conformance_read_holding_registers_function_code_only() {
+        let request = ReadHoldingRegistersRequest::new(0x0000, 2).unwrap();
+        let response = Pdu::new(0x03).unwrap();
+
+        assert_eq!(
+            check_read_holding_registers(&request, &response),
+            [ConformanceViolation::ResponseLength {
+                expected: 5,
+                actual: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_conformance_read_coils_quantity_mismatch() {
+        let request = ReadCoilsRequest::new(0x0000, 10).unwrap();
+        let mut response = Pdu::new(0x01).unwrap();
+        response.put_slice(&[0x01, 0xFF]).unwrap();
+
+        assert_eq!(
+            check_read_coils(&request, &response),
+            [ConformanceViolation::QuantityMismatch {
+                expected: 2,
+                byte_count: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_conformance_exception() {
+        let request = ReadInputRegistersRequest::new(0x0000, 1).unwrap();
+
+        let mut response = Pdu::new(0x84).unwrap();
+        response.put_u8(0x02).unwrap();
+        assert!(check_read_input_registers(&request, &response).is_empty());
+
+        let mut response = Pdu::new(0x84).unwrap();
+        response.put_slice(&[0x07, 0x00]).unwrap();
+        assert_eq!(
+            check_read_input_registers(&request, &response),
+            [ConformanceViolation::ExceptionLength(2)]
+        );
+
+        let mut response = Pdu::new(0x84).unwrap();
+        response.put_u8(0x07).unwrap();
+        assert_eq!(
+            check_read_input_registers(&request, &response),
+            [ConformanceViolation::UndefinedExceptionCode(0x07)]
+        );
+    }
+
+    #[test]
+    fn test_conformance_write_single_register_echo() {
+        let request = WriteSingleRegisterRequest::new(0x0001, 0x1234).unwrap();
+
+        let mut response = Pdu::new(0x06).unwrap();
+        response.put_slice(&[0x00, 0x01, 0x12, 0x34]).unwrap();
+        assert!(check_write_single_register(&request, &response).is_empty());
+
+        let mut response = Pdu::new(0x06).unwrap();
+        response.put_slice(&[0x00, 0x01, 0x12, 0x35]).unwrap();
+        assert_eq!(
+            check_write_single_register(&request, &response),
+            [ConformanceViolation::EchoMismatch("register_value")]
+        );
+    }
+
+    #[test]
+    fn test_conformance_function_code_mismatch() {
+        let request = WriteSingleCoilRequest::new(0x0001, true).unwrap();
+        let response = Pdu::new(0x06).unwrap();
+
+        assert_eq!(
+            check_write_single_coil(&request, &response),
+            [ConformanceViolation::FunctionCodeMismatch {
+                expected: 0x05,
+                actual: Some(0x06)
+            }]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::frame::pdu::fcode::ExceptionCode;
 use crate::lib::*;
 use thiserror::Error;
 
@@ -9,10 +10,19 @@ pub enum ModbusError {
     FrameError(#[from] ModbusFrameError),
     #[error("Modbus transport error: {0}")]
     TransportError(#[from] ModbusTransportError),
+    #[error("Modbus exception response: {0} ({code:#04x})", code = u8::from(*.0))]
+    Exception(ExceptionCode),
 }
 
 #[derive(Debug, Error)]
-pub enum ModbusApplicationError {}
+pub enum ModbusApplicationError {
+    #[error("Response does not match the request")]
+    ResponseMismatch,
+    #[error("Function code {0:#04x} can not be broadcast")]
+    NotBroadcastable(u8),
+    #[error("Long operation still busy at the deadline")]
+    LongOperationTimeout,
+}
 
 #[derive(Debug, Error)]
 pub enum ModbusTransportError {
@@ -23,6 +33,10 @@ pub enum ModbusTransportError {
     Timeout,
     #[error("Frame incomplete")]
     FrameIncomplete,
+    #[error("Unknown bus: {0}")]
+    UnknownBus(u8),
+    #[error("Bus busy")]
+    BusBusy,
 }
 
 #[derive(Debug, Error)]
@@ -31,9 +45,15 @@ pub enum ModbusFrameError {
     PduError(#[from] ModbusPduError),
     #[error("Modbus buffer error: {0}")]
     BufferError(#[from] BufferError),
+    #[cfg(feature = "ascii")]
+    #[error("Modbus ASCII error: {0}")]
+    AsciiError(#[from] ModbusAsciiError),
     #[cfg(feature = "rtu")]
     #[error("Modbus RTU error: {0}")]
     RtuError(#[from] ModbusRtuError),
+    #[cfg(feature = "tcp")]
+    #[error("Modbus TCP error: {0}")]
+    TcpError(#[from] ModbusTcpError),
 }
 
 #[derive(Debug, Error)]
@@ -42,12 +62,24 @@ pub enum ModbusPduError {
     UndefinedFunctionCode(u8),
     #[error("Undefined exception code: {0}")]
     UndefinedExceptionCode(u8),
+    #[error("Undefined sub-function code: {0}")]
+    UndefinedSubFunctionCode(u16),
     #[error("Unexpected code: {0}")]
     UnexpectedCode(u8),
+    #[error("Function code {0:#04x} is not available to user defined functions")]
+    ReservedFunctionCode(u8),
     #[error("Data out of range")]
     OutOfRange,
 }
 
+#[derive(Debug, Error)]
+pub enum ResponseError {
+    #[error("Exception response: {0}")]
+    Exception(ExceptionCode),
+    #[error("Response to another function")]
+    WrongFunction,
+}
+
 #[derive(Debug, Error)]
 pub enum BufferError {
     #[error("Buffer overflow occurred")]
@@ -56,6 +88,21 @@ pub enum BufferError {
     NoSpaceLeft,
 }
 
+#[cfg(feature = "ascii")]
+#[derive(Debug, Error)]
+pub enum ModbusAsciiError {
+    #[error("Invalid slave address: {0}")]
+    InvalidSlaveAddress(u8),
+    #[error("LRC validation failure")]
+    LrcValidationFailure,
+    #[error("Invalid frame length")]
+    InvalidFrameLength,
+    #[error("Missing frame delimiter")]
+    InvalidDelimiter,
+    #[error("Invalid character: {0}")]
+    InvalidCharacter(u8),
+}
+
 #[cfg(feature = "rtu")]
 #[derive(Debug, Error)]
 pub enum ModbusRtuError {
@@ -69,4 +116,13 @@ pub enum ModbusRtuError {
 
 #[cfg(feature = "tcp")]
 #[derive(Debug, Error)]
-pub enum ModbusTcpError {}
+pub enum ModbusTcpError {
+    #[error("Invalid protocol identifier: {0}")]
+    InvalidProtocolId(u16),
+    #[error("Invalid frame length")]
+    InvalidFrameLength,
+    #[error("Invalid length field: {0}")]
+    InvalidLength(u16),
+    #[error("Empty response")]
+    EmptyResponse,
+}
@@ -1,11 +1,16 @@
 use crate::{error::BufferError, lib::*};
 
+#[cfg(feature = "ascii")]
+pub mod ascii;
+
 #[cfg(feature = "rtu")]
 pub mod rtu;
 
 #[cfg(feature = "tcp")]
 pub mod tcp;
 
+mod crc;
+pub mod detect;
 pub mod pdu;
 
 #[derive(Clone, PartialEq)]
@@ -49,7 +54,12 @@ impl<const N: usize> DataUnit<N> {
     }
 
     pub fn as_slice_mut(&mut self) -> &mut [u8] {
-        &mut self.data
+        &mut self.data[..self.position]
+    }
+
+    /// Unused space after the content, to read into before committing the bytes with `advance`
+    pub fn spare_capacity_mut(&mut self) -> &mut [u8] {
+        &mut self.data[self.position..]
     }
 
     pub fn put_u8(&mut self, src: u8) -> result::Result<(), BufferError> {
@@ -88,6 +98,32 @@ impl<const N: usize> DataUnit<N> {
         Some(u16::from_le_bytes([*low, *high]))
     }
 
+    /// Decode a run of big-endian `u16` values starting at `index` into `dst`
+    ///
+    /// Returns the number of values copied, or `None` if the run exceeds the buffer.
+    pub fn copy_u16_be_into(&self, index: usize, dst: &mut [u16]) -> Option<usize> {
+        let bytes = self.u16_run(index, dst.len())?;
+
+        for (value, bytes) in dst.iter_mut().zip(bytes.chunks_exact(2)) {
+            *value = u16::from_be_bytes([bytes[0], bytes[1]]);
+        }
+
+        Some(dst.len())
+    }
+
+    /// Decode `count` big-endian `u16` values starting at `index`
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    pub fn get_u16_be_vec(&self, index: usize, count: usize) -> Option<Vec<u16>> {
+        let bytes = self.u16_run(index, count)?;
+
+        Some(
+            bytes
+                .chunks_exact(2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+                .collect(),
+        )
+    }
+
     /// Set the length of the buffer.
     ///
     /// # Safety
@@ -126,6 +162,12 @@ impl<const N: usize> DataUnit<N> {
     fn get(&self, index: usize) -> Option<&u8> {
         self.data.get(index)
     }
+
+    /// Bytes of `count` consecutive `u16` values starting at `index`, with a single bounds check
+    fn u16_run(&self, index: usize, count: usize) -> Option<&[u8]> {
+        let end = count.checked_mul(2)?.checked_add(index)?;
+        self.as_slice().get(index..end)
+    }
 }
 
 #[cfg(test)]
@@ -236,6 +278,37 @@ mod tests {
         assert_eq!(pdu.get_u16_le(2), Some(0x0304));
     }
 
+    #[test]
+    fn test_frame_data_unit_copy_u16_be_into() {
+        let mut pdu = DataUnit {
+            data: [0; 10],
+            position: 0,
+        };
+        pdu.put_slice(&[0xFF, 0x01, 0x02, 0x03, 0x04]).unwrap();
+
+        let mut dst = [0; 2];
+        assert_eq!(pdu.copy_u16_be_into(1, &mut dst), Some(2));
+        assert_eq!(dst, [0x0102, 0x0304]);
+
+        let mut dst = [0; 3];
+        assert_eq!(pdu.copy_u16_be_into(1, &mut dst), None);
+        assert_eq!(pdu.copy_u16_be_into(usize::MAX, &mut dst), None);
+    }
+
+    #[test]
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    fn test_frame_data_unit_get_u16_be_vec() {
+        let mut pdu = DataUnit {
+            data: [0; 10],
+            position: 0,
+        };
+        pdu.put_slice(&[0xFF, 0x01, 0x02, 0x03, 0x04]).unwrap();
+
+        assert_eq!(pdu.get_u16_be_vec(1, 2), Some(Vec::from([0x0102, 0x0304])));
+        assert_eq!(pdu.get_u16_be_vec(1, 0), Some(Vec::new()));
+        assert_eq!(pdu.get_u16_be_vec(2, 2), None);
+    }
+
     #[test]
     fn test_frame_data_unit_as_slice() {
         let mut pdu = DataUnit {
@@ -248,6 +321,22 @@ mod tests {
         assert_eq!(pdu.as_slice(), &[0x01, 0x02, 0x03]);
     }
 
+    #[test]
+    fn test_frame_data_unit_as_slice_mut() {
+        let mut pdu = DataUnit {
+            data: [0; 4],
+            position: 0,
+        };
+        pdu.put_u8(0x01).unwrap();
+        pdu.as_slice_mut()[0] = 0x02;
+        assert_eq!(pdu.as_slice_mut().len(), 1);
+
+        pdu.spare_capacity_mut()[..2].copy_from_slice(&[0x03, 0x04]);
+        assert_eq!(pdu.spare_capacity_mut().len(), 3);
+        pdu.advance(2);
+        assert_eq!(pdu.as_slice(), &[0x02, 0x03, 0x04]);
+    }
+
     #[test]
     fn test_frame_data_unit_clear() {
         let mut pdu = DataUnit {
@@ -0,0 +1,186 @@
+use super::{pdu::Pdu, DataUnit};
+use crate::error::{ModbusAsciiError, ModbusFrameError};
+use crate::lib::*;
+
+/// Start + hex encoded (Address + PDU + LRC) + CR LF
+const MAX_ADU_SIZE: usize = 1 + (1 + 253 + 1) * 2 + 2;
+const START: u8 = b':';
+const END: [u8; 2] = [b'\r', b'\n'];
+
+/// Modbus ASCII Application Data Unit
+/// # Structure
+/// * Start : `:`
+/// * Address : `u8` (2 chars)
+/// * PDU : `FunctionCode` + `Data` (MAX : 253 bytes, 2 chars each)
+/// * LRC : `u8` (2 chars)
+/// * End : `CR LF`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Adu(DataUnit<MAX_ADU_SIZE>);
+
+impl Deref for Adu {
+    type Target = DataUnit<MAX_ADU_SIZE>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Adu {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+pub struct AsciiFrameHandler;
+
+impl AsciiFrameHandler {
+    pub fn build_frame(adu: &mut Adu, address: u8, pdu: &Pdu) -> Result<usize, ModbusFrameError> {
+        adu.clear();
+
+        adu.put_u8(START)?;
+        put_hex(adu, address)?;
+        for byte in pdu.as_slice() {
+            put_hex(adu, *byte)?;
+        }
+        let lrc = calc_lrc(iter::once(&address).chain(pdu.as_slice()));
+        put_hex(adu, lrc)?;
+        adu.put_slice(&END)?;
+
+        Ok(adu.len())
+    }
+
+    /// Parse the PDU from the given frame
+    ///
+    /// An `expected_address` of `0` accepts frames from any address, as with RTU.
+    pub fn parse_frame(frame: &[u8], expected_address: u8) -> Result<Pdu, ModbusFrameError> {
+        let body = check_frame_delimiters(frame)?;
+
+        // Address + Function Code + LRC
+        if body.len() < 6 || body.len() % 2 != 0 {
+            return Err(ModbusAsciiError::InvalidFrameLength.into());
+        }
+
+        let byte = |index: usize| decode_hex(body[index * 2], body[index * 2 + 1]);
+        let len = body.len() / 2;
+
+        let address = byte(0)?;
+        if expected_address != 0 && address != expected_address {
+            return Err(ModbusAsciiError::InvalidSlaveAddress(address).into());
+        }
+
+        let mut pdu = Pdu::new(byte(1)?)?;
+        for index in 2..len - 1 {
+            pdu.put_u8(byte(index)?)?;
+        }
+
+        let lrc = byte(len - 1)?;
+        if lrc != calc_lrc(iter::once(&address).chain(pdu.as_slice())) {
+            return Err(ModbusAsciiError::LrcValidationFailure.into());
+        }
+
+        Ok(pdu)
+    }
+}
+
+/// Strip the start and end delimiters of the given frame
+fn check_frame_delimiters(frame: &[u8]) -> Result<&[u8], ModbusAsciiError> {
+    if frame.len() > MAX_ADU_SIZE {
+        return Err(ModbusAsciiError::InvalidFrameLength);
+    }
+
+    frame
+        .strip_prefix(&[START])
+        .and_then(|frame| frame.strip_suffix(&END))
+        .ok_or(ModbusAsciiError::InvalidDelimiter)
+}
+
+/// Append the given byte as two uppercase hex characters
+fn put_hex(adu: &mut Adu, byte: u8) -> Result<(), ModbusFrameError> {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    adu.put_u8(HEX[(byte >> 4) as usize])?;
+    adu.put_u8(HEX[(byte & 0x0F) as usize])?;
+
+    Ok(())
+}
+
+/// Decode two hex characters, either case, into a byte
+fn decode_hex(high: u8, low: u8) -> Result<u8, ModbusAsciiError> {
+    let nibble = |char: u8| match char {
+        b'0'..=b'9' => Ok(char - b'0'),
+        b'A'..=b'F' => Ok(char - b'A' + 10),
+        b'a'..=b'f' => Ok(char - b'a' + 10),
+        _ => Err(ModbusAsciiError::InvalidCharacter(char)),
+    };
+
+    Ok(nibble(high)? << 4 | nibble(low)?)
+}
+
+/// Calculate the Modbus LRC, the two's complement of the sum of all bytes
+fn calc_lrc<'a>(data: impl Iterator<Item = &'a u8>) -> u8 {
+    data.fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+        .wrapping_neg()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_ascii_build_frame() {
+        let mut pdu = Pdu::new(0x03).unwrap();
+        pdu.put_slice(&[0x00, 0x6B, 0x00, 0x03]).unwrap();
+
+        let mut adu = Adu::default();
+        let len = AsciiFrameHandler::build_frame(&mut adu, 0x11, &pdu).unwrap();
+        assert_eq!(adu.as_slice(), b":1103006B00037E\r\n");
+        assert_eq!(len, 17);
+    }
+
+    #[test]
+    fn test_frame_ascii_parse_frame() {
+        let pdu = AsciiFrameHandler::parse_frame(b":1103006B00037E\r\n", 0x11).unwrap();
+        assert_eq!(pdu.as_slice(), &[0x03, 0x00, 0x6B, 0x00, 0x03]);
+
+        // Lowercase hex and any address
+        let pdu = AsciiFrameHandler::parse_frame(b":1103006b00037e\r\n", 0).unwrap();
+        assert_eq!(pdu.function_code(), Some(0x03));
+
+        assert!(matches!(
+            AsciiFrameHandler::parse_frame(b":1103006B00037F\r\n", 0x11),
+            Err(ModbusFrameError::AsciiError(
+                ModbusAsciiError::LrcValidationFailure
+            ))
+        ));
+        assert!(matches!(
+            AsciiFrameHandler::parse_frame(b":1103006B00037E\r\n", 0x12),
+            Err(ModbusFrameError::AsciiError(
+                ModbusAsciiError::InvalidSlaveAddress(0x11)
+            ))
+        ));
+        assert!(matches!(
+            AsciiFrameHandler::parse_frame(b"1103006B00037E\r\n", 0x11),
+            Err(ModbusFrameError::AsciiError(
+                ModbusAsciiError::InvalidDelimiter
+            ))
+        ));
+        assert!(matches!(
+            AsciiFrameHandler::parse_frame(b":1103006G00037E\r\n", 0x11),
+            Err(ModbusFrameError::AsciiError(
+                ModbusAsciiError::InvalidCharacter(b'G')
+            ))
+        ));
+        assert!(matches!(
+            AsciiFrameHandler::parse_frame(b":1103\r\n", 0x11),
+            Err(ModbusFrameError::AsciiError(
+                ModbusAsciiError::InvalidFrameLength
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_frame_ascii_lrc() {
+        assert_eq!(calc_lrc([0x11, 0x03, 0x00, 0x6B, 0x00, 0x03].iter()), 0x7E);
+        assert_eq!(calc_lrc([].iter()), 0x00);
+    }
+}
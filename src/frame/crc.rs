@@ -0,0 +1,77 @@
+//! Modbus CRC-16, shared by the RTU framing and the protocol detection
+
+/// Calculate the Modbus 16-bit CRC for the given data
+pub(crate) fn calc_crc(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for byte in data {
+        crc = (crc >> 8) ^ MODBUS_16_CRC[((crc ^ (*byte as u16)) & 0xFF) as usize];
+    }
+
+    crc
+}
+
+/// Lookup table for the Modbus 16-bit CRC algorithm
+const MODBUS_16_CRC: [u16; 256] = generate_crc_table();
+
+/// Generate a lookup table for the Modbus 16-bit CRC algorithm
+const fn generate_crc_table() -> [u16; 256] {
+    let mut table = [0u16; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u16;
+        let mut j = 0;
+        while j < 8 {
+            if crc & 0x0001 != 0 {
+                crc = (crc >> 1) ^ 0xA001;
+            } else {
+                crc >>= 1;
+            }
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_crc_calc_crc_with_standard_data() {
+        let data = b"123456789";
+        let expected_crc = 0x4B37;
+        assert_eq!(calc_crc(data), expected_crc);
+    }
+
+    #[test]
+    fn test_frame_crc_calc_crc_with_empty_data() {
+        let data: [u8; 0] = [];
+        let expected_crc = 0xFFFF;
+        assert_eq!(calc_crc(&data), expected_crc);
+    }
+
+    #[test]
+    fn test_frame_crc_calc_crc_with_single_byte() {
+        let data = [0x01];
+        let expected_crc = 0x807E;
+        assert_eq!(calc_crc(&data), expected_crc);
+    }
+
+    #[test]
+    fn test_frame_crc_calc_crc_with_multiple_bytes() {
+        let data = [0x01, 0x02, 0x03, 0x04];
+        let expected_crc = 0x2BA1;
+        assert_eq!(calc_crc(&data), expected_crc);
+    }
+
+    #[test]
+    fn test_frame_crc_calc_crc_with_edge_values() {
+        let data = [0xFF, 0x00, 0xFF, 0x00];
+        let expected_crc = 0xC071;
+        assert_eq!(calc_crc(&data), expected_crc);
+    }
+}
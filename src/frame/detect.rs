@@ -0,0 +1,105 @@
+//! Heuristic detection of the Modbus framing used by a byte sample
+//!
+//! The checks run in this order, and the first match wins:
+//!
+//! 1. ASCII: starts with `:` and continues with hexadecimal characters, optionally ending
+//!    with CR LF.
+//! 2. RTU: at least 4 bytes whose last two are the CRC of the rest.
+//! 3. TCP: an MBAP header with protocol identifier `0x0000` whose length field covers the
+//!    rest of the sample.
+//!
+//! The heuristics only recognize a sample holding exactly one frame, starting at its first
+//! byte. Garbage before the frame or several frames back to back are not detected. RTU is
+//! checked before TCP because a short RTU request can carry a plausible MBAP header, e.g. a
+//! read of 2 registers at address 0; a TCP frame is taken for RTU only when its last two bytes
+//! happen to be a valid CRC, about once in 65536 frames.
+
+use super::crc::calc_crc;
+
+/// Modbus framing variant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Framing {
+    Rtu,
+    Ascii,
+    Tcp,
+}
+
+/// Guess the framing of the frame held by `sample`
+pub fn detect_framing(sample: &[u8]) -> Option<Framing> {
+    if is_ascii(sample) {
+        Some(Framing::Ascii)
+    } else if is_rtu(sample) {
+        Some(Framing::Rtu)
+    } else if is_tcp(sample) {
+        Some(Framing::Tcp)
+    } else {
+        None
+    }
+}
+
+fn is_ascii(sample: &[u8]) -> bool {
+    let Some((b':', body)) = sample.split_first() else {
+        return false;
+    };
+    let body = body.strip_suffix(b"\r\n").unwrap_or(body);
+
+    !body.is_empty() && body.iter().all(u8::is_ascii_hexdigit)
+}
+
+fn is_rtu(sample: &[u8]) -> bool {
+    if sample.len() < 4 {
+        return false;
+    }
+
+    let (data, crc) = sample.split_at(sample.len() - 2);
+    calc_crc(data) == u16::from_le_bytes([crc[0], crc[1]])
+}
+
+fn is_tcp(sample: &[u8]) -> bool {
+    match sample {
+        [_, _, 0x00, 0x00, length_hi, length_lo, _, _, ..] => {
+            let length = u16::from_be_bytes([*length_hi, *length_lo]) as usize;
+            length >= 2 && 6 + length == sample.len()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_detect_ascii() {
+        assert_eq!(detect_framing(b":1103006B0003\r\n"), Some(Framing::Ascii));
+        assert_eq!(detect_framing(b":1103006B0003"), Some(Framing::Ascii));
+        assert_eq!(detect_framing(b":\r\n"), None);
+    }
+
+    #[test]
+    fn test_frame_detect_rtu() {
+        let frame = [0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9B];
+        assert_eq!(detect_framing(&frame), Some(Framing::Rtu));
+
+        // Also a plausible MBAP header: protocol id 0, length 2
+        let frame = [0x01, 0x03, 0x00, 0x00, 0x00, 0x02, 0xC4, 0x0B];
+        assert_eq!(detect_framing(&frame), Some(Framing::Rtu));
+    }
+
+    #[test]
+    fn test_frame_detect_tcp() {
+        let frame = [
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x11, 0x03, 0x00, 0x6B, 0x00, 0x03,
+        ];
+        assert_eq!(detect_framing(&frame), Some(Framing::Tcp));
+
+        // Length field inconsistent with the sample
+        assert_eq!(detect_framing(&frame[..11]), None);
+    }
+
+    #[test]
+    fn test_frame_detect_unknown() {
+        assert_eq!(detect_framing(&[]), None);
+        assert_eq!(detect_framing(&[0x11, 0x06, 0x00]), None);
+    }
+}
@@ -1,36 +1,43 @@
-use crate::error::ModbusFrameError;
+use crate::error::{ModbusFrameError, ModbusPduError};
 use crate::lib::*;
 
 use super::DataUnit;
+use fcode::{PublicFunctionCode, EXCEPTION_FLAG};
+use types::RegisterSlice;
 
 pub mod fcode;
 pub mod function;
 pub mod types;
 
-const MAX_PDU_SIZE: usize = 253;
+/// Largest PDU allowed by the specification
+pub const MAX_PDU_SIZE: usize = 253;
 
 /// Protocol Data Unit
 /// # Structure
 /// * Code : `u8`
 /// * Data : `[u8; N]` (MAX : 252 bytes)
+///
+/// `N` defaults to the specification limit. A larger `N` holds the PDUs of vendor extensions
+/// exceeding it, see [`Pdu::new_sized`]. The typed requests and responses always use the
+/// default size and keep enforcing the limits of the specification.
 #[derive(Debug, Clone, PartialEq)]
-pub struct Pdu(DataUnit<MAX_PDU_SIZE>);
+pub struct Pdu<const N: usize = MAX_PDU_SIZE>(DataUnit<N>);
 
-impl Deref for Pdu {
-    type Target = DataUnit<MAX_PDU_SIZE>;
+impl<const N: usize> Deref for Pdu<N> {
+    type Target = DataUnit<N>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl DerefMut for Pdu {
+impl<const N: usize> DerefMut for Pdu<N> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
 }
 
-impl Display for Pdu {
+impl<const N: usize> Display for Pdu<N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Code:{:?} {:?}", self.function_code(), self.data())
     }
@@ -38,6 +45,73 @@ impl Display for Pdu {
 
 impl Pdu {
     pub fn new(function_code: u8) -> Result<Self, ModbusFrameError> {
+        Self::new_sized(function_code)
+    }
+
+    /// Copy a PDU from its wire bytes, the function code followed by the data
+    ///
+    /// Only the length is checked, `1..=253` bytes. Use this to replay captured PDUs without
+    /// the addressing and checksum of a frame.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ModbusFrameError> {
+        if !(1..=MAX_PDU_SIZE).contains(&bytes.len()) {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+
+        let mut pdu = Pdu(DataUnit::default());
+        pdu.put_slice(bytes)?;
+
+        Ok(pdu)
+    }
+
+    /// Build a request PDU, checking that `data` has the shape of a request for `code`
+    ///
+    /// The data length must match the fixed request size of the function, or reach the
+    /// minimum size of a variable length request. A byte count field must agree with the
+    /// bytes that follow it.
+    pub fn new_validated_request(
+        code: PublicFunctionCode,
+        data: &[u8],
+    ) -> Result<Self, ModbusPduError> {
+        use PublicFunctionCode::*;
+
+        // (minimum length, fixed length, index of the byte count)
+        let (min_len, fixed, byte_count_index) = match code {
+            ReadCoils | ReadDiscreteInputs | ReadHoldingRegisters | ReadInputRegisters
+            | WriteSingleCoil | WriteSingleRegister => (4, true, None),
+            ReadExceptionStatus | GetCommEventCounter | GetCommEventLog | ReportServerId => {
+                (0, true, None)
+            }
+            Diagnostics => (4, false, None),
+            WriteMultipleCoils | WriteMultipleRegisters => (6, false, Some(4)),
+            ReadFileRecord => (8, false, Some(0)),
+            WriteFileRecord => (10, false, Some(0)),
+            MaskWriteRegister => (6, true, None),
+            ReadWriteMultipleRegisters => (11, false, Some(8)),
+            ReadFifoQueue => (2, true, None),
+            EncapsulatedInterfaceTransport => (1, false, None),
+        };
+
+        if data.len() < min_len || (fixed && data.len() != min_len) {
+            return Err(ModbusPduError::OutOfRange);
+        }
+
+        if let Some(index) = byte_count_index {
+            if data[index] as usize != data.len() - index - 1 {
+                return Err(ModbusPduError::OutOfRange);
+            }
+        }
+
+        let mut pdu = Self::new(code.into()).map_err(|_| ModbusPduError::OutOfRange)?;
+        pdu.put_slice(data)
+            .map_err(|_| ModbusPduError::OutOfRange)?;
+
+        Ok(pdu)
+    }
+}
+
+impl<const N: usize> Pdu<N> {
+    /// Create a PDU holding up to `N` bytes, e.g. `Pdu::<1024>::new_sized(0x41)`
+    pub fn new_sized(function_code: u8) -> Result<Self, ModbusFrameError> {
         let mut pdu = Pdu(DataUnit::default());
 
         // Push function code
@@ -46,12 +120,55 @@ impl Pdu {
         Ok(pdu)
     }
 
+    /// Copy into a PDU of another size, failing if it does not fit
+    ///
+    /// Use this to hand a PDU received in a larger buffer to the typed requests and responses.
+    pub fn try_resize<const M: usize>(&self) -> Result<Pdu<M>, ModbusFrameError> {
+        let mut pdu = Pdu(DataUnit::default());
+        pdu.put_slice(self.as_slice())?;
+
+        Ok(pdu)
+    }
+
+    /// Wire bytes of the PDU, the inverse of `Pdu::from_bytes`
+    pub fn to_bytes(&self) -> &[u8] {
+        self.as_slice()
+    }
+
     pub fn function_code(&self) -> Option<u8> {
         self.get_u8(0)
     }
 
+    /// Function code with the exception bit masked off
+    ///
+    /// Use this to correlate a response, exception or not, with its request.
+    pub fn base_function_code(&self) -> Option<u8> {
+        self.function_code().map(|code| code & !EXCEPTION_FLAG)
+    }
+
     pub fn data(&self) -> &[u8] {
-        &self.as_slice()[1..]
+        self.as_slice().get(1..).unwrap_or_default()
+    }
+
+    /// `len` bytes of the data field from `start` on, or `None` past the end of the data
+    ///
+    /// Use this to slice by length fields of the PDU, which are not to be trusted.
+    pub fn try_data_slice(&self, start: usize, len: usize) -> Option<&[u8]> {
+        self.data().get(start..start.checked_add(len)?)
+    }
+
+    /// Data field as 16-bit registers, after the leading byte count if `has_byte_count`
+    ///
+    /// A trailing odd byte is not part of any register and is left out.
+    pub fn data_as_registers(&self, has_byte_count: bool) -> RegisterSlice<'_> {
+        let data = self.data();
+        let data = if has_byte_count {
+            data.get(1..).unwrap_or_default()
+        } else {
+            data
+        };
+
+        RegisterSlice::new(&data[..data.len() & !1])
     }
 
     pub fn read_u8(&self, index: usize) -> Option<u8> {
@@ -71,7 +188,101 @@ mod tests {
     fn test_frame_pdu_new() {
         let pdu = Pdu::new(1).unwrap();
         assert_eq!(pdu.function_code(), Some(1));
-        assert_eq!(pdu.data(), &[]);
+        assert_eq!(pdu.data(), &[] as &[u8]);
+    }
+
+    #[test]
+    fn test_frame_pdu_from_bytes() {
+        let bytes = [0x03, 0x00, 0x6B, 0x00, 0x03];
+        let pdu = Pdu::from_bytes(&bytes).unwrap();
+        assert_eq!(pdu.function_code(), Some(0x03));
+        assert_eq!(pdu.data(), &bytes[1..]);
+        assert_eq!(pdu.to_bytes(), &bytes);
+        assert_eq!(Pdu::from_bytes(pdu.to_bytes()).unwrap(), pdu);
+
+        assert!(Pdu::from_bytes(&[0x41; MAX_PDU_SIZE]).is_ok());
+        assert!(matches!(
+            Pdu::from_bytes(&[]),
+            Err(ModbusFrameError::PduError(ModbusPduError::OutOfRange))
+        ));
+        assert!(matches!(
+            Pdu::from_bytes(&[0x41; MAX_PDU_SIZE + 1]),
+            Err(ModbusFrameError::PduError(ModbusPduError::OutOfRange))
+        ));
+    }
+
+    #[test]
+    fn test_frame_pdu_try_data_slice() {
+        let mut pdu = Pdu::new(0x03).unwrap();
+        pdu.put_slice(&[0x04, 0x12, 0x34]).unwrap();
+        assert_eq!(pdu.try_data_slice(1, 2), Some(&[0x12, 0x34][..]));
+        assert_eq!(pdu.try_data_slice(3, 0), Some(&[][..]));
+        assert_eq!(pdu.try_data_slice(1, 4), None);
+        assert_eq!(pdu.try_data_slice(4, 0), None);
+        assert_eq!(pdu.try_data_slice(1, usize::MAX), None);
+    }
+
+    #[test]
+    fn test_frame_pdu_new_validated_request() {
+        let pdu =
+            Pdu::new_validated_request(PublicFunctionCode::ReadHoldingRegisters, &[0, 1, 0, 2])
+                .unwrap();
+        assert_eq!(pdu.as_slice(), &[0x03, 0, 1, 0, 2]);
+        assert!(
+            Pdu::new_validated_request(PublicFunctionCode::ReadHoldingRegisters, &[0, 1, 0])
+                .is_err()
+        );
+        assert!(Pdu::new_validated_request(
+            PublicFunctionCode::WriteSingleRegister,
+            &[0, 1, 0, 2, 0]
+        )
+        .is_err());
+
+        assert!(Pdu::new_validated_request(PublicFunctionCode::GetCommEventCounter, &[]).is_ok());
+
+        // Byte count must match the values that follow
+        assert!(Pdu::new_validated_request(
+            PublicFunctionCode::WriteMultipleRegisters,
+            &[0, 1, 0, 1, 2, 0x12, 0x34]
+        )
+        .is_ok());
+        assert!(Pdu::new_validated_request(
+            PublicFunctionCode::WriteMultipleRegisters,
+            &[0, 1, 0, 1, 4, 0x12, 0x34]
+        )
+        .is_err());
+        assert!(Pdu::new_validated_request(
+            PublicFunctionCode::WriteMultipleCoils,
+            &[0, 1, 0, 1, 1]
+        )
+        .is_err());
+
+        assert!(Pdu::new_validated_request(
+            PublicFunctionCode::EncapsulatedInterfaceTransport,
+            &[0; MAX_PDU_SIZE]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_base_function_code() {
+        let pdu = Pdu::new(0x03).unwrap();
+        assert_eq!(pdu.base_function_code(), Some(0x03));
+
+        let pdu = Pdu::new(0x83).unwrap();
+        assert_eq!(pdu.function_code(), Some(0x83));
+        assert_eq!(pdu.base_function_code(), Some(0x03));
+    }
+
+    #[test]
+    fn test_frame_pdu_data_as_registers() {
+        let mut pdu = Pdu::new(0x03).unwrap();
+        pdu.put_slice(&[0x04, 0x12, 0x34, 0x56, 0x78]).unwrap();
+        assert!(pdu.data_as_registers(true).eq([0x1234, 0x5678]));
+        assert!(pdu.data_as_registers(false).eq([0x0412, 0x3456]));
+
+        let pdu = Pdu::new(0x03).unwrap();
+        assert_eq!(pdu.data_as_registers(true).count(), 0);
     }
 
     #[test]
@@ -106,4 +317,21 @@ mod tests {
 
         assert!(pdu.put_slice(&buf).is_err());
     }
+
+    #[test]
+    fn test_frame_pdu_new_sized() {
+        let mut pdu = Pdu::<512>::new_sized(0x41).unwrap();
+        pdu.put_slice(&[0xAA; 300]).unwrap();
+        assert_eq!(pdu.function_code(), Some(0x41));
+        assert_eq!(pdu.data().len(), 300);
+        assert!(pdu.put_slice(&[0; 212]).is_err());
+
+        // Too large for the specification limit
+        assert!(pdu.try_resize::<MAX_PDU_SIZE>().is_err());
+
+        let mut pdu = Pdu::<512>::new_sized(0x03).unwrap();
+        pdu.put_slice(&[0x02, 0x12, 0x34]).unwrap();
+        let resized: Pdu = pdu.try_resize().unwrap();
+        assert_eq!(resized.as_slice(), pdu.as_slice());
+    }
 }
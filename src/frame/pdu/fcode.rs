@@ -1,7 +1,11 @@
 use crate::{error::ModbusPduError, lib::*};
 
+/// Bit set in the function code of an exception response
+pub const EXCEPTION_FLAG: u8 = 0x80;
+
 /// Modbus function code
 #[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FunctionCode {
     Public(PublicFunctionCode),
     UserDefined(u8),
@@ -10,7 +14,7 @@ pub enum FunctionCode {
 impl From<u8> for FunctionCode {
     fn from(value: u8) -> Self {
         // MSB is reserved for exception code
-        let value = value & 0x7F;
+        let value = value & !EXCEPTION_FLAG;
 
         match PublicFunctionCode::try_from(value) {
             Ok(code) => Self::Public(code),
@@ -19,6 +23,34 @@ impl From<u8> for FunctionCode {
     }
 }
 
+impl FunctionCode {
+    /// Classify a raw function code by the ranges of the Modbus specification
+    pub fn classify(code: u8) -> FunctionClass {
+        if code & EXCEPTION_FLAG != 0 {
+            FunctionClass::Exception
+        } else if PublicFunctionCode::try_from(code).is_ok() {
+            FunctionClass::Public
+        } else if matches!(code, 65..=72 | 100..=110) {
+            FunctionClass::UserDefined
+        } else {
+            FunctionClass::Reserved
+        }
+    }
+}
+
+/// Class of a raw function code
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FunctionClass {
+    /// Function code defined by the Modbus specification
+    Public,
+    /// Function code in the user defined ranges `65..=72` and `100..=110`
+    UserDefined,
+    /// Function code neither public nor user defined
+    Reserved,
+    /// Function code of an exception response
+    Exception,
+}
+
 impl From<FunctionCode> for u8 {
     fn from(value: FunctionCode) -> Self {
         match value {
@@ -40,6 +72,7 @@ impl Debug for FunctionCode {
 /// Public Modbus function codes
 #[repr(u8)]
 #[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PublicFunctionCode {
     ReadCoils = 0x01,
     ReadDiscreteInputs = 0x02,
@@ -91,6 +124,21 @@ impl TryFrom<u8> for PublicFunctionCode {
     }
 }
 
+impl PublicFunctionCode {
+    /// Whether the function only writes, the functions a request to all slaves may carry
+    pub fn is_write(self) -> bool {
+        matches!(
+            self,
+            Self::WriteSingleCoil
+                | Self::WriteSingleRegister
+                | Self::WriteMultipleCoils
+                | Self::WriteMultipleRegisters
+                | Self::WriteFileRecord
+                | Self::MaskWriteRegister
+        )
+    }
+}
+
 impl From<PublicFunctionCode> for u8 {
     fn from(value: PublicFunctionCode) -> Self {
         value as u8
@@ -106,6 +154,7 @@ impl fmt::Debug for PublicFunctionCode {
 /// Modbus exception code
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExceptionCode {
     IllegalFunction = 0x01,
     IllegalDataAddress = 0x02,
@@ -144,6 +193,78 @@ impl From<ExceptionCode> for u8 {
     }
 }
 
+impl ExceptionCode {
+    /// Description of the exception as worded in the Modbus specification
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::IllegalFunction => "Illegal Function",
+            Self::IllegalDataAddress => "Illegal Data Address",
+            Self::IllegalDataValue => "Illegal Data Value",
+            Self::ServerDeviceFailure => "Server Device Failure",
+            Self::Acknowledge => "Acknowledge",
+            Self::ServerDeviceBusy => "Server Device Busy",
+            Self::MemoryParityError => "Memory Parity Error",
+            Self::GatewayPathUnavailable => "Gateway Path Unavailable",
+            Self::GatewayTargetDeviceFailedToRespond => "Gateway Target Device Failed to Respond",
+            Self::__Unknown => "Unknown exception",
+        }
+    }
+}
+
+impl Display for ExceptionCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Modbus diagnostics sub-function code
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagnosticSubFunction {
+    ReturnQueryData = 0x0000,
+    RestartCommunicationsOption = 0x0001,
+    ForceListenOnlyMode = 0x0004,
+    ClearCountersAndDiagnosticRegister = 0x000A,
+    ReturnBusMessageCount = 0x000B,
+    ReturnBusCommunicationErrorCount = 0x000C,
+    ReturnBusExceptionErrorCount = 0x000D,
+    ReturnServerMessageCount = 0x000E,
+    ReturnServerNoResponseCount = 0x000F,
+    ReturnServerNakCount = 0x0010,
+    ReturnServerBusyCount = 0x0011,
+    ReturnBusCharacterOverrunCount = 0x0012,
+    ClearOverrunCounterAndFlag = 0x0014,
+}
+
+impl TryFrom<u16> for DiagnosticSubFunction {
+    type Error = ModbusPduError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0x0000 => Ok(Self::ReturnQueryData),
+            0x0001 => Ok(Self::RestartCommunicationsOption),
+            0x0004 => Ok(Self::ForceListenOnlyMode),
+            0x000A => Ok(Self::ClearCountersAndDiagnosticRegister),
+            0x000B => Ok(Self::ReturnBusMessageCount),
+            0x000C => Ok(Self::ReturnBusCommunicationErrorCount),
+            0x000D => Ok(Self::ReturnBusExceptionErrorCount),
+            0x000E => Ok(Self::ReturnServerMessageCount),
+            0x000F => Ok(Self::ReturnServerNoResponseCount),
+            0x0010 => Ok(Self::ReturnServerNakCount),
+            0x0011 => Ok(Self::ReturnServerBusyCount),
+            0x0012 => Ok(Self::ReturnBusCharacterOverrunCount),
+            0x0014 => Ok(Self::ClearOverrunCounterAndFlag),
+            _ => Err(ModbusPduError::UndefinedSubFunctionCode(value)),
+        }
+    }
+}
+
+impl From<DiagnosticSubFunction> for u16 {
+    fn from(value: DiagnosticSubFunction) -> Self {
+        value as u16
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,6 +351,21 @@ mod tests {
         assert_eq!(FunctionCode::from(0x0A), FunctionCode::UserDefined(0x0A));
     }
 
+    #[test]
+    fn test_model_code_function_code_classify() {
+        assert_eq!(FunctionCode::classify(0x03), FunctionClass::Public);
+        assert_eq!(FunctionCode::classify(0x2B), FunctionClass::Public);
+        assert_eq!(FunctionCode::classify(65), FunctionClass::UserDefined);
+        assert_eq!(FunctionCode::classify(72), FunctionClass::UserDefined);
+        assert_eq!(FunctionCode::classify(100), FunctionClass::UserDefined);
+        assert_eq!(FunctionCode::classify(110), FunctionClass::UserDefined);
+        assert_eq!(FunctionCode::classify(0x00), FunctionClass::Reserved);
+        assert_eq!(FunctionCode::classify(0x0A), FunctionClass::Reserved);
+        assert_eq!(FunctionCode::classify(73), FunctionClass::Reserved);
+        assert_eq!(FunctionCode::classify(111), FunctionClass::Reserved);
+        assert_eq!(FunctionCode::classify(0x83), FunctionClass::Exception);
+    }
+
     #[test]
     fn test_model_code_public_function_code_try_from() {
         assert_eq!(
@@ -247,4 +383,78 @@ mod tests {
         );
         assert!(ExceptionCode::try_from(0x80).is_err());
     }
+
+    /// Formats into a fixed buffer, to check `Display` without alloc
+    struct FixedWriter {
+        buf: [u8; 64],
+        len: usize,
+    }
+
+    impl FixedWriter {
+        fn format(value: impl Display) -> Self {
+            let mut writer = Self {
+                buf: [0; 64],
+                len: 0,
+            };
+            fmt::Write::write_fmt(&mut writer, format_args!("{}", value)).unwrap();
+            writer
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.buf[..self.len]).unwrap()
+        }
+    }
+
+    impl fmt::Write for FixedWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.buf
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_model_code_exception_code_display() {
+        assert_eq!(
+            FixedWriter::format(ExceptionCode::IllegalDataAddress).as_str(),
+            "Illegal Data Address"
+        );
+        assert_eq!(
+            ExceptionCode::ServerDeviceBusy.description(),
+            "Server Device Busy"
+        );
+        assert_eq!(
+            FixedWriter::format(ExceptionCode::__Unknown).as_str(),
+            "Unknown exception"
+        );
+        assert_eq!(
+            FixedWriter::format(crate::error::ModbusError::Exception(
+                ExceptionCode::IllegalFunction
+            ))
+            .as_str(),
+            "Modbus exception response: Illegal Function (0x01)"
+        );
+    }
+
+    #[test]
+    fn test_model_code_diagnostic_sub_function_try_from() {
+        assert_eq!(
+            DiagnosticSubFunction::try_from(0x0001).unwrap(),
+            DiagnosticSubFunction::RestartCommunicationsOption
+        );
+        assert_eq!(
+            DiagnosticSubFunction::try_from(0x0012).unwrap(),
+            DiagnosticSubFunction::ReturnBusCharacterOverrunCount
+        );
+        assert!(DiagnosticSubFunction::try_from(0x0013).is_err());
+        assert_eq!(
+            DiagnosticSubFunction::try_from(0x0014).unwrap(),
+            DiagnosticSubFunction::ClearOverrunCounterAndFlag
+        );
+        assert!(DiagnosticSubFunction::try_from(0xFFFF).is_err());
+    }
 }
@@ -1,9 +1,20 @@
-use crate::{error::ModbusPduError, lib::*};
+use crate::{
+    error::{ModbusFrameError, ModbusPduError, ResponseError},
+    lib::*,
+};
 
-use super::{fcode::PublicFunctionCode, Pdu};
+use super::{
+    fcode::{ExceptionCode, PublicFunctionCode, EXCEPTION_FLAG},
+    types, Pdu,
+};
 
 pub mod request;
 pub mod response;
+#[cfg(feature = "serde")]
+mod serialize;
+
+/// MEI type of Read Device Identification in an Encapsulated Interface Transport PDU
+pub const READ_DEVICE_IDENTIFICATION_MEI_TYPE: u8 = 0x0E;
 
 /// Modbus request implementation
 #[derive(Clone, PartialEq)]
@@ -25,6 +36,16 @@ impl<T> Request<T> {
     pub fn into_inner(self) -> Pdu {
         self.inner
     }
+
+    pub fn as_pdu(&self) -> &Pdu {
+        &self.inner
+    }
+}
+
+impl<T> From<Request<T>> for Pdu {
+    fn from(request: Request<T>) -> Self {
+        request.inner
+    }
 }
 
 /// Modbus response implementation
@@ -47,10 +68,24 @@ impl<T> Response<T> {
     pub fn into_inner(self) -> Pdu {
         self.inner
     }
+
+    pub fn as_pdu(&self) -> &Pdu {
+        &self.inner
+    }
+
+    /// Wire bytes of the response PDU, function code followed by data
+    pub fn raw_bytes(&self) -> &[u8] {
+        self.inner.as_slice()
+    }
 }
 
 pub trait PublicFunction {
     fn function_code() -> PublicFunctionCode;
+
+    /// Check the data of a response to this function for consistency
+    fn check_response(_pdu: &Pdu) -> Result<(), ModbusPduError> {
+        Ok(())
+    }
 }
 
 impl<T: PublicFunction> TryFrom<Pdu> for Request<T> {
@@ -66,11 +101,14 @@ impl<T: PublicFunction> TryFrom<Pdu> for Request<T> {
     }
 }
 
+/// Checks the function code and, for the read functions, that the byte count matches the
+/// data. Use `parse` to accept a truncated response.
 impl<T: PublicFunction> TryFrom<Pdu> for Response<T> {
     type Error = ModbusPduError;
 
     fn try_from(value: Pdu) -> Result<Self, Self::Error> {
         check_function_code(&value, T::function_code() as u8)?;
+        T::check_response(&value)?;
 
         Ok(Self {
             inner: value,
@@ -79,6 +117,31 @@ impl<T: PublicFunction> TryFrom<Pdu> for Response<T> {
     }
 }
 
+impl<T: PublicFunction> Response<T> {
+    /// Decode a response, telling an exception response to this function from any other
+    /// unexpected response
+    pub fn parse(pdu: Pdu) -> Result<Self, ResponseError> {
+        let function_code = T::function_code() as u8;
+
+        match pdu.function_code() {
+            Some(code) if code == function_code => Ok(Self {
+                inner: pdu,
+                _marker: PhantomData,
+            }),
+            Some(code) if code == function_code | EXCEPTION_FLAG => {
+                let exception_code = pdu
+                    .data()
+                    .first()
+                    .and_then(|code| ExceptionCode::try_from(*code).ok())
+                    .unwrap_or(ExceptionCode::__Unknown);
+
+                Err(ResponseError::Exception(exception_code))
+            }
+            _ => Err(ResponseError::WrongFunction),
+        }
+    }
+}
+
 impl TryFrom<(Pdu, u8)> for Response<UserDefined> {
     type Error = ModbusPduError;
 
@@ -92,6 +155,54 @@ impl TryFrom<(Pdu, u8)> for Response<UserDefined> {
     }
 }
 
+/// Check that the leading byte count covers exactly the rest of the data, in whole `unit`s
+fn check_byte_count(pdu: &Pdu, unit: usize) -> Result<(), ModbusPduError> {
+    match pdu.data().split_first() {
+        Some((&byte_count, values))
+            if byte_count as usize == values.len() && values.len() % unit == 0 =>
+        {
+            Ok(())
+        }
+        _ => Err(ModbusPduError::OutOfRange),
+    }
+}
+
+/// Build the PDU shared by a Write File Record request and its echo
+fn write_file_record_pdu(records: &[types::FileRecord]) -> Result<Pdu, ModbusFrameError> {
+    if records.is_empty() {
+        return Err(ModbusPduError::OutOfRange.into());
+    }
+
+    let mut byte_count = 0usize;
+    for record in records {
+        if record.reference_type != types::FILE_RECORD_REFERENCE_TYPE
+            || record.file_number == 0
+            || record.record_number > 0x270F
+            || record.register_values.is_empty()
+        {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+        byte_count += 7 + record.register_values.len() * 2;
+    }
+    if byte_count > 0xF5 {
+        return Err(ModbusPduError::OutOfRange.into());
+    }
+
+    let mut pdu = Pdu::new(PublicFunctionCode::WriteFileRecord.into())?;
+    pdu.put_u8(byte_count as u8)?;
+    for record in records {
+        pdu.put_u8(record.reference_type)?;
+        pdu.put_u16(record.file_number)?;
+        pdu.put_u16(record.record_number)?;
+        pdu.put_u16(record.register_values.len() as u16)?;
+        for value in record.register_values {
+            pdu.put_u16(*value)?;
+        }
+    }
+
+    Ok(pdu)
+}
+
 fn check_function_code(pdu: &Pdu, function_code: u8) -> Result<(), ModbusPduError> {
     if pdu.function_code() != Some(function_code) {
         Err(ModbusPduError::UnexpectedCode(function_code))
@@ -119,6 +230,10 @@ impl PublicFunction for ReadCoils {
     fn function_code() -> PublicFunctionCode {
         PublicFunctionCode::ReadCoils
     }
+
+    fn check_response(pdu: &Pdu) -> Result<(), ModbusPduError> {
+        check_byte_count(pdu, 1)
+    }
 }
 
 /// Read Discrete Inputs
@@ -140,6 +255,10 @@ impl PublicFunction for ReadDiscreteInputs {
     fn function_code() -> PublicFunctionCode {
         PublicFunctionCode::ReadDiscreteInputs
     }
+
+    fn check_response(pdu: &Pdu) -> Result<(), ModbusPduError> {
+        check_byte_count(pdu, 1)
+    }
 }
 
 /// Read Holding Registers
@@ -161,6 +280,10 @@ impl PublicFunction for ReadHoldingRegisters {
     fn function_code() -> PublicFunctionCode {
         PublicFunctionCode::ReadHoldingRegisters
     }
+
+    fn check_response(pdu: &Pdu) -> Result<(), ModbusPduError> {
+        check_byte_count(pdu, 2)
+    }
 }
 
 /// Read Input Registers
@@ -182,6 +305,10 @@ impl PublicFunction for ReadInputRegisters {
     fn function_code() -> PublicFunctionCode {
         PublicFunctionCode::ReadInputRegisters
     }
+
+    fn check_response(pdu: &Pdu) -> Result<(), ModbusPduError> {
+        check_byte_count(pdu, 2)
+    }
 }
 
 /// Write Single Coil
@@ -226,6 +353,245 @@ impl PublicFunction for WriteSingleRegister {
     }
 }
 
+/// Read Exception Status
+///
+/// This function code is used to read the contents of eight Exception Status outputs in a remote device.
+///
+/// # Code
+/// * Function Code : `0x07`
+/// # Request
+/// * None
+/// # Response
+/// * Output Data : `u8`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadExceptionStatus;
+
+impl PublicFunction for ReadExceptionStatus {
+    fn function_code() -> PublicFunctionCode {
+        PublicFunctionCode::ReadExceptionStatus
+    }
+}
+
+/// Diagnostics
+///
+/// This function code is used to provide a series of tests for checking the communication system between a client device and a server, or for checking various internal error conditions within a server.
+///
+/// # Code
+/// * Function Code : `0x08`
+/// # Request
+/// * Sub-function : `u16`
+/// * Data : `u16`
+/// # Response
+/// * Sub-function : `u16`
+/// * Data : `u16`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics;
+
+impl PublicFunction for Diagnostics {
+    fn function_code() -> PublicFunctionCode {
+        PublicFunctionCode::Diagnostics
+    }
+}
+
+/// Get Comm Event Counter
+///
+/// This function code is used to get a status word and an event count from the remote device's communication event counter.
+///
+/// # Code
+/// * Function Code : `0x0B`
+/// # Request
+/// * None
+/// # Response
+/// * Status : `u16`
+/// * Event Count : `u16`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetCommEventCounter;
+
+impl PublicFunction for GetCommEventCounter {
+    fn function_code() -> PublicFunctionCode {
+        PublicFunctionCode::GetCommEventCounter
+    }
+}
+
+/// Write Multiple Coils
+///
+/// This function code is used to force each coil in a sequence of coils to either ON or OFF in a remote device.
+///
+/// # Code
+/// * Function Code : `0x0F`
+/// # Request
+/// * Starting Address : `u16`
+/// * Quantity of Outputs : `u16`
+/// * Byte Count : `u8`
+/// * Outputs Value : `[u8; N]`
+/// # Response
+/// * Starting Address : `u16`
+/// * Quantity of Outputs : `u16`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteMultipleCoils;
+
+impl PublicFunction for WriteMultipleCoils {
+    fn function_code() -> PublicFunctionCode {
+        PublicFunctionCode::WriteMultipleCoils
+    }
+}
+
+/// Write Multiple Registers
+///
+/// This function code is used to write a block of contiguous registers (1 to 123 registers) in a remote device.
+///
+/// # Code
+/// * Function Code : `0x10`
+/// # Request
+/// * Starting Address : `u16`
+/// * Quantity of Registers : `u16`
+/// * Byte Count : `u8`
+/// * Registers Value : `[u16; N]`
+/// # Response
+/// * Starting Address : `u16`
+/// * Quantity of Registers : `u16`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteMultipleRegisters;
+
+impl PublicFunction for WriteMultipleRegisters {
+    fn function_code() -> PublicFunctionCode {
+        PublicFunctionCode::WriteMultipleRegisters
+    }
+}
+
+/// Report Server ID
+///
+/// This function code is used to read the description of the type, the current status, and other information specific to a remote device.
+///
+/// # Code
+/// * Function Code : `0x11`
+/// # Request
+/// * None
+/// # Response
+/// * Byte Count : `u8`
+/// * Server ID : device specific
+/// * Run Indicator Status : `u8` (`0x00` = OFF, `0xFF` = ON)
+/// * Additional Data : `[u8; N]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportServerId;
+
+impl PublicFunction for ReportServerId {
+    fn function_code() -> PublicFunctionCode {
+        PublicFunctionCode::ReportServerId
+    }
+}
+
+/// Mask Write Register
+///
+/// This function code is used to modify the contents of a specified holding register using a combination of an AND mask, an OR mask, and the register's current contents.
+///
+/// # Code
+/// * Function Code : `0x16`
+/// # Request
+/// * Reference Address : `u16`
+/// * And_Mask : `u16`
+/// * Or_Mask : `u16`
+/// # Response
+/// * Reference Address : `u16`
+/// * And_Mask : `u16`
+/// * Or_Mask : `u16`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaskWriteRegister;
+
+impl PublicFunction for MaskWriteRegister {
+    fn function_code() -> PublicFunctionCode {
+        PublicFunctionCode::MaskWriteRegister
+    }
+}
+
+/// Read/Write Multiple Registers
+///
+/// This function code performs a combination of one read operation and one write operation in a single MODBUS transaction. The write operation is performed before the read.
+///
+/// # Code
+/// * Function Code : `0x17`
+/// # Request
+/// * Read Starting Address : `u16`
+/// * Quantity to Read : `u16`
+/// * Write Starting Address : `u16`
+/// * Quantity to Write : `u16`
+/// * Write Byte Count : `u8`
+/// * Write Registers Value : `[u16; N]`
+/// # Response
+/// * Byte Count : `u8`
+/// * Read Registers Value : `[u16; N]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadWriteMultipleRegisters;
+
+impl PublicFunction for ReadWriteMultipleRegisters {
+    fn function_code() -> PublicFunctionCode {
+        PublicFunctionCode::ReadWriteMultipleRegisters
+    }
+
+    fn check_response(pdu: &Pdu) -> Result<(), ModbusPduError> {
+        check_byte_count(pdu, 2)
+    }
+}
+
+/// Write File Record
+///
+/// This function code is used to perform a file record write. All Request Data Lengths are provided in terms of number of bytes and all Record Lengths are provided in terms of the number of 16-bit words.
+///
+/// # Code
+/// * Function Code : `0x15`
+/// # Request
+/// * Request Data Length : `u8`
+/// * Sub-Requests : `[(Reference Type, File Number, Record Number, Record Length, Record Data); N]`
+/// # Response
+/// * Echo of the request
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteFileRecord;
+
+impl PublicFunction for WriteFileRecord {
+    fn function_code() -> PublicFunctionCode {
+        PublicFunctionCode::WriteFileRecord
+    }
+
+    fn check_response(pdu: &Pdu) -> Result<(), ModbusPduError> {
+        check_byte_count(pdu, 1)
+    }
+}
+
+/// Read Device Identification
+///
+/// This function code is used to read the identification and additional information relative to the physical and functional description of a remote device. It is carried by the Encapsulated Interface Transport with MEI type `0x0E`.
+///
+/// # Code
+/// * Function Code : `0x2B`
+/// * MEI Type : `0x0E`
+/// # Request
+/// * Read Device ID Code : `u8`
+/// * Object Id : `u8`
+/// # Response
+/// * Read Device ID Code : `u8`
+/// * Conformity Level : `u8`
+/// * More Follows : `u8`
+/// * Next Object Id : `u8`
+/// * Number of Objects : `u8`
+/// * Objects : `[(Object Id, Object Length, Object Value); N]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadDeviceIdentification;
+
+impl PublicFunction for ReadDeviceIdentification {
+    fn function_code() -> PublicFunctionCode {
+        PublicFunctionCode::EncapsulatedInterfaceTransport
+    }
+
+    fn check_response(pdu: &Pdu) -> Result<(), ModbusPduError> {
+        match pdu.read_u8(0) {
+            Some(READ_DEVICE_IDENTIFICATION_MEI_TYPE) => Ok(()),
+            _ => Err(ModbusPduError::UnexpectedCode(
+                READ_DEVICE_IDENTIFICATION_MEI_TYPE,
+            )),
+        }
+    }
+}
+
 /// User Defined
 ///
 /// This function code is used to define user defined function code.
@@ -1,5 +1,14 @@
+use core::ops::RangeInclusive;
+
 use super::*;
-use crate::error::ModbusFrameError;
+use crate::error::{ModbusFrameError, ModbusPduError};
+use crate::frame::pdu::fcode::{FunctionClass, FunctionCode};
+use crate::frame::pdu::types::{
+    pack_bits, BitSet, CoilBits, FileRecord, FileSubRecords, RegisterSlice, WordOrder,
+};
+
+/// Holding register reference numbers in 5-digit (`4xxxx`) and 6-digit (`4xxxxx`) notation
+const HOLDING_REGISTER_REFERENCES: [RangeInclusive<u32>; 2] = [40001..=49999, 400001..=465536];
 
 /// Read Coils
 /// ## Code
@@ -112,6 +121,27 @@ impl Request<ReadHoldingRegisters> {
         })
     }
 
+    /// Build a request from an inclusive range of holding register reference numbers
+    ///
+    /// e.g. `40001..=40010` reads 10 registers starting at address `0x0000`.
+    /// Both references must use the same notation.
+    pub fn for_references(start_ref: u32, end_ref: u32) -> Result<Self, ModbusFrameError> {
+        let (start_notation, starting_address) =
+            holding_register_address(start_ref).ok_or(ModbusPduError::OutOfRange)?;
+        let (end_notation, end_address) =
+            holding_register_address(end_ref).ok_or(ModbusPduError::OutOfRange)?;
+
+        if start_notation != end_notation || end_address < starting_address {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+
+        let quantity_of_registers = (end_address - starting_address)
+            .checked_add(1)
+            .ok_or(ModbusPduError::OutOfRange)?;
+
+        Self::new(starting_address, quantity_of_registers)
+    }
+
     pub fn starting_address(&self) -> Option<u16> {
         self.inner.read_u16(0)
     }
@@ -205,6 +235,11 @@ impl Request<WriteSingleCoil> {
     pub fn output_value(&self) -> Option<bool> {
         self.inner.read_u16(2).map(|v| v == 0xFF00)
     }
+
+    /// Whether `response` echoes this request, i.e. the write was applied as requested
+    pub fn verify_echo(&self, response: &Response<WriteSingleCoil>) -> bool {
+        self.inner == response.inner
+    }
 }
 
 impl Display for Request<WriteSingleCoil> {
@@ -243,6 +278,11 @@ impl Request<WriteSingleRegister> {
     pub fn register_value(&self) -> Option<u16> {
         self.inner.read_u16(2)
     }
+
+    /// Whether `response` echoes this request, i.e. the write was applied as requested
+    pub fn verify_echo(&self, response: &Response<WriteSingleRegister>) -> bool {
+        self.inner == response.inner
+    }
 }
 
 impl Display for Request<WriteSingleRegister> {
@@ -254,6 +294,686 @@ impl Display for Request<WriteSingleRegister> {
     }
 }
 
+/// Read Exception Status
+/// ## Code
+/// * Function Code : `0x07`
+/// ## Data fields
+/// * None
+pub type ReadExceptionStatusRequest = Request<ReadExceptionStatus>;
+
+impl Request<ReadExceptionStatus> {
+    pub fn new() -> Result<Self, ModbusFrameError> {
+        let pdu = Pdu::new(PublicFunctionCode::ReadExceptionStatus.into())?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl Display for Request<ReadExceptionStatus> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request<ReadExceptionStatus>").finish()
+    }
+}
+
+/// Diagnostics
+/// ## Code
+/// * Function Code : `0x08`
+/// ## Data fields
+/// * Sub-function : `u16`
+/// * Data : `u16`
+pub type DiagnosticsRequest = Request<Diagnostics>;
+
+impl Request<Diagnostics> {
+    pub fn new(sub_function: u16, data: u16) -> Result<Self, ModbusFrameError> {
+        let mut pdu = Pdu::new(PublicFunctionCode::Diagnostics.into())?;
+        pdu.put_u16(sub_function)?;
+        pdu.put_u16(data)?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn sub_function(&self) -> Option<u16> {
+        self.inner.read_u16(0)
+    }
+
+    pub fn data(&self) -> Option<u16> {
+        self.inner.read_u16(2)
+    }
+}
+
+impl Display for Request<Diagnostics> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request<Diagnostics>")
+            .field("sub_function", &self.sub_function())
+            .field("data", &self.data())
+            .finish()
+    }
+}
+
+/// Get Comm Event Counter
+/// ## Code
+/// * Function Code : `0x0B`
+/// ## Data fields
+/// * None
+pub type GetCommEventCounterRequest = Request<GetCommEventCounter>;
+
+impl Request<GetCommEventCounter> {
+    pub fn new() -> Result<Self, ModbusFrameError> {
+        let pdu = Pdu::new(PublicFunctionCode::GetCommEventCounter.into())?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl Display for Request<GetCommEventCounter> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request<GetCommEventCounter>").finish()
+    }
+}
+
+/// Write Multiple Coils
+/// ## Code
+/// * Function Code : `0x0F`
+/// ## Data fields
+/// * Starting Address : `u16`
+/// * Quantity of Outputs : `u16`
+/// * Byte Count : `N / 8 (+ 1)`
+/// * Outputs Value : `[bool; N]`
+pub type WriteMultipleCoilsRequest = Request<WriteMultipleCoils>;
+
+impl Request<WriteMultipleCoils> {
+    /// Write `coils` from `starting_address` on, packed LSB first
+    pub fn new(starting_address: u16, coils: &[bool]) -> Result<Self, ModbusFrameError> {
+        if !(1..=1968).contains(&coils.len()) {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+
+        let mut pdu = Pdu::new(PublicFunctionCode::WriteMultipleCoils.into())?;
+        pdu.put_u16(starting_address)?;
+        pdu.put_u16(coils.len() as u16)?;
+        pdu.put_u8(coils.len().div_ceil(8) as u8)?;
+        for byte in pack_bits(coils) {
+            pdu.put_u8(byte)?;
+        }
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Write the coils of `coils` from `starting_address` on
+    pub fn from_coil_bits<const N: usize>(
+        starting_address: u16,
+        coils: &CoilBits<N>,
+    ) -> Result<Self, ModbusFrameError> {
+        Self::new(starting_address, coils.as_slice())
+    }
+
+    /// Write the low `count` bits of `mask` as coils, LSB to the first coil
+    pub fn from_bitmask(
+        starting_address: u16,
+        mask: u32,
+        count: u8,
+    ) -> Result<Self, ModbusFrameError> {
+        if !(1..=32).contains(&count) {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+
+        let byte_count = (count as usize).div_ceil(8);
+        let mask = if count < 32 {
+            mask & ((1 << count) - 1)
+        } else {
+            mask
+        };
+
+        let mut pdu = Pdu::new(PublicFunctionCode::WriteMultipleCoils.into())?;
+        pdu.put_u16(starting_address)?;
+        pdu.put_u16(count as u16)?;
+        pdu.put_u8(byte_count as u8)?;
+        pdu.put_slice(&mask.to_le_bytes()[..byte_count])?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn starting_address(&self) -> Option<u16> {
+        self.inner.read_u16(0)
+    }
+
+    pub fn quantity_of_outputs(&self) -> Option<u16> {
+        self.inner.read_u16(2)
+    }
+
+    pub fn byte_count(&self) -> Option<u8> {
+        self.inner.read_u8(4)
+    }
+
+    pub fn outputs_value(&self) -> Option<BitSet<'_>> {
+        let byte_count = self.byte_count()? as usize;
+        let outputs_value = self.inner.try_data_slice(5, byte_count)?;
+
+        Some(BitSet::new(outputs_value))
+    }
+
+    /// Whether `response` echoes the starting address and quantity of this request
+    pub fn verify_echo(&self, response: &Response<WriteMultipleCoils>) -> bool {
+        self.starting_address().is_some()
+            && response.starting_address() == self.starting_address()
+            && response.quantity_of_outputs() == self.quantity_of_outputs()
+    }
+}
+
+#[cfg(any(feature = "alloc", feature = "std"))]
+impl TryFrom<(u16, Vec<bool>)> for Request<WriteMultipleCoils> {
+    type Error = ModbusFrameError;
+
+    fn try_from((starting_address, coils): (u16, Vec<bool>)) -> Result<Self, Self::Error> {
+        Self::new(starting_address, &coils)
+    }
+}
+
+impl Display for Request<WriteMultipleCoils> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request<WriteMultipleCoils>")
+            .field("starting_address", &self.starting_address())
+            .field("quantity_of_outputs", &self.quantity_of_outputs())
+            .field("byte_count", &self.byte_count())
+            .field("outputs_value", &self.outputs_value())
+            .finish()
+    }
+}
+
+/// Write Multiple Registers
+/// ## Code
+/// * Function Code : `0x10`
+/// ## Data fields
+/// * Starting Address : `u16`
+/// * Quantity of Registers : `u16`
+/// * Byte Count : `N * 2`
+/// * Registers Value : `[u16; N]`
+pub type WriteMultipleRegistersRequest = Request<WriteMultipleRegisters>;
+
+impl Request<WriteMultipleRegisters> {
+    pub fn new(starting_address: u16, values: &[u16]) -> Result<Self, ModbusFrameError> {
+        if !(1..=123).contains(&values.len()) {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+
+        let mut pdu = Pdu::new(PublicFunctionCode::WriteMultipleRegisters.into())?;
+        pdu.put_u16(starting_address)?;
+        pdu.put_u16(values.len() as u16)?;
+        pdu.put_u8(values.len() as u8 * 2)?;
+        for value in values {
+            pdu.put_u16(*value)?;
+        }
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Create a request writing each `f32` into two registers in the given word order
+    pub fn from_f32(
+        starting_address: u16,
+        values: &[f32],
+        order: WordOrder,
+    ) -> Result<Self, ModbusFrameError> {
+        if !(1..=61).contains(&values.len()) {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+
+        let mut pdu = Pdu::new(PublicFunctionCode::WriteMultipleRegisters.into())?;
+        pdu.put_u16(starting_address)?;
+        pdu.put_u16(values.len() as u16 * 2)?;
+        pdu.put_u8(values.len() as u8 * 4)?;
+        for value in values {
+            for register in order.to_registers_f32(*value) {
+                pdu.put_u16(register)?;
+            }
+        }
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn starting_address(&self) -> Option<u16> {
+        self.inner.read_u16(0)
+    }
+
+    pub fn quantity_of_registers(&self) -> Option<u16> {
+        self.inner.read_u16(2)
+    }
+
+    pub fn byte_count(&self) -> Option<u8> {
+        self.inner.read_u8(4)
+    }
+
+    pub fn registers_value(&self) -> Option<RegisterSlice<'_>> {
+        let byte_count = self.byte_count()? as usize;
+        let registers_value = self.inner.try_data_slice(5, byte_count)?;
+
+        Some(RegisterSlice::new(registers_value))
+    }
+
+    pub fn register_value(&self, index: usize) -> Option<u16> {
+        if index >= self.quantity_of_registers()? as usize {
+            return None;
+        }
+
+        let start = 5 + index * 2;
+        let value = self.inner.try_data_slice(start, 2)?;
+
+        Some(u16::from_be_bytes([value[0], value[1]]))
+    }
+
+    /// Whether `response` echoes the starting address and quantity of this request
+    pub fn verify_echo(&self, response: &Response<WriteMultipleRegisters>) -> bool {
+        self.starting_address().is_some()
+            && response.starting_address() == self.starting_address()
+            && response.quantity_of_registers() == self.quantity_of_registers()
+    }
+}
+
+#[cfg(any(feature = "alloc", feature = "std"))]
+impl TryFrom<(u16, Vec<u16>)> for Request<WriteMultipleRegisters> {
+    type Error = ModbusFrameError;
+
+    fn try_from((starting_address, values): (u16, Vec<u16>)) -> Result<Self, Self::Error> {
+        Self::new(starting_address, &values)
+    }
+}
+
+impl Display for Request<WriteMultipleRegisters> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request<WriteMultipleRegisters>")
+            .field("starting_address", &self.starting_address())
+            .field("quantity_of_registers", &self.quantity_of_registers())
+            .field("byte_count", &self.byte_count())
+            .field("registers_value", &self.registers_value())
+            .finish()
+    }
+}
+
+/// Report Server ID
+/// ## Code
+/// * Function Code : `0x11`
+/// ## Data fields
+/// * None
+pub type ReportServerIdRequest = Request<ReportServerId>;
+
+impl Request<ReportServerId> {
+    pub fn new() -> Result<Self, ModbusFrameError> {
+        let pdu = Pdu::new(PublicFunctionCode::ReportServerId.into())?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl Display for Request<ReportServerId> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request<ReportServerId>").finish()
+    }
+}
+
+/// Mask Write Register
+/// ## Code
+/// * Function Code : `0x16`
+/// ## Data fields
+/// * Reference Address : `u16`
+/// * And_Mask : `u16`
+/// * Or_Mask : `u16`
+pub type MaskWriteRegisterRequest = Request<MaskWriteRegister>;
+
+impl Request<MaskWriteRegister> {
+    pub fn new(
+        reference_address: u16,
+        and_mask: u16,
+        or_mask: u16,
+    ) -> Result<Self, ModbusFrameError> {
+        let mut pdu = Pdu::new(PublicFunctionCode::MaskWriteRegister.into())?;
+        pdu.put_u16(reference_address)?;
+        pdu.put_u16(and_mask)?;
+        pdu.put_u16(or_mask)?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn reference_address(&self) -> Option<u16> {
+        self.inner.read_u16(0)
+    }
+
+    pub fn and_mask(&self) -> Option<u16> {
+        self.inner.read_u16(2)
+    }
+
+    pub fn or_mask(&self) -> Option<u16> {
+        self.inner.read_u16(4)
+    }
+
+    /// Register value resulting from applying the masks to `current`
+    ///
+    /// `(current AND And_Mask) OR (Or_Mask AND (NOT And_Mask))`
+    pub fn apply_mask(&self, current: u16) -> Option<u16> {
+        let and_mask = self.and_mask()?;
+        let or_mask = self.or_mask()?;
+
+        Some((current & and_mask) | (or_mask & !and_mask))
+    }
+
+    /// Whether `response` echoes this request, i.e. the write was applied as requested
+    pub fn verify_echo(&self, response: &Response<MaskWriteRegister>) -> bool {
+        self.inner == response.inner
+    }
+}
+
+impl Display for Request<MaskWriteRegister> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request<MaskWriteRegister>")
+            .field("reference_address", &self.reference_address())
+            .field("and_mask", &self.and_mask())
+            .field("or_mask", &self.or_mask())
+            .finish()
+    }
+}
+
+/// Read/Write Multiple Registers
+/// ## Code
+/// * Function Code : `0x17`
+/// ## Data fields
+/// * Read Starting Address : `u16`
+/// * Quantity to Read : `u16`
+/// * Write Starting Address : `u16`
+/// * Quantity to Write : `u16`
+/// * Write Byte Count : `N * 2`
+/// * Write Registers Value : `[u16; N]`
+pub type ReadWriteMultipleRegistersRequest = Request<ReadWriteMultipleRegisters>;
+
+impl Request<ReadWriteMultipleRegisters> {
+    pub fn new(
+        read_starting_address: u16,
+        quantity_to_read: u16,
+        write_starting_address: u16,
+        write_registers_value: &[u16],
+    ) -> Result<Self, ModbusFrameError> {
+        if !(1..=125).contains(&quantity_to_read)
+            || !(1..=121).contains(&write_registers_value.len())
+        {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+
+        let mut pdu = Pdu::new(PublicFunctionCode::ReadWriteMultipleRegisters.into())?;
+        pdu.put_u16(read_starting_address)?;
+        pdu.put_u16(quantity_to_read)?;
+        pdu.put_u16(write_starting_address)?;
+        pdu.put_u16(write_registers_value.len() as u16)?;
+        pdu.put_u8(write_registers_value.len() as u8 * 2)?;
+        for value in write_registers_value {
+            pdu.put_u16(*value)?;
+        }
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn read_starting_address(&self) -> Option<u16> {
+        self.inner.read_u16(0)
+    }
+
+    pub fn quantity_to_read(&self) -> Option<u16> {
+        self.inner.read_u16(2)
+    }
+
+    pub fn write_starting_address(&self) -> Option<u16> {
+        self.inner.read_u16(4)
+    }
+
+    pub fn quantity_to_write(&self) -> Option<u16> {
+        self.inner.read_u16(6)
+    }
+
+    pub fn write_byte_count(&self) -> Option<u8> {
+        self.inner.read_u8(8)
+    }
+
+    pub fn write_registers_value(&self) -> Option<RegisterSlice<'_>> {
+        let byte_count = self.write_byte_count()? as usize;
+        let write_registers_value = self.inner.try_data_slice(9, byte_count)?;
+
+        Some(RegisterSlice::new(write_registers_value))
+    }
+}
+
+impl Display for Request<ReadWriteMultipleRegisters> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request<ReadWriteMultipleRegisters>")
+            .field("read_starting_address", &self.read_starting_address())
+            .field("quantity_to_read", &self.quantity_to_read())
+            .field("write_starting_address", &self.write_starting_address())
+            .field("quantity_to_write", &self.quantity_to_write())
+            .field("write_byte_count", &self.write_byte_count())
+            .field("write_registers_value", &self.write_registers_value())
+            .finish()
+    }
+}
+
+/// Write File Record
+/// ## Code
+/// * Function Code : `0x15`
+/// ## Data fields
+/// * Request Data Length : `u8`
+/// * Sub-Requests : `[(u8, u16, u16, u16, [u16; N]); M]`
+pub type WriteFileRecordRequest = Request<WriteFileRecord>;
+
+impl Request<WriteFileRecord> {
+    /// Write each of `records`, 7 + 2 * N bytes apiece and up to 245 bytes in total
+    pub fn new(records: &[FileRecord]) -> Result<Self, ModbusFrameError> {
+        Ok(Self {
+            inner: write_file_record_pdu(records)?,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn request_data_length(&self) -> Option<u8> {
+        self.inner.read_u8(0)
+    }
+
+    pub fn sub_requests(&self) -> Option<FileSubRecords<'_>> {
+        let byte_count = self.request_data_length()? as usize;
+        let sub_requests = self.inner.try_data_slice(1, byte_count)?;
+
+        Some(FileSubRecords::new(sub_requests))
+    }
+
+    /// Whether `response` echoes this request, i.e. the write was applied as requested
+    pub fn verify_echo(&self, response: &Response<WriteFileRecord>) -> bool {
+        self.inner == response.inner
+    }
+}
+
+impl Display for Request<WriteFileRecord> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request<WriteFileRecord>")
+            .field("request_data_length", &self.request_data_length())
+            .field("sub_requests", &self.sub_requests())
+            .finish()
+    }
+}
+
+/// Read Device Identification
+/// ## Code
+/// * Function Code : `0x2B`
+/// ## Data fields
+/// * MEI Type : `0x0E`
+/// * Read Device ID Code : `u8` (`0x01` basic, `0x02` regular, `0x03` extended, `0x04` specific)
+/// * Object Id : `u8`
+pub type ReadDeviceIdentificationRequest = Request<ReadDeviceIdentification>;
+
+impl Request<ReadDeviceIdentification> {
+    pub fn new(read_device_id_code: u8, object_id: u8) -> Result<Self, ModbusFrameError> {
+        if !(0x01..=0x04).contains(&read_device_id_code) {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+
+        let mut pdu = Pdu::new(PublicFunctionCode::EncapsulatedInterfaceTransport.into())?;
+        pdu.put_u8(READ_DEVICE_IDENTIFICATION_MEI_TYPE)?;
+        pdu.put_u8(read_device_id_code)?;
+        pdu.put_u8(object_id)?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn mei_type(&self) -> Option<u8> {
+        self.inner.data().first().copied()
+    }
+
+    pub fn read_device_id_code(&self) -> Option<u8> {
+        self.inner.data().get(1).copied()
+    }
+
+    pub fn object_id(&self) -> Option<u8> {
+        self.inner.data().get(2).copied()
+    }
+}
+
+impl Display for Request<ReadDeviceIdentification> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request<ReadDeviceIdentification>")
+            .field("mei_type", &self.mei_type())
+            .field("read_device_id_code", &self.read_device_id_code())
+            .field("object_id", &self.object_id())
+            .finish()
+    }
+}
+
+/// Request of any function, dispatched on the function code
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RequestPdu {
+    ReadCoils(ReadCoilsRequest),
+    ReadDiscreteInputs(ReadDiscreteInputsRequest),
+    ReadHoldingRegisters(ReadHoldingRegistersRequest),
+    ReadInputRegisters(ReadInputRegistersRequest),
+    WriteSingleCoil(WriteSingleCoilRequest),
+    WriteSingleRegister(WriteSingleRegisterRequest),
+    ReadExceptionStatus(ReadExceptionStatusRequest),
+    Diagnostics(DiagnosticsRequest),
+    GetCommEventCounter(GetCommEventCounterRequest),
+    WriteMultipleCoils(WriteMultipleCoilsRequest),
+    WriteMultipleRegisters(WriteMultipleRegistersRequest),
+    ReportServerId(ReportServerIdRequest),
+    MaskWriteRegister(MaskWriteRegisterRequest),
+    ReadWriteMultipleRegisters(ReadWriteMultipleRegistersRequest),
+    WriteFileRecord(WriteFileRecordRequest),
+    /// Function without a typed request
+    Other(Pdu),
+}
+
+impl From<Pdu> for RequestPdu {
+    fn from(pdu: Pdu) -> Self {
+        fn typed<T>(inner: Pdu) -> Request<T> {
+            Request {
+                inner,
+                _marker: PhantomData,
+            }
+        }
+
+        match pdu.function_code().map(PublicFunctionCode::try_from) {
+            Some(Ok(PublicFunctionCode::ReadCoils)) => Self::ReadCoils(typed(pdu)),
+            Some(Ok(PublicFunctionCode::ReadDiscreteInputs)) => {
+                Self::ReadDiscreteInputs(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::ReadHoldingRegisters)) => {
+                Self::ReadHoldingRegisters(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::ReadInputRegisters)) => {
+                Self::ReadInputRegisters(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::WriteSingleCoil)) => Self::WriteSingleCoil(typed(pdu)),
+            Some(Ok(PublicFunctionCode::WriteSingleRegister)) => {
+                Self::WriteSingleRegister(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::ReadExceptionStatus)) => {
+                Self::ReadExceptionStatus(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::Diagnostics)) => Self::Diagnostics(typed(pdu)),
+            Some(Ok(PublicFunctionCode::GetCommEventCounter)) => {
+                Self::GetCommEventCounter(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::WriteMultipleCoils)) => {
+                Self::WriteMultipleCoils(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::WriteMultipleRegisters)) => {
+                Self::WriteMultipleRegisters(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::ReportServerId)) => Self::ReportServerId(typed(pdu)),
+            Some(Ok(PublicFunctionCode::MaskWriteRegister)) => Self::MaskWriteRegister(typed(pdu)),
+            Some(Ok(PublicFunctionCode::ReadWriteMultipleRegisters)) => {
+                Self::ReadWriteMultipleRegisters(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::WriteFileRecord)) => Self::WriteFileRecord(typed(pdu)),
+            _ => Self::Other(pdu),
+        }
+    }
+}
+
+impl From<RequestPdu> for Pdu {
+    fn from(request: RequestPdu) -> Self {
+        match request {
+            RequestPdu::ReadCoils(request) => request.inner,
+            RequestPdu::ReadDiscreteInputs(request) => request.inner,
+            RequestPdu::ReadHoldingRegisters(request) => request.inner,
+            RequestPdu::ReadInputRegisters(request) => request.inner,
+            RequestPdu::WriteSingleCoil(request) => request.inner,
+            RequestPdu::WriteSingleRegister(request) => request.inner,
+            RequestPdu::ReadExceptionStatus(request) => request.inner,
+            RequestPdu::Diagnostics(request) => request.inner,
+            RequestPdu::GetCommEventCounter(request) => request.inner,
+            RequestPdu::WriteMultipleCoils(request) => request.inner,
+            RequestPdu::WriteMultipleRegisters(request) => request.inner,
+            RequestPdu::ReportServerId(request) => request.inner,
+            RequestPdu::MaskWriteRegister(request) => request.inner,
+            RequestPdu::ReadWriteMultipleRegisters(request) => request.inner,
+            RequestPdu::WriteFileRecord(request) => request.inner,
+            RequestPdu::Other(pdu) => pdu,
+        }
+    }
+}
+
+/// Convert a holding register reference number to its notation and zero-based address
+fn holding_register_address(reference: u32) -> Option<(usize, u16)> {
+    HOLDING_REGISTER_REFERENCES
+        .iter()
+        .enumerate()
+        .find(|(_, references)| references.contains(&reference))
+        .map(|(notation, references)| (notation, (reference - references.start()) as u16))
+}
+
 /// User Defined
 /// ## Code
 /// * Function Code : `u8`
@@ -262,7 +982,20 @@ impl Display for Request<WriteSingleRegister> {
 pub type UserDefinedRequest = Request<UserDefined>;
 
 impl Request<UserDefined> {
+    /// Codes outside the user defined ranges are still accepted, a warning is traced for
+    /// reserved ones
+    ///
+    /// A public function code is accepted as well, but the request is then dispatched by
+    /// `RequestPdu::from` as that public function. Prefer `try_user_defined`.
     pub fn new(function_code: u8, data: &[u8]) -> Result<Self, ModbusFrameError> {
+        #[cfg(feature = "tracing")]
+        if FunctionCode::classify(function_code) == FunctionClass::Reserved {
+            tracing::warn!(
+                function_code,
+                "user defined request with a reserved function code"
+            );
+        }
+
         let mut pdu = Pdu::new(function_code)?;
         pdu.put_slice(data)?;
 
@@ -272,6 +1005,22 @@ impl Request<UserDefined> {
         })
     }
 
+    /// Like `new`, but rejects the public function codes and codes with the exception bit
+    ///
+    /// These would be taken for a public request or an exception response on the other end.
+    pub fn try_user_defined(function_code: u8, data: &[u8]) -> Result<Self, ModbusFrameError> {
+        if function_code == 0
+            || matches!(
+                FunctionCode::classify(function_code),
+                FunctionClass::Public | FunctionClass::Exception
+            )
+        {
+            return Err(ModbusPduError::ReservedFunctionCode(function_code).into());
+        }
+
+        Self::new(function_code, data)
+    }
+
     pub fn function_code(&self) -> Option<u8> {
         self.inner.function_code()
     }
@@ -293,6 +1042,10 @@ impl Display for Request<UserDefined> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::frame::pdu::function::response::{
+        MaskWriteRegisterResponse, WriteFileRecordResponse, WriteMultipleCoilsResponse,
+        WriteMultipleRegistersResponse, WriteSingleCoilResponse, WriteSingleRegisterResponse,
+    };
 
     #[test]
     fn test_frame_pdu_function_req_read_coils_valid() {
@@ -333,6 +1086,31 @@ mod tests {
         assert!(ReadHoldingRegistersRequest::new(0x0001, 0x007E).is_err());
     }
 
+    #[test]
+    fn test_frame_pdu_function_req_read_holding_registers_for_references() {
+        let req = ReadHoldingRegistersRequest::for_references(40001, 40010).unwrap();
+        assert_eq!(req.starting_address(), Some(0x0000));
+        assert_eq!(req.quantity_of_registers(), Some(10));
+
+        let req = ReadHoldingRegistersRequest::for_references(465412, 465536).unwrap();
+        assert_eq!(req.starting_address(), Some(0xFF83));
+        assert_eq!(req.quantity_of_registers(), Some(125));
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_read_holding_registers_for_references_out_of_range() {
+        // exceeds 125 registers
+        assert!(ReadHoldingRegistersRequest::for_references(40001, 40126).is_err());
+        // reversed range
+        assert!(ReadHoldingRegistersRequest::for_references(40010, 40001).is_err());
+        // input register references
+        assert!(ReadHoldingRegistersRequest::for_references(30001, 30010).is_err());
+        // crosses the register type boundary
+        assert!(ReadHoldingRegistersRequest::for_references(39999, 40010).is_err());
+        // mixed notation
+        assert!(ReadHoldingRegistersRequest::for_references(40001, 400010).is_err());
+    }
+
     #[test]
     fn test_frame_pdu_function_req_read_input_registers_vaild() {
         let req = ReadInputRegistersRequest::new(0x0001, 0x0002).unwrap();
@@ -351,6 +1129,11 @@ mod tests {
         let req = WriteSingleCoilRequest::new(0x0001, true).unwrap();
         assert_eq!(req.output_address(), Some(0x0001));
         assert_eq!(req.output_value(), Some(true));
+        assert_eq!(req.as_pdu().as_slice(), &[0x05, 0x00, 0x01, 0xFF, 0x00]);
+
+        let req = WriteSingleCoilRequest::new(0x0002, false).unwrap();
+        assert_eq!(req.output_value(), Some(false));
+        assert_eq!(req.as_pdu().as_slice(), &[0x05, 0x00, 0x02, 0x00, 0x00]);
     }
 
     #[test]
@@ -360,10 +1143,329 @@ mod tests {
         assert_eq!(req.register_value(), Some(0x0002));
     }
 
+    #[test]
+    fn test_frame_pdu_function_req_diagnostics_valid() {
+        let req = DiagnosticsRequest::new(0x0001, 0xFF00).unwrap();
+        assert_eq!(req.sub_function(), Some(0x0001));
+        assert_eq!(req.data(), Some(0xFF00));
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_read_exception_status() {
+        let req = ReadExceptionStatusRequest::new().unwrap();
+        assert_eq!(req.as_pdu().as_slice(), &[0x07]);
+        assert!(matches!(
+            RequestPdu::from(req.into_inner()),
+            RequestPdu::ReadExceptionStatus(_)
+        ));
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_get_comm_event_counter() {
+        let req = GetCommEventCounterRequest::new().unwrap();
+        assert_eq!(req.as_pdu().as_slice(), &[0x0B]);
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_report_server_id() {
+        let req = ReportServerIdRequest::new().unwrap();
+        assert_eq!(req.as_pdu().as_slice(), &[0x11]);
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_mask_write_register() {
+        let req = MaskWriteRegisterRequest::new(0x0004, 0x00F2, 0x0025).unwrap();
+        assert_eq!(
+            req.as_pdu().as_slice(),
+            &[0x16, 0x00, 0x04, 0x00, 0xF2, 0x00, 0x25]
+        );
+        assert_eq!(req.reference_address(), Some(0x0004));
+        assert_eq!(req.and_mask(), Some(0x00F2));
+        assert_eq!(req.or_mask(), Some(0x0025));
+
+        // Example of the specification
+        assert_eq!(req.apply_mask(0x0012), Some(0x0017));
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_verify_echo_single() {
+        let req = WriteSingleCoilRequest::new(0x00AC, true).unwrap();
+        assert!(req.verify_echo(&WriteSingleCoilResponse::new(0x00AC, true).unwrap()));
+        assert!(!req.verify_echo(&WriteSingleCoilResponse::new(0x00AC, false).unwrap()));
+        assert!(!req.verify_echo(&WriteSingleCoilResponse::new(0x00AD, true).unwrap()));
+
+        let req = WriteSingleRegisterRequest::new(0x0001, 0x0003).unwrap();
+        assert!(req.verify_echo(&WriteSingleRegisterResponse::new(0x0001, 0x0003).unwrap()));
+        assert!(!req.verify_echo(&WriteSingleRegisterResponse::new(0x0001, 0x0004).unwrap()));
+        assert!(!req.verify_echo(&WriteSingleRegisterResponse::new(0x0002, 0x0003).unwrap()));
+
+        let req = MaskWriteRegisterRequest::new(0x0004, 0x00F2, 0x0025).unwrap();
+        assert!(req.verify_echo(&MaskWriteRegisterResponse::new(0x0004, 0x00F2, 0x0025).unwrap()));
+        assert!(!req.verify_echo(&MaskWriteRegisterResponse::new(0x0004, 0x00F2, 0x0024).unwrap()));
+
+        let records = [FileRecord::new(4, 7, &[0x06AF, 0x04BE])];
+        let req = WriteFileRecordRequest::new(&records).unwrap();
+        assert!(req.verify_echo(&WriteFileRecordResponse::new(&records).unwrap()));
+        let other = [FileRecord::new(4, 7, &[0x06AF, 0x04BF])];
+        assert!(!req.verify_echo(&WriteFileRecordResponse::new(&other).unwrap()));
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_verify_echo_multiple() {
+        let req = WriteMultipleCoilsRequest::new(0x0013, &[true; 10]).unwrap();
+        assert!(req.verify_echo(&WriteMultipleCoilsResponse::new(0x0013, 10).unwrap()));
+        assert!(!req.verify_echo(&WriteMultipleCoilsResponse::new(0x0013, 9).unwrap()));
+        assert!(!req.verify_echo(&WriteMultipleCoilsResponse::new(0x0014, 10).unwrap()));
+
+        let req = WriteMultipleRegistersRequest::new(0x0001, &[0x000A, 0x0102]).unwrap();
+        assert!(req.verify_echo(&WriteMultipleRegistersResponse::new(0x0001, 2).unwrap()));
+        assert!(!req.verify_echo(&WriteMultipleRegistersResponse::new(0x0001, 1).unwrap()));
+        assert!(!req.verify_echo(&WriteMultipleRegistersResponse::new(0x0000, 2).unwrap()));
+
+        // A truncated echo never matches
+        let pdu = Pdu::new(0x10).unwrap();
+        let rsp = WriteMultipleRegistersResponse::parse(pdu).unwrap();
+        assert!(!req.verify_echo(&rsp));
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_read_write_multiple_registers() {
+        let req =
+            ReadWriteMultipleRegistersRequest::new(0x0003, 6, 0x000E, &[0x00FF, 0x00FF, 0x00FF])
+                .unwrap();
+        assert_eq!(
+            req.as_pdu().as_slice(),
+            &[
+                0x17, 0x00, 0x03, 0x00, 0x06, 0x00, 0x0E, 0x00, 0x03, 0x06, 0x00, 0xFF, 0x00, 0xFF,
+                0x00, 0xFF
+            ]
+        );
+        assert_eq!(req.read_starting_address(), Some(0x0003));
+        assert_eq!(req.quantity_to_read(), Some(6));
+        assert_eq!(req.write_starting_address(), Some(0x000E));
+        assert_eq!(req.quantity_to_write(), Some(3));
+        assert!(req
+            .write_registers_value()
+            .unwrap()
+            .eq([0x00FF, 0x00FF, 0x00FF]));
+
+        assert!(ReadWriteMultipleRegistersRequest::new(0x0000, 0, 0x0000, &[1]).is_err());
+        assert!(ReadWriteMultipleRegistersRequest::new(0x0000, 126, 0x0000, &[1]).is_err());
+        assert!(ReadWriteMultipleRegistersRequest::new(0x0000, 1, 0x0000, &[]).is_err());
+        assert!(ReadWriteMultipleRegistersRequest::new(0x0000, 125, 0x0000, &[0; 121]).is_ok());
+        assert!(ReadWriteMultipleRegistersRequest::new(0x0000, 1, 0x0000, &[0; 122]).is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_write_file_record() {
+        let req = WriteFileRecordRequest::new(&[FileRecord::new(
+            0x0004,
+            0x0007,
+            &[0x06AF, 0x04BE, 0x100D],
+        )])
+        .unwrap();
+        assert_eq!(
+            req.as_pdu().as_slice(),
+            &[
+                0x15, 0x0D, 0x06, 0x00, 0x04, 0x00, 0x07, 0x00, 0x03, 0x06, 0xAF, 0x04, 0xBE, 0x10,
+                0x0D
+            ]
+        );
+        assert_eq!(req.request_data_length(), Some(0x0D));
+        let mut sub_requests = req.sub_requests().unwrap();
+        let sub_request = sub_requests.next().unwrap();
+        assert_eq!(sub_request.reference_type, 0x06);
+        assert_eq!(sub_request.file_number, 0x0004);
+        assert_eq!(sub_request.record_number, 0x0007);
+        assert!(sub_request.record_data.eq([0x06AF, 0x04BE, 0x100D]));
+        assert!(sub_requests.next().is_none());
+
+        let records = [
+            FileRecord::new(0x0001, 0x0000, &[0x0001]),
+            FileRecord::new(0x0002, 0x270F, &[0x0002, 0x0003]),
+        ];
+        let req = WriteFileRecordRequest::new(&records).unwrap();
+        assert_eq!(req.request_data_length(), Some(20));
+        assert_eq!(req.sub_requests().unwrap().count(), 2);
+
+        assert!(WriteFileRecordRequest::new(&[]).is_err());
+        assert!(WriteFileRecordRequest::new(&[FileRecord::new(0x0000, 0x0000, &[1])]).is_err());
+        assert!(WriteFileRecordRequest::new(&[FileRecord::new(0x0001, 0x2710, &[1])]).is_err());
+        assert!(WriteFileRecordRequest::new(&[FileRecord::new(0x0001, 0x0000, &[])]).is_err());
+        let record = FileRecord {
+            reference_type: 0x05,
+            ..FileRecord::new(0x0001, 0x0000, &[1])
+        };
+        assert!(WriteFileRecordRequest::new(&[record]).is_err());
+        // 7 + 2 * 119 = 245 bytes
+        assert!(WriteFileRecordRequest::new(&[FileRecord::new(0x0001, 0x0000, &[0; 119])]).is_ok());
+        assert!(
+            WriteFileRecordRequest::new(&[FileRecord::new(0x0001, 0x0000, &[0; 120])]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_read_device_identification() {
+        let req = ReadDeviceIdentificationRequest::new(0x03, 0x00).unwrap();
+        assert_eq!(req.as_pdu().as_slice(), &[0x2B, 0x0E, 0x03, 0x00]);
+        assert_eq!(req.read_device_id_code(), Some(0x03));
+
+        assert!(ReadDeviceIdentificationRequest::new(0x00, 0x00).is_err());
+        assert!(ReadDeviceIdentificationRequest::new(0x05, 0x00).is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_write_multiple_coils() {
+        let coils = [
+            true, false, true, true, false, false, true, true, true, false,
+        ];
+        let req = WriteMultipleCoilsRequest::new(0x0013, &coils).unwrap();
+        assert_eq!(
+            req.as_pdu().as_slice(),
+            &[0x0F, 0x00, 0x13, 0x00, 0x0A, 0x02, 0xCD, 0x01]
+        );
+        assert_eq!(req.starting_address(), Some(0x0013));
+        assert_eq!(req.quantity_of_outputs(), Some(10));
+        assert!(req.outputs_value().unwrap().take(10).eq(coils));
+
+        assert!(WriteMultipleCoilsRequest::new(0x0000, &[]).is_err());
+        assert!(WriteMultipleCoilsRequest::new(0x0000, &[true; 1968]).is_ok());
+        assert!(WriteMultipleCoilsRequest::new(0x0000, &[true; 1969]).is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_request_pdu_dispatch() {
+        let req = WriteMultipleCoilsRequest::new(0x0013, &[true, false]).unwrap();
+        let pdu = req.clone().into_inner();
+
+        match RequestPdu::from(pdu.clone()) {
+            RequestPdu::WriteMultipleCoils(dispatched) => assert_eq!(dispatched, req),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(Pdu::from(RequestPdu::from(pdu.clone())), pdu);
+
+        let pdu = Pdu::new(0x41).unwrap();
+        assert_eq!(RequestPdu::from(pdu.clone()), RequestPdu::Other(pdu));
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_write_multiple_coils_from_coil_bits() {
+        let coils = CoilBits::from([
+            true, false, true, true, false, false, true, true, true, false,
+        ]);
+        let req = WriteMultipleCoilsRequest::from_coil_bits(0x0013, &coils).unwrap();
+        assert_eq!(
+            req.as_pdu().as_slice(),
+            &[0x0F, 0x00, 0x13, 0x00, 0x0A, 0x02, 0xCD, 0x01]
+        );
+
+        assert!(WriteMultipleCoilsRequest::from_coil_bits(0x0000, &CoilBits::<0>::new()).is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_write_multiple_coils_from_bitmask() {
+        let req = WriteMultipleCoilsRequest::from_bitmask(0x0013, 0xFFFF_FDCD, 10).unwrap();
+        assert_eq!(
+            req.as_pdu().as_slice(),
+            &[0x0F, 0x00, 0x13, 0x00, 0x0A, 0x02, 0xCD, 0x01]
+        );
+        assert_eq!(req.quantity_of_outputs(), Some(10));
+        assert!(req
+            .outputs_value()
+            .unwrap()
+            .take(10)
+            .eq([true, false, true, true, false, false, true, true, true, false]));
+
+        let req = WriteMultipleCoilsRequest::from_bitmask(0x0000, 0x8000_0001, 32).unwrap();
+        assert_eq!(req.byte_count(), Some(4));
+        assert_eq!(req.as_pdu().data()[5..], [0x01, 0x00, 0x00, 0x80]);
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_write_multiple_coils_from_bitmask_invalid_count() {
+        assert!(WriteMultipleCoilsRequest::from_bitmask(0x0000, 0x01, 0).is_err());
+        assert!(WriteMultipleCoilsRequest::from_bitmask(0x0000, 0x01, 33).is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_write_multiple_registers() {
+        let req = WriteMultipleRegistersRequest::new(0x0001, &[0x000A, 0x0102]).unwrap();
+        assert_eq!(
+            req.as_pdu().as_slice(),
+            &[0x10, 0x00, 0x01, 0x00, 0x02, 0x04, 0x00, 0x0A, 0x01, 0x02]
+        );
+        assert_eq!(req.quantity_of_registers(), Some(2));
+        assert!(req.registers_value().unwrap().eq([0x000A, 0x0102]));
+        assert_eq!(req.register_value(1), Some(0x0102));
+        assert_eq!(req.register_value(2), None);
+
+        assert!(WriteMultipleRegistersRequest::new(0x0001, &[]).is_err());
+        assert!(WriteMultipleRegistersRequest::new(0x0001, &[0; 124]).is_err());
+    }
+
+    #[test]
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    fn test_frame_pdu_function_req_write_multiple_try_from_vec() {
+        let req =
+            WriteMultipleRegistersRequest::try_from((0x0001, Vec::from([0x000A, 0x0102]))).unwrap();
+        assert_eq!(
+            req.as_pdu().as_slice(),
+            WriteMultipleRegistersRequest::new(0x0001, &[0x000A, 0x0102])
+                .unwrap()
+                .as_pdu()
+                .as_slice()
+        );
+        assert!(WriteMultipleRegistersRequest::try_from((0x0001, Vec::new())).is_err());
+        assert!(WriteMultipleRegistersRequest::try_from((0x0001, Vec::from([0; 124]))).is_err());
+
+        let req =
+            WriteMultipleCoilsRequest::try_from((0x0013, Vec::from([true, false, true]))).unwrap();
+        assert_eq!(req.quantity_of_outputs(), Some(3));
+        assert!(WriteMultipleCoilsRequest::try_from((0x0013, Vec::from([false; 1969]))).is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_function_req_write_multiple_registers_from_f32() {
+        let req = WriteMultipleRegistersRequest::from_f32(0x0010, &[1.5], WordOrder::LittleEndian)
+            .unwrap();
+        assert_eq!(
+            req.as_pdu().as_slice(),
+            &[0x10, 0x00, 0x10, 0x00, 0x02, 0x04, 0x00, 0x00, 0x3F, 0xC0]
+        );
+        assert_eq!(
+            req.registers_value()
+                .unwrap()
+                .read_f32(0, WordOrder::LittleEndian),
+            Some(1.5)
+        );
+
+        assert!(
+            WriteMultipleRegistersRequest::from_f32(0x0010, &[], WordOrder::BigEndian).is_err()
+        );
+        assert!(
+            WriteMultipleRegistersRequest::from_f32(0x0010, &[0.0; 62], WordOrder::BigEndian)
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_frame_pdu_function_req_user_defined() {
         let req = UserDefinedRequest::new(0x0A, &[0x01, 0x02]).unwrap();
         assert_eq!(req.function_code(), Some(0x0A));
         assert_eq!(req.data(), &[0x01, 0x02]);
     }
+
+    #[test]
+    fn test_frame_pdu_function_req_try_user_defined() {
+        let req = UserDefinedRequest::try_user_defined(0x41, &[0x01]).unwrap();
+        assert_eq!(req.as_pdu().as_slice(), &[0x41, 0x01]);
+        assert!(UserDefinedRequest::try_user_defined(0x0A, &[]).is_ok());
+
+        for code in [0x00, 0x01, 0x06, 0x10, 0x2B, 0x80, 0xC1] {
+            assert!(matches!(
+                UserDefinedRequest::try_user_defined(code, &[]),
+                Err(ModbusFrameError::PduError(ModbusPduError::ReservedFunctionCode(c))) if c == code
+            ));
+        }
+    }
 }
@@ -1,7 +1,9 @@
 use super::*;
 use crate::{
     error::ModbusFrameError,
-    frame::pdu::types::{BitSet, RegisterSlice},
+    frame::pdu::types::{
+        pack_bits, BitSet, CoilBits, FileRecord, FileSubRecords, RegisterSlice, WordOrder,
+    },
 };
 
 /// Read Coils
@@ -26,13 +28,60 @@ impl Response<ReadCoils> {
         })
     }
 
+    /// Create a response carrying the given coil status
+    pub fn from_coils(coils: &[bool]) -> Result<Self, ModbusFrameError> {
+        Ok(Self {
+            inner: bits_response(PublicFunctionCode::ReadCoils, coils)?,
+            _marker: PhantomData,
+        })
+    }
+
     pub fn byte_count(&self) -> Option<u8> {
         self.inner.read_u8(0)
     }
 
+    /// Every bit of the status bytes, `None` if the response is shorter than its byte count
     pub fn coil_status(&self) -> Option<BitSet<'_>> {
-        let byte_count = self.byte_count()?.checked_add(1)?;
-        Some(BitSet::new(&self.inner.data()[1..byte_count as usize]))
+        let byte_count = self.byte_count()? as usize;
+        self.inner.try_data_slice(1, byte_count).map(BitSet::new)
+    }
+
+    /// Exactly `quantity` coils, without the padding bits of the last byte
+    ///
+    /// `None` if the response holds fewer coils. Prefer this over `coil_status`, which walks
+    /// every bit of the status bytes.
+    pub fn coils(&self, quantity: u16) -> Option<BitSet<'_>> {
+        let byte_count = self.byte_count()? as usize;
+        let coil_status = self.inner.try_data_slice(1, byte_count)?;
+
+        if quantity as usize > byte_count * 8 {
+            return None;
+        }
+
+        Some(BitSet::with_limit(coil_status, quantity as usize))
+    }
+
+    /// `quantity` coils paired with their address from `starting_address`
+    ///
+    /// Yields nothing if the response holds fewer coils. Addresses wrap at the `u16` boundary.
+    pub fn addressed_coils(
+        &self,
+        starting_address: u16,
+        quantity: u16,
+    ) -> impl Iterator<Item = (u16, bool)> + '_ {
+        self.coils(quantity)
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(move |(index, value)| (starting_address.wrapping_add(index as u16), value))
+    }
+
+    /// Collect the first `N` coils, or `None` if the response holds fewer
+    pub fn into_coil_bits<const N: usize>(self) -> Option<CoilBits<N>> {
+        let byte_count = self.byte_count()? as usize;
+        let coil_status = self.inner.try_data_slice(1, byte_count)?;
+
+        CoilBits::from_bits(BitSet::new(coil_status))
     }
 }
 
@@ -67,13 +116,36 @@ impl Response<ReadDiscreteInputs> {
         })
     }
 
+    /// Create a response carrying the given input status
+    pub fn from_inputs(inputs: &[bool]) -> Result<Self, ModbusFrameError> {
+        Ok(Self {
+            inner: bits_response(PublicFunctionCode::ReadDiscreteInputs, inputs)?,
+            _marker: PhantomData,
+        })
+    }
+
     pub fn byte_count(&self) -> Option<u8> {
         self.inner.read_u8(0)
     }
 
+    /// Every bit of the status bytes, `None` if the response is shorter than its byte count
     pub fn input_status(&self) -> Option<BitSet<'_>> {
-        let byte_count = self.byte_count()?.checked_add(1)?;
-        Some(BitSet::new(&self.inner.data()[1..byte_count as usize]))
+        let byte_count = self.byte_count()? as usize;
+        self.inner.try_data_slice(1, byte_count).map(BitSet::new)
+    }
+
+    /// Exactly `quantity` inputs, without the padding bits of the last byte
+    ///
+    /// `None` if the response holds fewer inputs.
+    pub fn inputs(&self, quantity: u16) -> Option<BitSet<'_>> {
+        let byte_count = self.byte_count()? as usize;
+        let input_status = self.inner.try_data_slice(1, byte_count)?;
+
+        if quantity as usize > byte_count * 8 {
+            return None;
+        }
+
+        Some(BitSet::with_limit(input_status, quantity as usize))
     }
 }
 
@@ -108,27 +180,123 @@ impl Response<ReadHoldingRegisters> {
         })
     }
 
+    /// Create a response carrying the given register values
+    pub fn from_registers(registers: &[u16]) -> Result<Self, ModbusFrameError> {
+        Ok(Self {
+            inner: registers_response(PublicFunctionCode::ReadHoldingRegisters, registers)?,
+            _marker: PhantomData,
+        })
+    }
+
     pub fn byte_count(&self) -> Option<u8> {
         self.inner.read_u8(0)
     }
 
+    /// Register values, `None` if the response is shorter than its byte count
     pub fn register_value(&self) -> Option<RegisterSlice<'_>> {
-        let byte_count = self.byte_count()?.checked_add(1)?;
-        Some(RegisterSlice::new(
-            &self.inner.data()[1..byte_count as usize],
-        ))
+        self.register_bytes().map(RegisterSlice::new)
     }
 
     pub fn register(&self, index: usize) -> Option<u16> {
-        let byte_count = self.byte_count()?;
-        let start = 1 + index * 2;
-
-        // Check if the index is within the bounds
-        if start < byte_count as usize {
-            self.inner.read_u16(start)
-        } else {
-            None
+        self.register_bytes()
+            .and_then(|bytes| RegisterSlice::new(bytes).get(index))
+    }
+
+    /// Register values paired with their address from `starting_address`
+    ///
+    /// Yields nothing if the response is shorter than its byte count. Addresses wrap at the
+    /// `u16` boundary.
+    pub fn addressed(&self, starting_address: u16) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.register_value()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(move |(index, value)| (starting_address.wrapping_add(index as u16), value))
+    }
+
+    /// Registers actually present and whether the response is shorter than its byte count
+    ///
+    /// Recovers what a slave sent before cutting the response short instead of rejecting it.
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    pub fn registers_lossy(&self) -> (Vec<u16>, bool) {
+        let claimed = self.byte_count().unwrap_or(0) as usize;
+        let present = self.inner.data().get(1..).unwrap_or_default();
+        let bytes = &present[..claimed.min(present.len())];
+
+        let registers = bytes
+            .chunks_exact(2)
+            .map(|chunk| u16::from_be_bytes([chunk[0], chunk[1]]))
+            .collect();
+
+        (registers, bytes.len() < claimed)
+    }
+
+    /// Register value read as two's complement `i16`
+    pub fn register_i16(&self, index: usize) -> Option<i16> {
+        self.register(index).map(|value| value as i16)
+    }
+
+    /// Bits of the register at `index`, for registers packing status flags
+    ///
+    /// The `BitSet` walks the two bytes in wire order, high byte first, each from its LSB:
+    /// it yields register bits 8 to 15 followed by bits 0 to 7.
+    pub fn register_bits(&self, index: usize) -> Option<BitSet<'_>> {
+        let byte_count = self.byte_count()? as usize;
+        let start = 1 + index.checked_mul(2)?;
+
+        if start + 2 > 1 + byte_count {
+            return None;
         }
+
+        self.inner.try_data_slice(start, 2).map(BitSet::new)
+    }
+
+    /// Two registers from `index` combined into a `u32` in the given word order
+    pub fn register_u32(&self, index: usize, order: WordOrder) -> Option<u32> {
+        self.register_bytes()
+            .and_then(|bytes| RegisterSlice::new(bytes).read_u32(index, order))
+    }
+
+    /// Two registers from `index` combined into an `i32` in the given word order
+    pub fn register_i32(&self, index: usize, order: WordOrder) -> Option<i32> {
+        self.register_bytes()
+            .and_then(|bytes| RegisterSlice::new(bytes).read_i32(index, order))
+    }
+
+    /// Four registers from `index` combined into a `u64` in the given word order
+    pub fn register_u64(&self, index: usize, order: WordOrder) -> Option<u64> {
+        self.register_bytes()
+            .and_then(|bytes| RegisterSlice::new(bytes).read_u64(index, order))
+    }
+
+    /// Four registers from `index` combined into an `i64` in the given word order
+    pub fn register_i64(&self, index: usize, order: WordOrder) -> Option<i64> {
+        self.register_bytes()
+            .and_then(|bytes| RegisterSlice::new(bytes).read_i64(index, order))
+    }
+
+    /// Two registers from `index` combined into an IEEE-754 `f32` in the given word order
+    ///
+    /// `None` if the second register lies beyond the response, e.g. at the last register.
+    pub fn register_f32(&self, index: usize, order: WordOrder) -> Option<f32> {
+        self.register_bytes()
+            .and_then(|bytes| RegisterSlice::new(bytes).read_f32(index, order))
+    }
+
+    /// Register value multiplied by `scale`, e.g. `0.1` for a value reported in tenths
+    pub fn register_scaled(&self, index: usize, scale: f64) -> Option<f64> {
+        self.register(index).map(|value| value as f64 * scale)
+    }
+
+    /// Register value read as `i16` and multiplied by `scale`
+    pub fn register_scaled_signed(&self, index: usize, scale: f64) -> Option<f64> {
+        self.register(index)
+            .map(|value| value as i16 as f64 * scale)
+    }
+
+    fn register_bytes(&self) -> Option<&[u8]> {
+        let byte_count = self.byte_count()? as usize;
+        self.inner.try_data_slice(1, byte_count)
     }
 }
 
@@ -163,27 +331,62 @@ impl Response<ReadInputRegisters> {
         })
     }
 
+    /// Create a response carrying the given register values
+    pub fn from_registers(registers: &[u16]) -> Result<Self, ModbusFrameError> {
+        Ok(Self {
+            inner: registers_response(PublicFunctionCode::ReadInputRegisters, registers)?,
+            _marker: PhantomData,
+        })
+    }
+
     pub fn byte_count(&self) -> Option<u8> {
         self.inner.read_u8(0)
     }
 
+    /// Input register values, `None` if the response is shorter than its byte count
     pub fn input_registers(&self) -> Option<RegisterSlice<'_>> {
-        let byte_count = self.byte_count()?.checked_add(1)?;
-        Some(RegisterSlice::new(
-            &self.inner.data()[1..byte_count as usize],
-        ))
+        self.input_registers_bytes().map(RegisterSlice::new)
     }
 
     pub fn register(&self, index: usize) -> Option<u16> {
-        let byte_count = self.byte_count()?;
-        let start = 1 + index * 2;
-
-        // Check if the index is within the bounds
-        if start < byte_count as usize {
-            self.inner.read_u16(start)
-        } else {
-            None
-        }
+        let start = index.checked_mul(2)?;
+        let bytes = self.input_registers_bytes()?.get(start..start + 2)?;
+
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Register value read as two's complement `i16`
+    pub fn register_i16(&self, index: usize) -> Option<i16> {
+        self.register(index).map(|value| value as i16)
+    }
+
+    /// Two registers from `index` combined into a `u32` in the given word order
+    pub fn register_u32(&self, index: usize, order: WordOrder) -> Option<u32> {
+        self.input_registers_bytes()
+            .and_then(|bytes| RegisterSlice::new(bytes).read_u32(index, order))
+    }
+
+    /// Two registers from `index` combined into an `i32` in the given word order
+    pub fn register_i32(&self, index: usize, order: WordOrder) -> Option<i32> {
+        self.input_registers_bytes()
+            .and_then(|bytes| RegisterSlice::new(bytes).read_i32(index, order))
+    }
+
+    /// Four registers from `index` combined into a `u64` in the given word order
+    pub fn register_u64(&self, index: usize, order: WordOrder) -> Option<u64> {
+        self.input_registers_bytes()
+            .and_then(|bytes| RegisterSlice::new(bytes).read_u64(index, order))
+    }
+
+    /// Four registers from `index` combined into an `i64` in the given word order
+    pub fn register_i64(&self, index: usize, order: WordOrder) -> Option<i64> {
+        self.input_registers_bytes()
+            .and_then(|bytes| RegisterSlice::new(bytes).read_i64(index, order))
+    }
+
+    fn input_registers_bytes(&self) -> Option<&[u8]> {
+        let byte_count = self.byte_count()? as usize;
+        self.inner.try_data_slice(1, byte_count)
     }
 }
 
@@ -272,6 +475,338 @@ impl Display for Response<WriteSingleRegister> {
     }
 }
 
+/// Read Exception Status
+/// ## Code
+/// * Function Code : `0x07`
+/// ## Data fields
+/// * Output Data : `u8`
+pub type ReadExceptionStatusResponse = Response<ReadExceptionStatus>;
+
+impl Response<ReadExceptionStatus> {
+    pub fn new(output_data: u8) -> Result<Self, ModbusFrameError> {
+        let mut pdu = Pdu::new(PublicFunctionCode::ReadExceptionStatus.into())?;
+        pdu.put_u8(output_data)?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn output_data(&self) -> Option<u8> {
+        self.inner.data().first().copied()
+    }
+
+    /// The eight exception status outputs, from the LSB
+    pub fn status_bits(&self) -> Option<BitSet<'_>> {
+        self.inner.data().get(..1).map(BitSet::new)
+    }
+}
+
+impl Display for Response<ReadExceptionStatus> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response<ReadExceptionStatus>")
+            .field("output_data", &self.output_data())
+            .finish()
+    }
+}
+
+/// Diagnostics
+/// ## Code
+/// * Function Code : `0x08`
+/// ## Data fields
+/// * Sub-function : `u16`
+/// * Data : `u16`
+pub type DiagnosticsResponse = Response<Diagnostics>;
+
+impl Response<Diagnostics> {
+    pub fn new(sub_function: u16, data: u16) -> Result<Self, ModbusFrameError> {
+        let mut pdu = Pdu::new(PublicFunctionCode::Diagnostics.into())?;
+        pdu.put_u16(sub_function)?;
+        pdu.put_u16(data)?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn sub_function(&self) -> Option<u16> {
+        self.inner.read_u16(0)
+    }
+
+    pub fn data(&self) -> Option<u16> {
+        self.inner.read_u16(2)
+    }
+}
+
+impl Display for Response<Diagnostics> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response<Diagnostics>")
+            .field("sub_function", &self.sub_function())
+            .field("data", &self.data())
+            .finish()
+    }
+}
+
+/// Get Comm Event Counter
+/// ## Code
+/// * Function Code : `0x0B`
+/// ## Data fields
+/// * Status : `u16`
+/// * Event Count : `u16`
+pub type GetCommEventCounterResponse = Response<GetCommEventCounter>;
+
+impl Response<GetCommEventCounter> {
+    /// Status word of a device still processing a previously issued program command
+    pub const STATUS_BUSY: u16 = 0xFFFF;
+
+    pub fn new(status: u16, event_count: u16) -> Result<Self, ModbusFrameError> {
+        let mut pdu = Pdu::new(PublicFunctionCode::GetCommEventCounter.into())?;
+        pdu.put_u16(status)?;
+        pdu.put_u16(event_count)?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        self.inner.read_u16(0)
+    }
+
+    pub fn event_count(&self) -> Option<u16> {
+        self.inner.read_u16(2)
+    }
+
+    pub fn is_busy(&self) -> Option<bool> {
+        self.status().map(|status| status == Self::STATUS_BUSY)
+    }
+}
+
+impl Display for Response<GetCommEventCounter> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response<GetCommEventCounter>")
+            .field("status", &self.status())
+            .field("event_count", &self.event_count())
+            .finish()
+    }
+}
+
+/// Write Multiple Coils
+/// ## Code
+/// * Function Code : `0x0F`
+/// ## Data fields
+/// * Starting Address : `u16`
+/// * Quantity of Outputs : `u16`
+pub type WriteMultipleCoilsResponse = Response<WriteMultipleCoils>;
+
+impl Response<WriteMultipleCoils> {
+    pub fn new(starting_address: u16, quantity_of_outputs: u16) -> Result<Self, ModbusFrameError> {
+        let mut pdu = Pdu::new(PublicFunctionCode::WriteMultipleCoils.into())?;
+        pdu.put_u16(starting_address)?;
+        pdu.put_u16(quantity_of_outputs)?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn starting_address(&self) -> Option<u16> {
+        self.inner.read_u16(0)
+    }
+
+    pub fn quantity_of_outputs(&self) -> Option<u16> {
+        self.inner.read_u16(2)
+    }
+}
+
+impl Display for Response<WriteMultipleCoils> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response<WriteMultipleCoils>")
+            .field("starting_address", &self.starting_address())
+            .field("quantity_of_outputs", &self.quantity_of_outputs())
+            .finish()
+    }
+}
+
+/// Write Multiple Registers
+/// ## Code
+/// * Function Code : `0x10`
+/// ## Data fields
+/// * Starting Address : `u16`
+/// * Quantity of Registers : `u16`
+pub type WriteMultipleRegistersResponse = Response<WriteMultipleRegisters>;
+
+impl Response<WriteMultipleRegisters> {
+    pub fn new(
+        starting_address: u16,
+        quantity_of_registers: u16,
+    ) -> Result<Self, ModbusFrameError> {
+        let mut pdu = Pdu::new(PublicFunctionCode::WriteMultipleRegisters.into())?;
+        pdu.put_u16(starting_address)?;
+        pdu.put_u16(quantity_of_registers)?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn starting_address(&self) -> Option<u16> {
+        self.inner.read_u16(0)
+    }
+
+    pub fn quantity_of_registers(&self) -> Option<u16> {
+        self.inner.read_u16(2)
+    }
+}
+
+impl Display for Response<WriteMultipleRegisters> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response<WriteMultipleRegisters>")
+            .field("starting_address", &self.starting_address())
+            .field("quantity_of_registers", &self.quantity_of_registers())
+            .finish()
+    }
+}
+
+/// Report Server ID
+/// ## Code
+/// * Function Code : `0x11`
+/// ## Data fields
+/// * Byte Count : `u8`
+/// * Server ID : `u8`
+/// * Run Indicator Status : `u8`
+/// * Additional Data : `[u8; N]`
+///
+/// The size of the Server ID is device specific. The accessors assume the common one byte
+/// Server ID.
+pub type ReportServerIdResponse = Response<ReportServerId>;
+
+impl Response<ReportServerId> {
+    pub fn new(
+        server_id: u8,
+        run_indicator: bool,
+        additional_data: &[u8],
+    ) -> Result<Self, ModbusFrameError> {
+        if additional_data.len() > 249 {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+
+        let mut pdu = Pdu::new(PublicFunctionCode::ReportServerId.into())?;
+        pdu.put_u8(2 + additional_data.len() as u8)?;
+        pdu.put_u8(server_id)?;
+        pdu.put_u8(if run_indicator { 0xFF } else { 0x00 })?;
+        pdu.put_slice(additional_data)?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn byte_count(&self) -> Option<u8> {
+        self.inner.read_u8(0)
+    }
+
+    pub fn server_id(&self) -> Option<u8> {
+        self.inner.data().get(1).copied()
+    }
+
+    /// Run Indicator Status, `None` unless it is one of the two values defined by the spec
+    ///
+    /// A `None` alongside `Some` from `raw_run_indicator` reveals a noncompliant device.
+    pub fn run_indicator(&self) -> Option<bool> {
+        match self.raw_run_indicator()? {
+            0xFF => Some(true),
+            0x00 => Some(false),
+            _ => None,
+        }
+    }
+
+    pub fn raw_run_indicator(&self) -> Option<u8> {
+        self.inner.data().get(2).copied()
+    }
+
+    pub fn additional_data(&self) -> Option<&[u8]> {
+        self.inner.data().get(3..)
+    }
+}
+
+impl Display for Response<ReportServerId> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response<ReportServerId>")
+            .field("byte_count", &self.byte_count())
+            .field("server_id", &self.server_id())
+            .field("run_indicator", &self.run_indicator())
+            .field("additional_data", &self.additional_data())
+            .finish()
+    }
+}
+
+/// Exception
+/// ## Code
+/// * Function Code : Request Function Code + `0x80`
+/// ## Data fields
+/// * Exception Code : `u8`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExceptionResponse {
+    inner: Pdu,
+}
+
+impl ExceptionResponse {
+    pub fn new(function_code: u8, exception_code: ExceptionCode) -> Result<Self, ModbusFrameError> {
+        let mut pdu = Pdu::new(function_code | EXCEPTION_FLAG)?;
+        pdu.put_u8(exception_code.into())?;
+
+        Ok(Self { inner: pdu })
+    }
+
+    /// Function code of the refused request
+    pub fn function_code(&self) -> Option<u8> {
+        self.inner.base_function_code()
+    }
+
+    pub fn exception_code(&self) -> Option<ExceptionCode> {
+        let code = self.inner.data().first()?;
+        ExceptionCode::try_from(*code).ok()
+    }
+
+    pub fn as_pdu(&self) -> &Pdu {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> Pdu {
+        self.inner
+    }
+}
+
+/// Accepts a function code with the exception flag set, followed by exactly one exception code
+impl TryFrom<Pdu> for ExceptionResponse {
+    type Error = ModbusPduError;
+
+    fn try_from(pdu: Pdu) -> Result<Self, Self::Error> {
+        match pdu.function_code() {
+            Some(code) if code & EXCEPTION_FLAG == 0 => Err(ModbusPduError::UnexpectedCode(code)),
+            Some(_) if pdu.data().len() != 1 => Err(ModbusPduError::OutOfRange),
+            Some(_) => Ok(Self { inner: pdu }),
+            None => Err(ModbusPduError::UnexpectedCode(0)),
+        }
+    }
+}
+
+impl Display for ExceptionResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExceptionResponse")
+            .field("function_code", &self.function_code())
+            .field("exception_code", &self.exception_code())
+            .finish()
+    }
+}
+
 /// User Defined
 /// ## Code
 /// * Function Code : `u8`
@@ -308,9 +843,439 @@ impl Display for Response<UserDefined> {
     }
 }
 
+/// Mask Write Register
+/// ## Code
+/// * Function Code : `0x16`
+/// ## Data fields
+/// * Reference Address : `u16`
+/// * And_Mask : `u16`
+/// * Or_Mask : `u16`
+pub type MaskWriteRegisterResponse = Response<MaskWriteRegister>;
+
+impl Response<MaskWriteRegister> {
+    pub fn new(
+        reference_address: u16,
+        and_mask: u16,
+        or_mask: u16,
+    ) -> Result<Self, ModbusFrameError> {
+        let mut pdu = Pdu::new(PublicFunctionCode::MaskWriteRegister.into())?;
+        pdu.put_u16(reference_address)?;
+        pdu.put_u16(and_mask)?;
+        pdu.put_u16(or_mask)?;
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn reference_address(&self) -> Option<u16> {
+        self.inner.read_u16(0)
+    }
+
+    pub fn and_mask(&self) -> Option<u16> {
+        self.inner.read_u16(2)
+    }
+
+    pub fn or_mask(&self) -> Option<u16> {
+        self.inner.read_u16(4)
+    }
+}
+
+impl Display for Response<MaskWriteRegister> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response<MaskWriteRegister>")
+            .field("reference_address", &self.reference_address())
+            .field("and_mask", &self.and_mask())
+            .field("or_mask", &self.or_mask())
+            .finish()
+    }
+}
+
+/// Read/Write Multiple Registers
+/// ## Code
+/// * Function Code : `0x17`
+/// ## Data fields
+/// * Byte Count : `u8`
+/// * Read Registers Value : `[u16; N]`
+pub type ReadWriteMultipleRegistersResponse = Response<ReadWriteMultipleRegisters>;
+
+impl Response<ReadWriteMultipleRegisters> {
+    /// Create a response carrying the given read register values
+    pub fn from_registers(registers: &[u16]) -> Result<Self, ModbusFrameError> {
+        Ok(Self {
+            inner: registers_response(PublicFunctionCode::ReadWriteMultipleRegisters, registers)?,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn byte_count(&self) -> Option<u8> {
+        self.inner.read_u8(0)
+    }
+
+    pub fn register_value(&self) -> Option<RegisterSlice<'_>> {
+        let byte_count = self.byte_count()? as usize;
+        let register_value = self.inner.try_data_slice(1, byte_count)?;
+
+        Some(RegisterSlice::new(register_value))
+    }
+
+    pub fn register(&self, index: usize) -> Option<u16> {
+        self.register_value()?.get(index)
+    }
+}
+
+impl Display for Response<ReadWriteMultipleRegisters> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response<ReadWriteMultipleRegisters>")
+            .field("byte_count", &self.byte_count())
+            .field("register_value", &self.register_value())
+            .finish()
+    }
+}
+
+/// Write File Record
+/// ## Code
+/// * Function Code : `0x15`
+/// ## Data fields
+/// * Response Data Length : `u8`
+/// * Sub-Requests : `[(u8, u16, u16, u16, [u16; N]); M]`
+pub type WriteFileRecordResponse = Response<WriteFileRecord>;
+
+impl Response<WriteFileRecord> {
+    /// Create the echo of a request writing `records`
+    pub fn new(records: &[FileRecord]) -> Result<Self, ModbusFrameError> {
+        Ok(Self {
+            inner: write_file_record_pdu(records)?,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn response_data_length(&self) -> Option<u8> {
+        self.inner.read_u8(0)
+    }
+
+    pub fn sub_requests(&self) -> Option<FileSubRecords<'_>> {
+        let byte_count = self.response_data_length()? as usize;
+        let sub_requests = self.inner.try_data_slice(1, byte_count)?;
+
+        Some(FileSubRecords::new(sub_requests))
+    }
+}
+
+impl Display for Response<WriteFileRecord> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response<WriteFileRecord>")
+            .field("response_data_length", &self.response_data_length())
+            .field("sub_requests", &self.sub_requests())
+            .finish()
+    }
+}
+
+/// Read Device Identification
+/// ## Code
+/// * Function Code : `0x2B`
+/// ## Data fields
+/// * MEI Type : `0x0E`
+/// * Read Device ID Code : `u8`
+/// * Conformity Level : `u8`
+/// * More Follows : `u8` (`0x00` = no, `0xFF` = yes)
+/// * Next Object Id : `u8`
+/// * Number of Objects : `u8`
+/// * Objects : `[(u8, u8, [u8; N]); M]`
+pub type ReadDeviceIdentificationResponse = Response<ReadDeviceIdentification>;
+
+impl Response<ReadDeviceIdentification> {
+    pub fn new(
+        read_device_id_code: u8,
+        conformity_level: u8,
+        more_follows: bool,
+        next_object_id: u8,
+        objects: &[(u8, &[u8])],
+    ) -> Result<Self, ModbusFrameError> {
+        if objects.len() > u8::MAX as usize {
+            return Err(ModbusPduError::OutOfRange.into());
+        }
+
+        let mut pdu = Pdu::new(PublicFunctionCode::EncapsulatedInterfaceTransport.into())?;
+        pdu.put_u8(READ_DEVICE_IDENTIFICATION_MEI_TYPE)?;
+        pdu.put_u8(read_device_id_code)?;
+        pdu.put_u8(conformity_level)?;
+        pdu.put_u8(if more_follows { 0xFF } else { 0x00 })?;
+        pdu.put_u8(next_object_id)?;
+        pdu.put_u8(objects.len() as u8)?;
+        for (object_id, value) in objects {
+            if value.len() > u8::MAX as usize {
+                return Err(ModbusPduError::OutOfRange.into());
+            }
+
+            pdu.put_u8(*object_id)?;
+            pdu.put_u8(value.len() as u8)?;
+            pdu.put_slice(value)?;
+        }
+
+        Ok(Self {
+            inner: pdu,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn mei_type(&self) -> Option<u8> {
+        self.inner.data().first().copied()
+    }
+
+    pub fn read_device_id_code(&self) -> Option<u8> {
+        self.inner.data().get(1).copied()
+    }
+
+    pub fn conformity_level(&self) -> Option<u8> {
+        self.inner.data().get(2).copied()
+    }
+
+    /// Whether the objects continue in a further transaction, starting at `next_object_id`
+    pub fn more_follows(&self) -> Option<bool> {
+        self.inner.data().get(3).map(|value| *value == 0xFF)
+    }
+
+    pub fn next_object_id(&self) -> Option<u8> {
+        self.inner.data().get(4).copied()
+    }
+
+    pub fn number_of_objects(&self) -> Option<u8> {
+        self.inner.data().get(5).copied()
+    }
+
+    /// Iterate over the `(object_id, value)` pairs of the response
+    pub fn objects(&self) -> Option<DeviceObjects<'_>> {
+        let bytes = self.inner.data().get(6..)?;
+
+        Some(DeviceObjects {
+            bytes,
+            remaining: self.number_of_objects()?,
+        })
+    }
+}
+
+impl Display for Response<ReadDeviceIdentification> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response<ReadDeviceIdentification>")
+            .field("read_device_id_code", &self.read_device_id_code())
+            .field("conformity_level", &self.conformity_level())
+            .field("more_follows", &self.more_follows())
+            .field("next_object_id", &self.next_object_id())
+            .field("number_of_objects", &self.number_of_objects())
+            .finish()
+    }
+}
+
+/// Iterator over the objects of a Read Device Identification response
+///
+/// Stops early at an object running past the end of the response.
+#[derive(Debug, Clone)]
+pub struct DeviceObjects<'a> {
+    bytes: &'a [u8],
+    remaining: u8,
+}
+
+impl<'a> Iterator for DeviceObjects<'a> {
+    type Item = (u8, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let (&[object_id, length], rest) = self.bytes.split_first_chunk::<2>()?;
+        let value = rest.get(..length as usize)?;
+        self.bytes = &rest[length as usize..];
+        self.remaining -= 1;
+
+        Some((object_id, value))
+    }
+}
+
+/// Response of any function, dispatched on the function code
+///
+/// Exception responses and functions without a typed response are kept as `Other`, as is an
+/// Encapsulated Interface Transport response of another MEI type than Read Device
+/// Identification.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResponsePdu {
+    ReadCoils(ReadCoilsResponse),
+    ReadDiscreteInputs(ReadDiscreteInputsResponse),
+    ReadHoldingRegisters(ReadHoldingRegistersResponse),
+    ReadInputRegisters(ReadInputRegistersResponse),
+    WriteSingleCoil(WriteSingleCoilResponse),
+    WriteSingleRegister(WriteSingleRegisterResponse),
+    ReadExceptionStatus(ReadExceptionStatusResponse),
+    Diagnostics(DiagnosticsResponse),
+    GetCommEventCounter(GetCommEventCounterResponse),
+    WriteMultipleCoils(WriteMultipleCoilsResponse),
+    WriteMultipleRegisters(WriteMultipleRegistersResponse),
+    ReportServerId(ReportServerIdResponse),
+    MaskWriteRegister(MaskWriteRegisterResponse),
+    ReadWriteMultipleRegisters(ReadWriteMultipleRegistersResponse),
+    WriteFileRecord(WriteFileRecordResponse),
+    ReadDeviceIdentification(ReadDeviceIdentificationResponse),
+    Other(Pdu),
+}
+
+impl From<Pdu> for ResponsePdu {
+    fn from(pdu: Pdu) -> Self {
+        fn typed<T>(inner: Pdu) -> Response<T> {
+            Response {
+                inner,
+                _marker: PhantomData,
+            }
+        }
+
+        match pdu.function_code().map(PublicFunctionCode::try_from) {
+            Some(Ok(PublicFunctionCode::ReadCoils)) => Self::ReadCoils(typed(pdu)),
+            Some(Ok(PublicFunctionCode::ReadDiscreteInputs)) => {
+                Self::ReadDiscreteInputs(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::ReadHoldingRegisters)) => {
+                Self::ReadHoldingRegisters(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::ReadInputRegisters)) => {
+                Self::ReadInputRegisters(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::WriteSingleCoil)) => Self::WriteSingleCoil(typed(pdu)),
+            Some(Ok(PublicFunctionCode::WriteSingleRegister)) => {
+                Self::WriteSingleRegister(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::ReadExceptionStatus)) => {
+                Self::ReadExceptionStatus(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::Diagnostics)) => Self::Diagnostics(typed(pdu)),
+            Some(Ok(PublicFunctionCode::GetCommEventCounter)) => {
+                Self::GetCommEventCounter(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::WriteMultipleCoils)) => {
+                Self::WriteMultipleCoils(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::WriteMultipleRegisters)) => {
+                Self::WriteMultipleRegisters(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::ReportServerId)) => Self::ReportServerId(typed(pdu)),
+            Some(Ok(PublicFunctionCode::MaskWriteRegister)) => Self::MaskWriteRegister(typed(pdu)),
+            Some(Ok(PublicFunctionCode::ReadWriteMultipleRegisters)) => {
+                Self::ReadWriteMultipleRegisters(typed(pdu))
+            }
+            Some(Ok(PublicFunctionCode::WriteFileRecord)) => Self::WriteFileRecord(typed(pdu)),
+            Some(Ok(PublicFunctionCode::EncapsulatedInterfaceTransport))
+                if pdu.read_u8(0) == Some(READ_DEVICE_IDENTIFICATION_MEI_TYPE) =>
+            {
+                Self::ReadDeviceIdentification(typed(pdu))
+            }
+            _ => Self::Other(pdu),
+        }
+    }
+}
+
+impl From<ResponsePdu> for Pdu {
+    fn from(response: ResponsePdu) -> Self {
+        match response {
+            ResponsePdu::ReadCoils(response) => response.inner,
+            ResponsePdu::ReadDiscreteInputs(response) => response.inner,
+            ResponsePdu::ReadHoldingRegisters(response) => response.inner,
+            ResponsePdu::ReadInputRegisters(response) => response.inner,
+            ResponsePdu::WriteSingleCoil(response) => response.inner,
+            ResponsePdu::WriteSingleRegister(response) => response.inner,
+            ResponsePdu::ReadExceptionStatus(response) => response.inner,
+            ResponsePdu::Diagnostics(response) => response.inner,
+            ResponsePdu::GetCommEventCounter(response) => response.inner,
+            ResponsePdu::WriteMultipleCoils(response) => response.inner,
+            ResponsePdu::WriteMultipleRegisters(response) => response.inner,
+            ResponsePdu::ReportServerId(response) => response.inner,
+            ResponsePdu::MaskWriteRegister(response) => response.inner,
+            ResponsePdu::ReadWriteMultipleRegisters(response) => response.inner,
+            ResponsePdu::WriteFileRecord(response) => response.inner,
+            ResponsePdu::ReadDeviceIdentification(response) => response.inner,
+            ResponsePdu::Other(pdu) => pdu,
+        }
+    }
+}
+
+impl ResponsePdu {
+    /// Dispatch like `From<Pdu>`, also checking the data of the typed responses
+    ///
+    /// A read response must agree with its byte count, as required by `Response::try_from`.
+    pub fn new_validated(pdu: Pdu) -> Result<Self, ModbusPduError> {
+        fn checked<T: PublicFunction>(
+            response: Response<T>,
+        ) -> Result<Response<T>, ModbusPduError> {
+            T::check_response(&response.inner)?;
+            Ok(response)
+        }
+
+        Ok(match Self::from(pdu) {
+            Self::ReadCoils(response) => Self::ReadCoils(checked(response)?),
+            Self::ReadDiscreteInputs(response) => Self::ReadDiscreteInputs(checked(response)?),
+            Self::ReadHoldingRegisters(response) => Self::ReadHoldingRegisters(checked(response)?),
+            Self::ReadInputRegisters(response) => Self::ReadInputRegisters(checked(response)?),
+            Self::WriteSingleCoil(response) => Self::WriteSingleCoil(checked(response)?),
+            Self::WriteSingleRegister(response) => Self::WriteSingleRegister(checked(response)?),
+            Self::ReadExceptionStatus(response) => Self::ReadExceptionStatus(checked(response)?),
+            Self::Diagnostics(response) => Self::Diagnostics(checked(response)?),
+            Self::GetCommEventCounter(response) => Self::GetCommEventCounter(checked(response)?),
+            Self::WriteMultipleCoils(response) => Self::WriteMultipleCoils(checked(response)?),
+            Self::WriteMultipleRegisters(response) => {
+                Self::WriteMultipleRegisters(checked(response)?)
+            }
+            Self::ReportServerId(response) => Self::ReportServerId(checked(response)?),
+            Self::MaskWriteRegister(response) => Self::MaskWriteRegister(checked(response)?),
+            Self::ReadWriteMultipleRegisters(response) => {
+                Self::ReadWriteMultipleRegisters(checked(response)?)
+            }
+            Self::WriteFileRecord(response) => Self::WriteFileRecord(checked(response)?),
+            Self::ReadDeviceIdentification(response) => {
+                Self::ReadDeviceIdentification(checked(response)?)
+            }
+            Self::Other(pdu) => Self::Other(pdu),
+        })
+    }
+}
+
+/// Build a bit read response, packing the bits LSB first
+fn bits_response(code: PublicFunctionCode, bits: &[bool]) -> Result<Pdu, ModbusFrameError> {
+    if !(1..=2000).contains(&bits.len()) {
+        return Err(ModbusPduError::OutOfRange.into());
+    }
+
+    let mut pdu = Pdu::new(code.into())?;
+    pdu.put_u8(bits.len().div_ceil(8) as u8)?;
+    for byte in pack_bits(bits) {
+        pdu.put_u8(byte)?;
+    }
+
+    Ok(pdu)
+}
+
+/// Build a register read response
+fn registers_response(
+    code: PublicFunctionCode,
+    registers: &[u16],
+) -> Result<Pdu, ModbusFrameError> {
+    if !(1..=125).contains(&registers.len()) {
+        return Err(ModbusPduError::OutOfRange.into());
+    }
+
+    let mut pdu = Pdu::new(code.into())?;
+    pdu.put_u8(registers.len() as u8 * 2)?;
+    for register in registers {
+        pdu.put_u16(*register)?;
+    }
+
+    Ok(pdu)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::ResponseError;
+    use crate::frame::pdu::fcode::ExceptionCode;
 
     #[test]
     fn test_frame_pdu_fanction_rsp_read_coils() {
@@ -343,6 +1308,95 @@ mod tests {
         assert_eq!(coil_status.next(), None);
     }
 
+    #[test]
+    fn test_frame_pdu_fanction_rsp_byte_count_past_data() {
+        // Byte count of 0xFA with two bytes of data
+        let mut pdu = Pdu::new(0x01).unwrap();
+        pdu.put_slice(&[0xFA, 0x12, 0x34]).unwrap();
+        let ResponsePdu::ReadCoils(rsp) = ResponsePdu::from(pdu.clone()) else {
+            panic!("not dispatched as Read Coils");
+        };
+        assert!(rsp.coil_status().is_none());
+        assert!(rsp.coils(1).is_none());
+
+        pdu.as_slice_mut()[0] = 0x03;
+        let ResponsePdu::ReadHoldingRegisters(rsp) = ResponsePdu::from(pdu) else {
+            panic!("not dispatched as Read Holding Registers");
+        };
+        assert!(rsp.register_value().is_none());
+        assert_eq!(rsp.register(0), None);
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_coils_quantity() {
+        let rsp = ReadCoilsResponse::new(&[0xCD, 0x01]).unwrap();
+
+        let coils = rsp.coils(10).unwrap();
+        assert_eq!(coils.len(), 10);
+        assert!(coils.eq([true, false, true, true, false, false, true, true, true, false]));
+
+        assert_eq!(rsp.coils(16).unwrap().len(), 16);
+        assert!(rsp.coils(17).is_none());
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_coils_addressed() {
+        let rsp = ReadCoilsResponse::new(&[0xCD, 0x01]).unwrap();
+
+        assert!(rsp.addressed_coils(0x0013, 3).eq([
+            (0x0013, true),
+            (0x0014, false),
+            (0x0015, true)
+        ]));
+        assert!(rsp
+            .addressed_coils(0xFFFF, 2)
+            .eq([(0xFFFF, true), (0x0000, false)]));
+        assert_eq!(rsp.addressed_coils(0x0000, 17).count(), 0);
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_coils_into_coil_bits() {
+        let rsp = ReadCoilsResponse::new(&[0x12, 0x34]).unwrap();
+
+        let coils = rsp.clone().into_coil_bits::<10>().unwrap();
+        assert_eq!(coils.get(1), Some(true));
+        assert_eq!(coils.get(4), Some(true));
+        assert_eq!(coils.get(9), Some(false));
+
+        assert!(rsp.clone().into_coil_bits::<16>().is_some());
+        assert!(rsp.into_coil_bits::<17>().is_none());
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_coils_from_coils() {
+        let coils = [
+            false, true, false, false, true, false, false, false, false, true,
+        ];
+        let rsp = ReadCoilsResponse::from_coils(&coils).unwrap();
+        assert_eq!(rsp, ReadCoilsResponse::new(&[0x12, 0x02]).unwrap());
+        assert!(rsp.coil_status().unwrap().take(10).eq(coils));
+
+        assert!(ReadCoilsResponse::from_coils(&[]).is_err());
+        assert!(ReadCoilsResponse::from_coils(&[true; 2001]).is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_discrete_inputs_from_inputs() {
+        let rsp = ReadDiscreteInputsResponse::from_inputs(&[true, true, false]).unwrap();
+        assert_eq!(rsp, ReadDiscreteInputsResponse::new(&[0x03]).unwrap());
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_discrete_inputs_quantity() {
+        let rsp = ReadDiscreteInputsResponse::new(&[0xAC, 0xDB, 0x35]).unwrap();
+
+        let inputs = rsp.inputs(22).unwrap();
+        assert_eq!(inputs.len(), 22);
+        assert!(inputs.skip(16).eq([true, false, true, false, true, true]));
+
+        assert!(rsp.inputs(25).is_none());
+    }
+
     #[test]
     fn test_frame_pdu_fanction_rsp_read_discrete_inputs() {
         let input_status = [0x12, 0x34];
@@ -390,6 +1444,380 @@ mod tests {
         assert_eq!(rsp.register(2), None);
     }
 
+    #[test]
+    fn test_frame_pdu_fanction_rsp_parse() {
+        let mut pdu = Pdu::new(0x03).unwrap();
+        pdu.put_slice(&[0x02, 0x12, 0x34]).unwrap();
+        let rsp = ReadHoldingRegistersResponse::parse(pdu).unwrap();
+        assert_eq!(rsp.register(0), Some(0x1234));
+
+        let mut pdu = Pdu::new(0x83).unwrap();
+        pdu.put_u8(0x02).unwrap();
+        assert!(matches!(
+            ReadHoldingRegistersResponse::parse(pdu.clone()),
+            Err(ResponseError::Exception(ExceptionCode::IllegalDataAddress))
+        ));
+
+        // Exception to another function
+        assert!(matches!(
+            ReadInputRegistersResponse::parse(pdu),
+            Err(ResponseError::WrongFunction)
+        ));
+
+        let pdu = Pdu::new(0x04).unwrap();
+        assert!(matches!(
+            ReadHoldingRegistersResponse::parse(pdu),
+            Err(ResponseError::WrongFunction)
+        ));
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_try_from_byte_count() {
+        let pdu = |bytes: &[u8]| {
+            let mut pdu = Pdu::new(bytes[0]).unwrap();
+            pdu.put_slice(&bytes[1..]).unwrap();
+            pdu
+        };
+
+        assert!(ReadHoldingRegistersResponse::try_from(pdu(&[0x03, 0x02, 0x12, 0x34])).is_ok());
+        // Truncated, padded and odd byte count
+        assert!(ReadHoldingRegistersResponse::try_from(pdu(&[0x03, 0x04, 0x12, 0x34])).is_err());
+        assert!(ReadHoldingRegistersResponse::try_from(pdu(&[0x03, 0x01, 0x12, 0x34])).is_err());
+        assert!(
+            ReadInputRegistersResponse::try_from(pdu(&[0x04, 0x03, 0x12, 0x34, 0x56])).is_err()
+        );
+        assert!(ReadHoldingRegistersResponse::try_from(pdu(&[0x03])).is_err());
+
+        assert!(ReadCoilsResponse::try_from(pdu(&[0x01, 0x01, 0xCD])).is_ok());
+        assert!(ReadCoilsResponse::try_from(pdu(&[0x01, 0x02, 0xCD])).is_err());
+        assert!(ReadDiscreteInputsResponse::try_from(pdu(&[0x02, 0x00, 0xCD])).is_err());
+
+        // Parsing stays lenient, the accessors check the bounds
+        let rsp = ReadCoilsResponse::parse(pdu(&[0x01, 0x02, 0xCD])).unwrap();
+        assert!(rsp.coil_status().is_none());
+        let rsp = ReadHoldingRegistersResponse::parse(pdu(&[0x03, 0x04, 0x12, 0x34])).unwrap();
+        assert!(rsp.register_value().is_none());
+        assert_eq!(rsp.register(0), None);
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_raw_bytes() {
+        let mut pdu = Pdu::new(0x03).unwrap();
+        pdu.put_slice(&[0x02, 0x12, 0x34]).unwrap();
+
+        let rsp = ReadHoldingRegistersResponse::try_from(pdu).unwrap();
+        assert_eq!(rsp.register(0), Some(0x1234));
+        assert_eq!(rsp.raw_bytes(), &[0x03, 0x02, 0x12, 0x34]);
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_registers_from_registers() {
+        let rsp = ReadHoldingRegistersResponse::from_registers(&[0x1234, 0x5678]).unwrap();
+        assert_eq!(
+            rsp,
+            ReadHoldingRegistersResponse::new(&[0x12, 0x34, 0x56, 0x78]).unwrap()
+        );
+
+        let rsp = ReadInputRegistersResponse::from_registers(&[0xABCD]).unwrap();
+        assert_eq!(rsp.byte_count(), Some(2));
+        assert_eq!(rsp.register(0), Some(0xABCD));
+
+        assert!(ReadHoldingRegistersResponse::from_registers(&[0; 126]).is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_holding_registers_addressed() {
+        let rsp = ReadHoldingRegistersResponse::from_registers(&[0x022B, 0x0000, 0x0064]).unwrap();
+
+        assert!(rsp
+            .addressed(0x006B)
+            .eq([(0x006B, 0x022B), (0x006C, 0x0000), (0x006D, 0x0064)]));
+        assert!(rsp
+            .addressed(0xFFFE)
+            .eq([(0xFFFE, 0x022B), (0xFFFF, 0x0000), (0x0000, 0x0064)]));
+
+        // Byte count of 0x04 with two bytes of data
+        let mut pdu = Pdu::new(0x03).unwrap();
+        pdu.put_slice(&[0x04, 0x02, 0x2B]).unwrap();
+        let rsp = ReadHoldingRegistersResponse::parse(pdu).unwrap();
+        assert_eq!(rsp.addressed(0x0000).count(), 0);
+    }
+
+    #[test]
+    #[cfg(any(feature = "alloc", feature = "std"))]
+    fn test_frame_pdu_fanction_rsp_read_holding_registers_lossy() {
+        let rsp = ReadHoldingRegistersResponse::from_registers(&[0x0001, 0x0002]).unwrap();
+        assert_eq!(rsp.registers_lossy(), (Vec::from([0x0001, 0x0002]), false));
+
+        // Byte count claims 10 registers, only 8 and a half arrived
+        let mut pdu = Pdu::new(0x03).unwrap();
+        pdu.put_u8(20).unwrap();
+        for register in 0..8u16 {
+            pdu.put_u16(register).unwrap();
+        }
+        pdu.put_u8(0xFF).unwrap();
+        let rsp = ReadHoldingRegistersResponse::parse(pdu).unwrap();
+        assert_eq!(rsp.registers_lossy(), ((0..8).collect(), true));
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_holding_registers_bits() {
+        let rsp = ReadHoldingRegistersResponse::from_registers(&[0x0000, 0x8001]).unwrap();
+        assert!(rsp.register_bits(0).unwrap().all(|bit| !bit));
+
+        // Bit 15 comes last of the high byte, bit 0 first of the low byte
+        let mut expected = [false; 16];
+        expected[7] = true;
+        expected[8] = true;
+        assert!(rsp.register_bits(1).unwrap().eq(expected));
+
+        assert!(rsp.register_bits(2).is_none());
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_holding_registers_scaled() {
+        let rsp = ReadHoldingRegistersResponse::from_registers(&[2305, 0xFF38]).unwrap();
+
+        assert_eq!(rsp.register_scaled(0, 0.1), Some(230.5));
+        assert_eq!(rsp.register_scaled_signed(0, 0.1), Some(230.5));
+
+        // 0xFF38 is -200 as i16
+        assert_eq!(rsp.register_scaled(1, 0.5), Some(32668.0));
+        assert_eq!(rsp.register_scaled_signed(1, 0.5), Some(-100.0));
+        assert_eq!(rsp.register_scaled_signed(2, 0.5), None);
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_holding_registers_f32() {
+        // 1.5 = 0x3FC0_0000
+        let rsp = ReadHoldingRegistersResponse::from_registers(&[0x3FC0, 0x0000, 0x3FC0]).unwrap();
+
+        assert_eq!(rsp.register_f32(0, WordOrder::BigEndian), Some(1.5));
+        assert_eq!(rsp.register_f32(1, WordOrder::LittleEndian), Some(1.5));
+        assert_eq!(rsp.register_f32(2, WordOrder::BigEndian), None);
+        assert_eq!(rsp.register_f32(3, WordOrder::BigEndian), None);
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_registers_i16() {
+        let registers = [0xFFFF, 0x8000, 0x0001];
+
+        let rsp = ReadHoldingRegistersResponse::from_registers(&registers).unwrap();
+        assert_eq!(rsp.register_i16(0), Some(-1));
+        assert_eq!(rsp.register_i16(1), Some(-32768));
+        assert_eq!(rsp.register_i16(2), Some(1));
+        assert_eq!(rsp.register_i16(3), None);
+
+        let rsp = ReadInputRegistersResponse::from_registers(&registers).unwrap();
+        assert_eq!(rsp.register_i16(0), Some(-1));
+        assert_eq!(rsp.register_i16(1), Some(-32768));
+        assert_eq!(rsp.register_i16(3), None);
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_registers_integers() {
+        let registers = [0xFFFF, 0xFFFE, 0x0000, 0x0001];
+
+        let rsp = ReadHoldingRegistersResponse::from_registers(&registers).unwrap();
+        assert_eq!(rsp.register_i32(0, WordOrder::BigEndian), Some(-2));
+        assert_eq!(
+            rsp.register_u32(2, WordOrder::LittleEndian),
+            Some(0x0001_0000)
+        );
+        assert_eq!(rsp.register_u32(3, WordOrder::BigEndian), None);
+        assert_eq!(
+            rsp.register_u64(0, WordOrder::BigEndian),
+            Some(0xFFFF_FFFE_0000_0001)
+        );
+        assert_eq!(rsp.register_i64(1, WordOrder::BigEndian), None);
+
+        let rsp = ReadInputRegistersResponse::from_registers(&registers).unwrap();
+        assert_eq!(rsp.register_u32(0, WordOrder::BigEndian), Some(0xFFFF_FFFE));
+        assert_eq!(
+            rsp.register_i64(0, WordOrder::LittleEndian),
+            Some(0x0001_0000_FFFE_FFFF)
+        );
+        assert_eq!(rsp.register_i32(3, WordOrder::BigEndian), None);
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_exception_status() {
+        let rsp = ReadExceptionStatusResponse::new(0x6D).unwrap();
+        assert_eq!(rsp.output_data(), Some(0x6D));
+        assert!(rsp
+            .status_bits()
+            .unwrap()
+            .eq([true, false, true, true, false, true, true, false]));
+
+        let rsp = ReadExceptionStatusResponse::parse(Pdu::new(0x07).unwrap()).unwrap();
+        assert_eq!(rsp.output_data(), None);
+        assert!(rsp.status_bits().is_none());
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_get_comm_event_counter() {
+        let rsp = GetCommEventCounterResponse::new(0xFFFF, 0x0108).unwrap();
+        assert_eq!(rsp.status(), Some(0xFFFF));
+        assert_eq!(rsp.event_count(), Some(0x0108));
+        assert_eq!(rsp.is_busy(), Some(true));
+
+        let rsp = GetCommEventCounterResponse::new(0x0000, 0x0109).unwrap();
+        assert_eq!(rsp.is_busy(), Some(false));
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_write_multiple_coils() {
+        let rsp = WriteMultipleCoilsResponse::new(0x0013, 10).unwrap();
+        assert_eq!(rsp.as_pdu().as_slice(), &[0x0F, 0x00, 0x13, 0x00, 0x0A]);
+        assert_eq!(rsp.starting_address(), Some(0x0013));
+        assert_eq!(rsp.quantity_of_outputs(), Some(10));
+
+        let pdu = rsp.clone().into_inner();
+        assert_eq!(
+            ResponsePdu::from(pdu.clone()),
+            ResponsePdu::WriteMultipleCoils(rsp)
+        );
+        assert_eq!(Pdu::from(ResponsePdu::from(pdu.clone())), pdu);
+
+        // Exception responses are not dispatched
+        let mut pdu = Pdu::new(0x8F).unwrap();
+        pdu.put_u8(0x02).unwrap();
+        assert_eq!(ResponsePdu::from(pdu.clone()), ResponsePdu::Other(pdu));
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_response_pdu_new_validated() {
+        let rsp = ReadHoldingRegistersResponse::from_registers(&[0x1234]).unwrap();
+        assert_eq!(
+            ResponsePdu::new_validated(rsp.clone().into_inner()).unwrap(),
+            ResponsePdu::ReadHoldingRegisters(rsp)
+        );
+
+        // Byte count of 0x04 with two bytes of data
+        let mut pdu = Pdu::new(0x03).unwrap();
+        pdu.put_slice(&[0x04, 0x12, 0x34]).unwrap();
+        assert!(matches!(
+            ResponsePdu::from(pdu.clone()),
+            ResponsePdu::ReadHoldingRegisters(_)
+        ));
+        assert!(ResponsePdu::new_validated(pdu).is_err());
+
+        let pdu = Pdu::new(0x41).unwrap();
+        assert_eq!(
+            ResponsePdu::new_validated(pdu.clone()).unwrap(),
+            ResponsePdu::Other(pdu)
+        );
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_report_server_id() {
+        let rsp = ReportServerIdResponse::new(0x2A, true, b"v1.0").unwrap();
+        assert_eq!(rsp.byte_count(), Some(6));
+        assert_eq!(rsp.server_id(), Some(0x2A));
+        assert_eq!(rsp.run_indicator(), Some(true));
+        assert_eq!(rsp.additional_data(), Some(&b"v1.0"[..]));
+
+        let rsp = ReportServerIdResponse::new(0x2A, false, &[]).unwrap();
+        assert_eq!(rsp.run_indicator(), Some(false));
+        assert_eq!(rsp.additional_data(), Some(&[][..]));
+
+        // Noncompliant device
+        let mut pdu = Pdu::new(0x11).unwrap();
+        pdu.put_slice(&[0x02, 0x2A, 0x01]).unwrap();
+        let rsp = ReportServerIdResponse::try_from(pdu).unwrap();
+        assert_eq!(rsp.run_indicator(), None);
+        assert_eq!(rsp.raw_run_indicator(), Some(0x01));
+
+        let pdu = Pdu::new(0x11).unwrap();
+        let rsp = ReportServerIdResponse::try_from(pdu).unwrap();
+        assert_eq!(rsp.raw_run_indicator(), None);
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_mask_write_register() {
+        let rsp = MaskWriteRegisterResponse::new(0x0004, 0x00F2, 0x0025).unwrap();
+        assert_eq!(rsp.reference_address(), Some(0x0004));
+        assert_eq!(rsp.and_mask(), Some(0x00F2));
+        assert_eq!(rsp.or_mask(), Some(0x0025));
+
+        let pdu = rsp.clone().into_inner();
+        assert_eq!(ResponsePdu::from(pdu), ResponsePdu::MaskWriteRegister(rsp));
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_write_multiple_registers() {
+        let rsp = ReadWriteMultipleRegistersResponse::from_registers(&[0x00FE, 0x0ACD]).unwrap();
+        assert_eq!(
+            rsp.as_pdu().as_slice(),
+            &[0x17, 0x04, 0x00, 0xFE, 0x0A, 0xCD]
+        );
+        assert_eq!(rsp.byte_count(), Some(4));
+        assert!(rsp.register_value().unwrap().eq([0x00FE, 0x0ACD]));
+        assert_eq!(rsp.register(1), Some(0x0ACD));
+        assert_eq!(rsp.register(2), None);
+
+        let pdu = rsp.clone().into_inner();
+        assert_eq!(
+            ResponsePdu::from(pdu),
+            ResponsePdu::ReadWriteMultipleRegisters(rsp)
+        );
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_write_file_record() {
+        let records = [FileRecord::new(0x0004, 0x0007, &[0x06AF, 0x04BE, 0x100D])];
+        let rsp = WriteFileRecordResponse::new(&records).unwrap();
+        assert_eq!(rsp.response_data_length(), Some(0x0D));
+        assert_eq!(rsp.sub_requests().unwrap().count(), 1);
+        assert!(Request::<WriteFileRecord>::new(&records)
+            .unwrap()
+            .verify_echo(&rsp));
+        assert!(
+            !Request::<WriteFileRecord>::new(&[FileRecord::new(0x0004, 0x0007, &[0x06AF])])
+                .unwrap()
+                .verify_echo(&rsp)
+        );
+
+        let pdu = rsp.clone().into_inner();
+        assert_eq!(ResponsePdu::from(pdu), ResponsePdu::WriteFileRecord(rsp));
+
+        // Response data length beyond the sub-requests
+        let mut pdu = Pdu::new(0x15).unwrap();
+        pdu.put_slice(&[0x0E, 0x06, 0x00, 0x04, 0x00, 0x07, 0x00, 0x01, 0x06, 0xAF])
+            .unwrap();
+        assert!(WriteFileRecordResponse::try_from(pdu).is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_read_device_identification() {
+        let objects: [(u8, &[u8]); 2] = [(0x00, b"Vendor"), (0x01, b"P-01")];
+        let rsp = ReadDeviceIdentificationResponse::new(0x01, 0x81, true, 0x02, &objects).unwrap();
+        assert_eq!(rsp.mei_type(), Some(0x0E));
+        assert_eq!(rsp.conformity_level(), Some(0x81));
+        assert_eq!(rsp.more_follows(), Some(true));
+        assert_eq!(rsp.next_object_id(), Some(0x02));
+        assert!(rsp.objects().unwrap().eq(objects));
+
+        // Object length running past the end
+        let mut pdu = Pdu::new(0x2B).unwrap();
+        pdu.put_slice(&[
+            0x0E, 0x01, 0x01, 0x00, 0x00, 0x02, 0x00, 0x01, b'V', 0x01, 0x05,
+        ])
+        .unwrap();
+        let rsp = ReadDeviceIdentificationResponse::try_from(pdu.clone()).unwrap();
+        assert!(rsp.objects().unwrap().eq([(0x00, &b"V"[..])]));
+        assert_eq!(
+            ResponsePdu::from(pdu),
+            ResponsePdu::ReadDeviceIdentification(rsp)
+        );
+
+        // CANopen General Reference, another MEI type
+        let mut pdu = Pdu::new(0x2B).unwrap();
+        pdu.put_slice(&[0x0D, 0x00]).unwrap();
+        assert!(ReadDeviceIdentificationResponse::try_from(pdu.clone()).is_err());
+        assert_eq!(ResponsePdu::from(pdu.clone()), ResponsePdu::Other(pdu));
+    }
+
     #[test]
     fn test_frame_pdu_fanction_rsp_read_input_registers() {
         let input_registers = [0x12, 0x34, 0x56, 0x78];
@@ -404,6 +1832,29 @@ mod tests {
         assert_eq!(rsp.register(0), Some(0x1234));
         assert_eq!(rsp.register(1), Some(0x5678));
         assert_eq!(rsp.register(2), None);
+
+        // Byte count claims more registers than the response carries
+        let mut pdu = Pdu::new(0x04).unwrap();
+        pdu.put_slice(&[0x04, 0x12, 0x34]).unwrap();
+        let rsp = ReadInputRegistersResponse::parse(pdu).unwrap();
+        assert!(rsp.input_registers().is_none());
+        assert_eq!(rsp.register(0), None);
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_write_single_coil() {
+        let rsp = WriteSingleCoilResponse::new(0x00AC, true).unwrap();
+        assert_eq!(rsp.output_address(), Some(0x00AC));
+        assert_eq!(rsp.output_value(), Some(true));
+
+        // Only 0xFF00 switches the output on
+        for value in [0x0000u16, 0x00FF, 0xFFFF, 0x1234] {
+            let mut pdu = Pdu::new(0x05).unwrap();
+            pdu.put_u16(0x00AC).unwrap();
+            pdu.put_u16(value).unwrap();
+            let rsp = WriteSingleCoilResponse::parse(pdu).unwrap();
+            assert_eq!(rsp.output_value(), Some(false));
+        }
     }
 
     #[test]
@@ -413,6 +1864,38 @@ mod tests {
         assert_eq!(rsp.register_value(), Some(0x0304));
     }
 
+    #[test]
+    fn test_frame_pdu_fanction_rsp_diagnostics() {
+        let rsp = DiagnosticsResponse::new(0x0001, 0x0000).unwrap();
+        assert_eq!(rsp.sub_function(), Some(0x0001));
+        assert_eq!(rsp.data(), Some(0x0000));
+    }
+
+    #[test]
+    fn test_frame_pdu_fanction_rsp_exception() {
+        let rsp = ExceptionResponse::new(0x03, ExceptionCode::IllegalDataAddress).unwrap();
+        assert_eq!(rsp.as_pdu().as_slice(), &[0x83, 0x02]);
+        assert_eq!(rsp.function_code(), Some(0x03));
+        assert_eq!(
+            rsp.exception_code(),
+            Some(ExceptionCode::IllegalDataAddress)
+        );
+
+        let rsp = ExceptionResponse::try_from(rsp.into_inner()).unwrap();
+        assert_eq!(rsp.function_code(), Some(0x03));
+        assert!(ExceptionResponse::try_from(Pdu::new(0x03).unwrap()).is_err());
+
+        // Exactly one exception code
+        let pdu = Pdu::new(0x83).unwrap();
+        assert!(matches!(
+            ExceptionResponse::try_from(pdu.clone()),
+            Err(ModbusPduError::OutOfRange)
+        ));
+        let mut pdu = pdu;
+        pdu.put_slice(&[0x02, 0x00]).unwrap();
+        assert!(ExceptionResponse::try_from(pdu).is_err());
+    }
+
     #[test]
     fn test_frame_pdu_fanction_rsp_user_defined() {
         let data = [0x01, 0x02];
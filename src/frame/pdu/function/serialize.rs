@@ -0,0 +1,395 @@
+//! Serde support for requests and responses
+//!
+//! A typed PDU is serialized by its decoded fields, e.g. the starting address and the register
+//! values, and deserialized through its constructor, so that a round trip yields the same PDU.
+//! A field that can not be decoded fails the serialization.
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::request::*;
+use super::response::*;
+use super::*;
+use crate::frame::pdu::types::{FileRecord, FileSubRecords};
+
+/// Implement `Serialize`/`Deserialize` for a PDU type through a struct of its fields
+///
+/// Each field is read by an expression on `$pdu` returning an `Option`, and `$new` builds the
+/// PDU from the deserialized `$fields`.
+macro_rules! serde_fields {
+    (
+        $ty:ty,
+        |$pdu:ident| { $($field:ident : $field_ty:ty = $get:expr),* $(,)? },
+        |$fields:ident| $new:expr
+    ) => {
+        const _: () = {
+            #[derive(Serialize, Deserialize)]
+            struct Fields {
+                $($field: $field_ty,)*
+            }
+
+            impl Serialize for $ty {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    let $pdu = self;
+                    let fields = Fields {
+                        $($field: $get.ok_or_else(|| {
+                            <S::Error as ser::Error>::custom(concat!(
+                                "undecodable field `",
+                                stringify!($field),
+                                "`"
+                            ))
+                        })?,)*
+                    };
+
+                    fields.serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let $fields = Fields::deserialize(deserializer)?;
+                    $new.map_err(<D::Error as de::Error>::custom)
+                }
+            }
+        };
+    };
+}
+
+/// Sub-request of a Write File Record PDU
+#[derive(Serialize, Deserialize)]
+struct FileRecordFields {
+    reference_type: u8,
+    file_number: u16,
+    record_number: u16,
+    register_values: Vec<u16>,
+}
+
+fn file_record_fields(records: FileSubRecords<'_>) -> Vec<FileRecordFields> {
+    records
+        .map(|record| FileRecordFields {
+            reference_type: record.reference_type,
+            file_number: record.file_number,
+            record_number: record.record_number,
+            register_values: record.record_data.collect(),
+        })
+        .collect()
+}
+
+fn file_records(fields: &[FileRecordFields]) -> Vec<FileRecord<'_>> {
+    fields
+        .iter()
+        .map(|record| FileRecord {
+            reference_type: record.reference_type,
+            file_number: record.file_number,
+            record_number: record.record_number,
+            register_values: &record.register_values,
+        })
+        .collect()
+}
+
+serde_fields!(Pdu, |pdu| {
+    function_code: u8 = pdu.function_code(),
+    data: Vec<u8> = Some(pdu.data().to_vec()),
+}, |fields| {
+    Pdu::new(fields.function_code).and_then(|mut pdu| {
+        pdu.put_slice(&fields.data)?;
+        Ok(pdu)
+    })
+});
+
+serde_fields!(Request<ReadCoils>, |pdu| {
+    starting_address: u16 = pdu.starting_address(),
+    quantity_of_coils: u16 = pdu.quantity_of_coils(),
+}, |fields| ReadCoilsRequest::new(fields.starting_address, fields.quantity_of_coils));
+
+serde_fields!(Request<ReadDiscreteInputs>, |pdu| {
+    starting_address: u16 = pdu.starting_address(),
+    quantity_of_inputs: u16 = pdu.quantity_of_inputs(),
+}, |fields| ReadDiscreteInputsRequest::new(fields.starting_address, fields.quantity_of_inputs));
+
+serde_fields!(Request<ReadHoldingRegisters>, |pdu| {
+    starting_address: u16 = pdu.starting_address(),
+    quantity_of_registers: u16 = pdu.quantity_of_registers(),
+}, |fields| {
+    ReadHoldingRegistersRequest::new(fields.starting_address, fields.quantity_of_registers)
+});
+
+serde_fields!(Request<ReadInputRegisters>, |pdu| {
+    starting_address: u16 = pdu.starting_address(),
+    quantity_of_input_registers: u16 = pdu.quantity_of_input_registers(),
+}, |fields| {
+    ReadInputRegistersRequest::new(fields.starting_address, fields.quantity_of_input_registers)
+});
+
+serde_fields!(Request<WriteSingleCoil>, |pdu| {
+    output_address: u16 = pdu.output_address(),
+    output_value: bool = pdu.output_value(),
+}, |fields| WriteSingleCoilRequest::new(fields.output_address, fields.output_value));
+
+serde_fields!(Request<WriteSingleRegister>, |pdu| {
+    register_address: u16 = pdu.register_address(),
+    register_value: u16 = pdu.register_value(),
+}, |fields| WriteSingleRegisterRequest::new(fields.register_address, fields.register_value));
+
+serde_fields!(Request<ReadExceptionStatus>, |_pdu| {}, |_fields| {
+    ReadExceptionStatusRequest::new()
+});
+
+serde_fields!(Request<Diagnostics>, |pdu| {
+    sub_function: u16 = pdu.sub_function(),
+    data: u16 = pdu.data(),
+}, |fields| DiagnosticsRequest::new(fields.sub_function, fields.data));
+
+serde_fields!(Request<GetCommEventCounter>, |_pdu| {}, |_fields| {
+    GetCommEventCounterRequest::new()
+});
+
+serde_fields!(Request<WriteMultipleCoils>, |pdu| {
+    starting_address: u16 = pdu.starting_address(),
+    outputs_value: Vec<bool> = pdu
+        .outputs_value()
+        .zip(pdu.quantity_of_outputs())
+        .map(|(outputs, quantity)| outputs.take(quantity as usize).collect()),
+}, |fields| WriteMultipleCoilsRequest::new(fields.starting_address, &fields.outputs_value));
+
+serde_fields!(Request<WriteMultipleRegisters>, |pdu| {
+    starting_address: u16 = pdu.starting_address(),
+    registers_value: Vec<u16> = pdu.registers_value().map(Iterator::collect),
+}, |fields| {
+    WriteMultipleRegistersRequest::new(fields.starting_address, &fields.registers_value)
+});
+
+serde_fields!(Request<ReportServerId>, |_pdu| {}, |_fields| {
+    ReportServerIdRequest::new()
+});
+
+serde_fields!(Request<MaskWriteRegister>, |pdu| {
+    reference_address: u16 = pdu.reference_address(),
+    and_mask: u16 = pdu.and_mask(),
+    or_mask: u16 = pdu.or_mask(),
+}, |fields| {
+    MaskWriteRegisterRequest::new(fields.reference_address, fields.and_mask, fields.or_mask)
+});
+
+serde_fields!(Request<ReadWriteMultipleRegisters>, |pdu| {
+    read_starting_address: u16 = pdu.read_starting_address(),
+    quantity_to_read: u16 = pdu.quantity_to_read(),
+    write_starting_address: u16 = pdu.write_starting_address(),
+    write_registers_value: Vec<u16> = pdu.write_registers_value().map(Iterator::collect),
+}, |fields| {
+    ReadWriteMultipleRegistersRequest::new(
+        fields.read_starting_address,
+        fields.quantity_to_read,
+        fields.write_starting_address,
+        &fields.write_registers_value,
+    )
+});
+
+serde_fields!(Request<WriteFileRecord>, |pdu| {
+    sub_requests: Vec<FileRecordFields> = pdu.sub_requests().map(file_record_fields),
+}, |fields| WriteFileRecordRequest::new(&file_records(&fields.sub_requests)));
+
+serde_fields!(Request<ReadDeviceIdentification>, |pdu| {
+    read_device_id_code: u8 = pdu.read_device_id_code(),
+    object_id: u8 = pdu.object_id(),
+}, |fields| ReadDeviceIdentificationRequest::new(fields.read_device_id_code, fields.object_id));
+
+serde_fields!(Request<UserDefined>, |pdu| {
+    function_code: u8 = pdu.function_code(),
+    data: Vec<u8> = Some(pdu.data().to_vec()),
+}, |fields| UserDefinedRequest::new(fields.function_code, &fields.data));
+
+serde_fields!(Response<ReadCoils>, |pdu| {
+    coil_status: Vec<bool> = pdu.coil_status().map(Iterator::collect),
+}, |fields| ReadCoilsResponse::from_coils(&fields.coil_status));
+
+serde_fields!(Response<ReadDiscreteInputs>, |pdu| {
+    input_status: Vec<bool> = pdu.input_status().map(Iterator::collect),
+}, |fields| ReadDiscreteInputsResponse::from_inputs(&fields.input_status));
+
+serde_fields!(Response<ReadHoldingRegisters>, |pdu| {
+    register_value: Vec<u16> = pdu.register_value().map(Iterator::collect),
+}, |fields| ReadHoldingRegistersResponse::from_registers(&fields.register_value));
+
+serde_fields!(Response<ReadInputRegisters>, |pdu| {
+    input_registers: Vec<u16> = pdu.input_registers().map(Iterator::collect),
+}, |fields| ReadInputRegistersResponse::from_registers(&fields.input_registers));
+
+serde_fields!(Response<WriteSingleCoil>, |pdu| {
+    output_address: u16 = pdu.output_address(),
+    output_value: bool = pdu.output_value(),
+}, |fields| WriteSingleCoilResponse::new(fields.output_address, fields.output_value));
+
+serde_fields!(Response<WriteSingleRegister>, |pdu| {
+    register_address: u16 = pdu.register_address(),
+    register_value: u16 = pdu.register_value(),
+}, |fields| WriteSingleRegisterResponse::new(fields.register_address, fields.register_value));
+
+serde_fields!(Response<ReadExceptionStatus>, |pdu| {
+    output_data: u8 = pdu.output_data(),
+}, |fields| ReadExceptionStatusResponse::new(fields.output_data));
+
+serde_fields!(Response<Diagnostics>, |pdu| {
+    sub_function: u16 = pdu.sub_function(),
+    data: u16 = pdu.data(),
+}, |fields| DiagnosticsResponse::new(fields.sub_function, fields.data));
+
+serde_fields!(Response<GetCommEventCounter>, |pdu| {
+    status: u16 = pdu.status(),
+    event_count: u16 = pdu.event_count(),
+}, |fields| GetCommEventCounterResponse::new(fields.status, fields.event_count));
+
+serde_fields!(Response<WriteMultipleCoils>, |pdu| {
+    starting_address: u16 = pdu.starting_address(),
+    quantity_of_outputs: u16 = pdu.quantity_of_outputs(),
+}, |fields| WriteMultipleCoilsResponse::new(fields.starting_address, fields.quantity_of_outputs));
+
+serde_fields!(Response<WriteMultipleRegisters>, |pdu| {
+    starting_address: u16 = pdu.starting_address(),
+    quantity_of_registers: u16 = pdu.quantity_of_registers(),
+}, |fields| {
+    WriteMultipleRegistersResponse::new(fields.starting_address, fields.quantity_of_registers)
+});
+
+serde_fields!(Response<ReportServerId>, |pdu| {
+    server_id: u8 = pdu.server_id(),
+    run_indicator: bool = pdu.run_indicator(),
+    additional_data: Vec<u8> = pdu.additional_data().map(<[u8]>::to_vec),
+}, |fields| {
+    ReportServerIdResponse::new(
+        fields.server_id,
+        fields.run_indicator,
+        &fields.additional_data,
+    )
+});
+
+serde_fields!(Response<MaskWriteRegister>, |pdu| {
+    reference_address: u16 = pdu.reference_address(),
+    and_mask: u16 = pdu.and_mask(),
+    or_mask: u16 = pdu.or_mask(),
+}, |fields| {
+    MaskWriteRegisterResponse::new(fields.reference_address, fields.and_mask, fields.or_mask)
+});
+
+serde_fields!(Response<ReadWriteMultipleRegisters>, |pdu| {
+    register_value: Vec<u16> = pdu.register_value().map(Iterator::collect),
+}, |fields| ReadWriteMultipleRegistersResponse::from_registers(&fields.register_value));
+
+serde_fields!(Response<WriteFileRecord>, |pdu| {
+    sub_requests: Vec<FileRecordFields> = pdu.sub_requests().map(file_record_fields),
+}, |fields| WriteFileRecordResponse::new(&file_records(&fields.sub_requests)));
+
+serde_fields!(Response<ReadDeviceIdentification>, |pdu| {
+    read_device_id_code: u8 = pdu.read_device_id_code(),
+    conformity_level: u8 = pdu.conformity_level(),
+    more_follows: bool = pdu.more_follows(),
+    next_object_id: u8 = pdu.next_object_id(),
+    objects: Vec<(u8, Vec<u8>)> = pdu
+        .objects()
+        .map(|objects| objects.map(|(id, value)| (id, value.to_vec())).collect()),
+}, |fields| {
+    let objects: Vec<(u8, &[u8])> = fields
+        .objects
+        .iter()
+        .map(|(id, value)| (*id, value.as_slice()))
+        .collect();
+    ReadDeviceIdentificationResponse::new(
+        fields.read_device_id_code,
+        fields.conformity_level,
+        fields.more_follows,
+        fields.next_object_id,
+        &objects,
+    )
+});
+
+serde_fields!(Response<UserDefined>, |pdu| {
+    function_code: u8 = pdu.function_code(),
+    data: Vec<u8> = Some(pdu.data().to_vec()),
+}, |fields| UserDefinedResponse::new(fields.function_code, &fields.data));
+
+serde_fields!(ExceptionResponse, |pdu| {
+    function_code: u8 = pdu.function_code(),
+    exception_code: ExceptionCode = pdu.exception_code(),
+}, |fields| ExceptionResponse::new(fields.function_code, fields.exception_code));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T>(value: &T) -> T
+    where
+        T: Serialize + for<'de> Deserialize<'de>,
+    {
+        let json = serde_json::to_string(value).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_frame_pdu_function_serde_request() {
+        let req = ReadHoldingRegistersRequest::new(0x006B, 3).unwrap();
+        assert_eq!(
+            serde_json::to_string(&req).unwrap(),
+            r#"{"starting_address":107,"quantity_of_registers":3}"#
+        );
+        assert_eq!(round_trip(&req), req);
+
+        let req = WriteMultipleCoilsRequest::new(0x0013, &[true, false, true]).unwrap();
+        assert_eq!(round_trip(&req), req);
+
+        let req = RequestPdu::from(
+            WriteMultipleRegistersRequest::new(0x0001, &[0x000A, 0x0102])
+                .unwrap()
+                .into_inner(),
+        );
+        assert_eq!(
+            serde_json::to_string(&req).unwrap(),
+            r#"{"WriteMultipleRegisters":{"starting_address":1,"registers_value":[10,258]}}"#
+        );
+        assert_eq!(round_trip(&req), req);
+
+        let req = WriteFileRecordRequest::new(&[FileRecord::new(4, 7, &[0x06AF, 0x04BE])]).unwrap();
+        assert_eq!(round_trip(&req), req);
+
+        // Out of range fields are refused by the constructor
+        assert!(serde_json::from_str::<ReadHoldingRegistersRequest>(
+            r#"{"starting_address":0,"quantity_of_registers":126}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_frame_pdu_function_serde_response() {
+        let rsp = ResponsePdu::from(
+            ReadHoldingRegistersResponse::from_registers(&[0x022B, 0x0000])
+                .unwrap()
+                .into_inner(),
+        );
+        assert_eq!(
+            serde_json::to_string(&rsp).unwrap(),
+            r#"{"ReadHoldingRegisters":{"register_value":[555,0]}}"#
+        );
+        assert_eq!(round_trip(&rsp), rsp);
+
+        let rsp = ReadCoilsResponse::from_coils(&[true, false, true]).unwrap();
+        assert_eq!(round_trip(&rsp), rsp);
+
+        let objects: [(u8, &[u8]); 1] = [(0x00, b"Acme")];
+        let rsp = ReadDeviceIdentificationResponse::new(0x01, 0x81, false, 0x00, &objects).unwrap();
+        assert_eq!(round_trip(&rsp), rsp);
+
+        let rsp = ExceptionResponse::new(0x03, ExceptionCode::IllegalDataAddress).unwrap();
+        assert_eq!(
+            serde_json::to_string(&rsp).unwrap(),
+            r#"{"function_code":3,"exception_code":"IllegalDataAddress"}"#
+        );
+        assert_eq!(round_trip(&rsp), rsp);
+
+        // Undecodable fields can not be serialized
+        let mut pdu = Pdu::new(0x03).unwrap();
+        pdu.put_u8(0x04).unwrap();
+        let rsp = ResponsePdu::from(pdu.clone());
+        assert!(serde_json::to_string(&rsp).is_err());
+
+        let rsp = ResponsePdu::Other(Pdu::new(0x41).unwrap());
+        assert_eq!(round_trip(&rsp), rsp);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::ModbusPduError;
 use crate::lib::*;
 
 /// Iterator over bits in a byte array
@@ -5,6 +6,7 @@ pub struct BitSet<'a> {
     bytes: &'a [u8],
     byte_index: usize,
     bit_index: usize,
+    remaining: usize,
 }
 
 impl Debug for BitSet<'_> {
@@ -16,11 +18,22 @@ impl Debug for BitSet<'_> {
 }
 
 impl BitSet<'_> {
-    pub fn new(bytes: &[u8]) -> BitSet {
+    pub fn new(bytes: &[u8]) -> BitSet<'_> {
         BitSet {
             bytes,
             byte_index: 0,
             bit_index: 0,
+            remaining: bytes.len() * 8,
+        }
+    }
+
+    /// Bits of `bytes` stopping after `count`, leaving out the padding of the last byte
+    ///
+    /// `count` is clamped to the bits actually present.
+    pub fn with_limit(bytes: &[u8], count: usize) -> BitSet<'_> {
+        BitSet {
+            remaining: count.min(bytes.len() * 8),
+            ..BitSet::new(bytes)
         }
     }
 }
@@ -29,13 +42,14 @@ impl iter::Iterator for BitSet<'_> {
     type Item = bool;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.byte_index >= self.bytes.len() {
+        if self.remaining == 0 {
             return None;
         }
 
         // pick from LSB
         let bit = (self.bytes[self.byte_index] >> self.bit_index) & 0x01 != 0;
         self.bit_index += 1;
+        self.remaining -= 1;
 
         if self.bit_index >= 8 {
             self.byte_index += 1;
@@ -44,8 +58,14 @@ impl iter::Iterator for BitSet<'_> {
 
         Some(bit)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
 }
 
+impl iter::ExactSizeIterator for BitSet<'_> {}
+
 /// Iterator over 16-bit registers in a byte array
 pub struct RegisterSlice<'a> {
     bytes: &'a [u8],
@@ -60,17 +80,70 @@ impl Debug for RegisterSlice<'_> {
     }
 }
 
-impl RegisterSlice<'_> {
-    pub fn new(bytes: &[u8]) -> RegisterSlice {
+impl<'a> RegisterSlice<'a> {
+    pub fn new(bytes: &'a [u8]) -> RegisterSlice<'a> {
         RegisterSlice { bytes, index: 0 }
     }
+
+    /// Remaining registers read as two's complement `i16`
+    pub fn as_i16_iter(&self) -> impl Iterator<Item = i16> + 'a {
+        RegisterSlice {
+            bytes: self.bytes,
+            index: self.index,
+        }
+        .map(|value| value as i16)
+    }
+
+    /// Register at `index` from the start of the slice, regardless of iteration
+    pub fn get(&self, index: usize) -> Option<u16> {
+        let start = index.checked_mul(2)?;
+        let bytes = self.bytes.get(start..start.checked_add(2)?)?;
+
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Two registers from `index` combined into a `u32` in the given word order
+    pub fn read_u32(&self, index: usize, order: WordOrder) -> Option<u32> {
+        Some(order.from_registers_u32(self.get_array(index)?))
+    }
+
+    /// Two registers from `index` combined into an `i32` in the given word order
+    pub fn read_i32(&self, index: usize, order: WordOrder) -> Option<i32> {
+        self.read_u32(index, order).map(|value| value as i32)
+    }
+
+    /// Four registers from `index` combined into a `u64` in the given word order
+    pub fn read_u64(&self, index: usize, order: WordOrder) -> Option<u64> {
+        Some(order.from_registers_u64(self.get_array(index)?))
+    }
+
+    /// Four registers from `index` combined into an `i64` in the given word order
+    pub fn read_i64(&self, index: usize, order: WordOrder) -> Option<i64> {
+        self.read_u64(index, order).map(|value| value as i64)
+    }
+
+    /// Two registers from `index` combined into an `f32` in the given word order
+    pub fn read_f32(&self, index: usize, order: WordOrder) -> Option<f32> {
+        Some(order.from_registers_f32(self.get_array(index)?))
+    }
+
+    /// `N` consecutive registers from `index`
+    fn get_array<const N: usize>(&self, index: usize) -> Option<[u16; N]> {
+        let mut registers = [0; N];
+        for (offset, register) in registers.iter_mut().enumerate() {
+            *register = self.get(index.checked_add(offset)?)?;
+        }
+
+        Some(registers)
+    }
 }
 
 impl Iterator for RegisterSlice<'_> {
     type Item = u16;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index >= self.bytes.len() {
+        // A trailing odd byte is not a register
+        if self.index + 1 >= self.bytes.len() {
             return None;
         }
 
@@ -81,6 +154,224 @@ impl Iterator for RegisterSlice<'_> {
     }
 }
 
+/// Order of the 16-bit words of a value spanning several registers
+///
+/// Registers themselves are always big-endian; vendors disagree on which register comes first.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum WordOrder {
+    /// Most significant word in the first register
+    #[default]
+    BigEndian,
+    /// Least significant word in the first register
+    LittleEndian,
+}
+
+impl WordOrder {
+    /// Split a 32-bit value into two registers
+    pub fn to_registers_u32(self, value: u32) -> [u16; 2] {
+        let (high, low) = ((value >> 16) as u16, value as u16);
+
+        match self {
+            Self::BigEndian => [high, low],
+            Self::LittleEndian => [low, high],
+        }
+    }
+
+    /// Combine two registers into a 32-bit value
+    pub fn from_registers_u32(self, registers: [u16; 2]) -> u32 {
+        let [high, low] = match self {
+            Self::BigEndian => registers,
+            Self::LittleEndian => [registers[1], registers[0]],
+        };
+
+        ((high as u32) << 16) | low as u32
+    }
+
+    /// Split a 64-bit value into four registers
+    pub fn to_registers_u64(self, value: u64) -> [u16; 4] {
+        let mut registers = [
+            (value >> 48) as u16,
+            (value >> 32) as u16,
+            (value >> 16) as u16,
+            value as u16,
+        ];
+        if self == Self::LittleEndian {
+            registers.reverse();
+        }
+
+        registers
+    }
+
+    /// Combine four registers into a 64-bit value
+    pub fn from_registers_u64(self, mut registers: [u16; 4]) -> u64 {
+        if self == Self::LittleEndian {
+            registers.reverse();
+        }
+
+        registers
+            .iter()
+            .fold(0, |value, register| (value << 16) | *register as u64)
+    }
+
+    /// Split an IEEE-754 `f32` into two registers
+    pub fn to_registers_f32(self, value: f32) -> [u16; 2] {
+        self.to_registers_u32(value.to_bits())
+    }
+
+    /// Combine two registers into an IEEE-754 `f32`
+    pub fn from_registers_f32(self, registers: [u16; 2]) -> f32 {
+        f32::from_bits(self.from_registers_u32(registers))
+    }
+}
+
+/// Fixed-size set of `N` coils
+///
+/// Holds one `bool` per coil, as stable Rust cannot size a packed array from `N`. Use
+/// `packed_bytes` for the wire format, LSB first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoilBits<const N: usize> {
+    bits: [bool; N],
+}
+
+impl<const N: usize> Default for CoilBits<N> {
+    fn default() -> Self {
+        Self { bits: [false; N] }
+    }
+}
+
+impl<const N: usize> From<[bool; N]> for CoilBits<N> {
+    fn from(bits: [bool; N]) -> Self {
+        Self { bits }
+    }
+}
+
+impl<const N: usize> CoilBits<N> {
+    /// Create a set with every coil OFF
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take the first `N` bits of the iterator, or `None` if it yields fewer
+    pub fn from_bits(bits: impl Iterator<Item = bool>) -> Option<Self> {
+        let mut coils = Self::new();
+        let mut count = 0;
+
+        for (coil, bit) in coils.bits.iter_mut().zip(bits) {
+            *coil = bit;
+            count += 1;
+        }
+
+        (count == N).then_some(coils)
+    }
+
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.bits.get(index).copied()
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) -> Result<(), ModbusPduError> {
+        let coil = self.bits.get_mut(index).ok_or(ModbusPduError::OutOfRange)?;
+        *coil = value;
+
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        self.bits.iter().copied()
+    }
+
+    pub fn as_slice(&self) -> &[bool] {
+        &self.bits
+    }
+
+    /// Coils packed into bytes, LSB first, as carried by coil requests and responses
+    pub fn packed_bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        pack_bits(&self.bits)
+    }
+}
+
+/// Pack bits into bytes, LSB first, the last byte padded with zeros
+pub(crate) fn pack_bits(bits: &[bool]) -> impl Iterator<Item = u8> + '_ {
+    bits.chunks(8).map(|chunk| {
+        chunk
+            .iter()
+            .enumerate()
+            .fold(0, |byte, (index, bit)| byte | ((*bit as u8) << index))
+    })
+}
+
+/// Reference type of every file record sub-request
+pub const FILE_RECORD_REFERENCE_TYPE: u8 = 0x06;
+
+/// Sub-request of a file record request, addressing `register_values.len()` registers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FileRecord<'a> {
+    /// Must be `FILE_RECORD_REFERENCE_TYPE`
+    pub reference_type: u8,
+    pub file_number: u16,
+    pub record_number: u16,
+    pub register_values: &'a [u16],
+}
+
+impl<'a> FileRecord<'a> {
+    pub fn new(file_number: u16, record_number: u16, register_values: &'a [u16]) -> Self {
+        Self {
+            reference_type: FILE_RECORD_REFERENCE_TYPE,
+            file_number,
+            record_number,
+            register_values,
+        }
+    }
+}
+
+/// Sub-request decoded from a file record PDU
+#[derive(Debug)]
+pub struct FileSubRecord<'a> {
+    pub reference_type: u8,
+    pub file_number: u16,
+    pub record_number: u16,
+    pub record_data: RegisterSlice<'a>,
+}
+
+/// Iterator over the sub-requests of a file record PDU
+///
+/// Stops early at a sub-request running past the end of the data.
+#[derive(Debug, Clone)]
+pub struct FileSubRecords<'a> {
+    bytes: &'a [u8],
+}
+
+impl FileSubRecords<'_> {
+    pub fn new(bytes: &[u8]) -> FileSubRecords<'_> {
+        FileSubRecords { bytes }
+    }
+}
+
+impl<'a> Iterator for FileSubRecords<'a> {
+    type Item = FileSubRecord<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (header, rest) = self.bytes.split_first_chunk::<7>()?;
+        let record_length = u16::from_be_bytes([header[5], header[6]]) as usize;
+        let record_data = rest.get(..record_length * 2)?;
+        self.bytes = &rest[record_length * 2..];
+
+        Some(FileSubRecord {
+            reference_type: header[0],
+            file_number: u16::from_be_bytes([header[1], header[2]]),
+            record_number: u16::from_be_bytes([header[3], header[4]]),
+            record_data: RegisterSlice::new(record_data),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,11 +379,8 @@ mod tests {
     #[test]
     fn test_frame_pdu_types_bitset_next() {
         let bytes = [0b0001_0001, 0b0010_0010];
-        let mut bitset = BitSet {
-            bytes: &bytes,
-            byte_index: 0,
-            bit_index: 0,
-        };
+        let mut bitset = BitSet::new(&bytes);
+        assert_eq!(bitset.len(), 16);
 
         // first byte
         assert_eq!(bitset.next(), Some(true));
@@ -105,6 +393,7 @@ mod tests {
         assert_eq!(bitset.next(), Some(false));
 
         // second byte
+        assert_eq!(bitset.len(), 8);
         assert_eq!(bitset.next(), Some(false));
         assert_eq!(bitset.next(), Some(true));
         assert_eq!(bitset.next(), Some(false));
@@ -113,9 +402,22 @@ mod tests {
         assert_eq!(bitset.next(), Some(true));
         assert_eq!(bitset.next(), Some(false));
         assert_eq!(bitset.next(), Some(false));
+        assert_eq!(bitset.len(), 0);
         assert_eq!(bitset.next(), None);
     }
 
+    #[test]
+    fn test_frame_pdu_types_bitset_with_limit() {
+        let bytes = [0b0000_0101];
+        let bitset = BitSet::with_limit(&bytes, 3);
+        assert_eq!(bitset.len(), 3);
+        assert!(bitset.eq([true, false, true]));
+
+        // Clamped to the bits present
+        assert_eq!(BitSet::with_limit(&bytes, 9).len(), 8);
+        assert_eq!(BitSet::with_limit(&[], 1).next(), None);
+    }
+
     #[test]
     fn test_frame_pdu_types_register_slice_next() {
         let bytes = [0x01, 0x02, 0x03, 0x04];
@@ -127,5 +429,119 @@ mod tests {
         assert_eq!(register.next(), Some(0x0102));
         assert_eq!(register.next(), Some(0x0304));
         assert_eq!(register.next(), None);
+
+        let mut register = RegisterSlice::new(&bytes[..3]);
+        assert_eq!(register.next(), Some(0x0102));
+        assert_eq!(register.next(), None);
+    }
+
+    #[test]
+    fn test_frame_pdu_types_word_order() {
+        assert_eq!(
+            WordOrder::BigEndian.to_registers_u32(0x1234_5678),
+            [0x1234, 0x5678]
+        );
+        assert_eq!(
+            WordOrder::LittleEndian.to_registers_u32(0x1234_5678),
+            [0x5678, 0x1234]
+        );
+
+        for order in [WordOrder::BigEndian, WordOrder::LittleEndian] {
+            let registers = order.to_registers_u32(0xDEAD_BEEF);
+            assert_eq!(order.from_registers_u32(registers), 0xDEAD_BEEF);
+        }
+    }
+
+    #[test]
+    fn test_frame_pdu_types_register_slice_as_i16_iter() {
+        let bytes = [0xFF, 0xFF, 0x80, 0x00, 0x7F, 0xFF];
+        let mut registers = RegisterSlice::new(&bytes);
+
+        assert!(registers.as_i16_iter().eq([-1, -32768, 32767]));
+        registers.next();
+        assert!(registers.as_i16_iter().eq([-32768, 32767]));
+    }
+
+    #[test]
+    fn test_frame_pdu_types_register_slice_read_f32() {
+        // 1.5 = 0x3FC0_0000
+        let bytes = [0x3F, 0xC0, 0x00, 0x00, 0x3F, 0xC0];
+        let registers = RegisterSlice::new(&bytes);
+
+        assert_eq!(registers.get(0), Some(0x3FC0));
+        assert_eq!(registers.get(3), None);
+        assert_eq!(registers.read_f32(0, WordOrder::BigEndian), Some(1.5));
+        assert_eq!(registers.read_f32(1, WordOrder::LittleEndian), Some(1.5));
+        assert_eq!(registers.read_f32(2, WordOrder::BigEndian), None);
+
+        for order in [WordOrder::BigEndian, WordOrder::LittleEndian] {
+            let registers = order.to_registers_f32(-273.15);
+            assert_eq!(order.from_registers_f32(registers), -273.15);
+        }
+    }
+
+    #[test]
+    fn test_frame_pdu_types_register_slice_read_integers() {
+        let bytes = [0xFF, 0xFF, 0xFF, 0xFE, 0x00, 0x01];
+        let registers = RegisterSlice::new(&bytes);
+
+        assert_eq!(
+            registers.read_u32(0, WordOrder::BigEndian),
+            Some(0xFFFF_FFFE)
+        );
+        assert_eq!(registers.read_i32(0, WordOrder::BigEndian), Some(-2));
+        assert_eq!(
+            registers.read_u32(1, WordOrder::LittleEndian),
+            Some(0x0001_FFFE)
+        );
+        assert_eq!(registers.read_u32(2, WordOrder::BigEndian), None);
+        assert_eq!(registers.read_u64(0, WordOrder::BigEndian), None);
+
+        let bytes = [0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let registers = RegisterSlice::new(&bytes);
+        assert_eq!(registers.read_u64(0, WordOrder::BigEndian), Some(1 << 48));
+        assert_eq!(registers.read_i64(0, WordOrder::LittleEndian), Some(1));
+    }
+
+    #[test]
+    fn test_frame_pdu_types_word_order_u64() {
+        assert_eq!(
+            WordOrder::BigEndian.to_registers_u64(0x0123_4567_89AB_CDEF),
+            [0x0123, 0x4567, 0x89AB, 0xCDEF]
+        );
+        assert_eq!(
+            WordOrder::LittleEndian.to_registers_u64(0x0123_4567_89AB_CDEF),
+            [0xCDEF, 0x89AB, 0x4567, 0x0123]
+        );
+
+        for order in [WordOrder::BigEndian, WordOrder::LittleEndian] {
+            let registers = order.to_registers_u64(u64::MAX - 1);
+            assert_eq!(order.from_registers_u64(registers), u64::MAX - 1);
+        }
+    }
+
+    #[test]
+    fn test_frame_pdu_types_coil_bits() {
+        let mut coils = CoilBits::<10>::new();
+        assert_eq!(coils.len(), 10);
+        assert_eq!(coils.get(0), Some(false));
+        assert_eq!(coils.get(10), None);
+
+        coils.set(0, true).unwrap();
+        coils.set(9, true).unwrap();
+        assert!(coils.set(10, true).is_err());
+
+        assert_eq!(coils.iter().filter(|bit| *bit).count(), 2);
+        assert!(coils.packed_bytes().eq([0b0000_0001, 0b0000_0010]));
+    }
+
+    #[test]
+    fn test_frame_pdu_types_coil_bits_from_bits() {
+        let bytes = [0b0000_0101];
+
+        let coils = CoilBits::<3>::from_bits(BitSet::new(&bytes)).unwrap();
+        assert_eq!(coils, CoilBits::from([true, false, true]));
+
+        assert!(CoilBits::<9>::from_bits(BitSet::new(&bytes)).is_none());
     }
 }
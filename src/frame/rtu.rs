@@ -1,26 +1,32 @@
-use super::{pdu::Pdu, DataUnit};
+use super::{
+    crc::calc_crc,
+    pdu::{Pdu, MAX_PDU_SIZE},
+    DataUnit,
+};
 use crate::error::{ModbusFrameError, ModbusRtuError};
 use crate::lib::*;
 
-const MAX_ADU_SIZE: usize = 256;
+const MAX_ADU_SIZE: usize = MAX_PDU_SIZE + 3;
 
 /// Modbus RTU Application Data Unit
 /// # Structure
 /// * Slave Address : `u8`
 /// * PDU : `FunctionCode` + `Data` (MAX : 253 bytes)
 /// * CRC : `[u8; 2]`
+///
+/// `N` defaults to the frame of the largest standard PDU, see [`Pdu`] for larger sizes.
 #[derive(Debug, Clone, Default, PartialEq)]
-pub struct Adu(DataUnit<MAX_ADU_SIZE>);
+pub struct Adu<const N: usize = MAX_ADU_SIZE>(DataUnit<N>);
 
-impl Deref for Adu {
-    type Target = DataUnit<MAX_ADU_SIZE>;
+impl<const N: usize> Deref for Adu<N> {
+    type Target = DataUnit<N>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl DerefMut for Adu {
+impl<const N: usize> DerefMut for Adu<N> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
@@ -33,6 +39,15 @@ impl RtuFrameHandler {
         adu: &mut Adu,
         slave_address: u8,
         pdu: &Pdu,
+    ) -> Result<usize, ModbusFrameError> {
+        Self::build_frame_sized(adu, slave_address, pdu)
+    }
+
+    /// Build a frame of a PDU of any size, see [`Pdu::new_sized`]
+    pub fn build_frame_sized<const A: usize, const P: usize>(
+        adu: &mut Adu<A>,
+        slave_address: u8,
+        pdu: &Pdu<P>,
     ) -> Result<usize, ModbusFrameError> {
         adu.clear();
 
@@ -44,21 +59,65 @@ impl RtuFrameHandler {
         Ok(adu.len())
     }
 
+    /// Parse the PDU from the given frame
+    ///
+    /// An `expected_address` of `0` accepts frames from any address, but the CRC is always
+    /// validated so that line noise is never taken for a valid frame.
     pub fn parse_frame(frame: &[u8], expected_address: u8) -> Result<Pdu, ModbusFrameError> {
-        check_frame_length(frame)?;
+        Self::parse_frame_sized(frame, expected_address)
+    }
+
+    /// Parse a PDU of up to `P` bytes from the given frame
+    ///
+    /// Frames are limited to hold such a PDU instead of the largest standard one.
+    pub fn parse_frame_sized<const P: usize>(
+        frame: &[u8],
+        expected_address: u8,
+    ) -> Result<Pdu<P>, ModbusFrameError> {
+        check_frame_length(frame, P + 3)?;
         check_frame_address(frame, expected_address)?;
         check_frame_crc(frame)?;
 
-        let mut pdu = Pdu::new(frame[1])?;
+        let mut pdu = Pdu::new_sized(frame[1])?;
         pdu.put_slice(&frame[2..frame.len() - 2])?;
 
         Ok(pdu)
     }
+
+    /// Parse the given frame and build it again, recomputing the CRC
+    ///
+    /// A frame that survives this unchanged proves the parse and build paths agree.
+    pub fn reencode(frame: &[u8]) -> Result<Adu, ModbusFrameError> {
+        let pdu = Self::parse_frame(frame, 0)?;
+
+        let mut adu = Adu::default();
+        Self::build_frame(&mut adu, frame[0], &pdu)?;
+
+        Ok(adu)
+    }
+
+    /// Skip the `0x00` bytes some serial adapters emit ahead of a frame at line-idle transitions
+    pub fn strip_leading_nulls(frame: &[u8]) -> &[u8] {
+        let start = frame
+            .iter()
+            .position(|byte| *byte != 0x00)
+            .unwrap_or(frame.len());
+
+        &frame[start..]
+    }
+
+    /// Peek the `(slave_address, function_code)` of a possibly incomplete frame
+    pub fn peek_header(frame: &[u8]) -> Option<(u8, u8)> {
+        match frame {
+            [slave_address, function_code, ..] => Some((*slave_address, *function_code)),
+            _ => None,
+        }
+    }
 }
 
-/// Check the Modbus RTU frame length of the given frame
-fn check_frame_length(frame: &[u8]) -> Result<(), ModbusRtuError> {
-    if frame.len() < 4 || frame.len() > MAX_ADU_SIZE {
+/// Check the Modbus RTU frame length of the given frame against the largest frame
+fn check_frame_length(frame: &[u8], max_len: usize) -> Result<(), ModbusRtuError> {
+    if frame.len() < 4 || frame.len() > max_len {
         Err(ModbusRtuError::InvalidFrameLength)
     } else {
         Ok(())
@@ -91,78 +150,103 @@ fn checksum(data: &[u8], crc: u16) -> Result<(), ModbusRtuError> {
     }
 }
 
-/// Calculate the Modbus 16-bit CRC for the given data
-fn calc_crc(data: &[u8]) -> u16 {
-    let mut crc: u16 = 0xFFFF;
-    for byte in data {
-        crc = (crc >> 8) ^ MODBUS_16_CRC[((crc ^ (*byte as u16)) & 0xFF) as usize];
-    }
-
-    crc
-}
-
-/// Lookup table for the Modbus 16-bit CRC algorithm
-const MODBUS_16_CRC: [u16; 256] = generate_crc_table();
-
-/// Generate a lookup table for the Modbus 16-bit CRC algorithm
-const fn generate_crc_table() -> [u16; 256] {
-    let mut table = [0u16; 256];
-    let mut i = 0;
-
-    while i < 256 {
-        let mut crc = i as u16;
-        let mut j = 0;
-        while j < 8 {
-            if crc & 0x0001 != 0 {
-                crc = (crc >> 1) ^ 0xA001;
-            } else {
-                crc >>= 1;
-            }
-            j += 1;
-        }
-        table[i] = crc;
-        i += 1;
-    }
-
-    table
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_frame_rtu_calc_crc_with_standard_data() {
-        let data = b"123456789";
-        let expected_crc = 0x4B37;
-        assert_eq!(calc_crc(data), expected_crc);
+    fn test_frame_rtu_parse_frame_accept_all_address() {
+        let frame = [0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9B];
+        let pdu = RtuFrameHandler::parse_frame(&frame, 0).unwrap();
+        assert_eq!(pdu.as_slice(), &frame[1..6]);
     }
 
     #[test]
-    fn test_frame_rtu_calc_crc_with_empty_data() {
-        let data: [u8; 0] = [];
-        let expected_crc = 0xFFFF;
-        assert_eq!(calc_crc(&data), expected_crc);
+    fn test_frame_rtu_parse_frame_accept_all_address_rejects_bad_crc() {
+        let frame = [0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9C];
+        assert!(matches!(
+            RtuFrameHandler::parse_frame(&frame, 0),
+            Err(ModbusFrameError::RtuError(
+                ModbusRtuError::CrcValidationFailure
+            ))
+        ));
+
+        // Short noise burst passing the length check
+        let noise = [0x5A, 0xA5, 0x3C, 0xC3];
+        assert!(matches!(
+            RtuFrameHandler::parse_frame(&noise, 0),
+            Err(ModbusFrameError::RtuError(
+                ModbusRtuError::CrcValidationFailure
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_frame_rtu_reencode() {
+        let frames: [&[u8]; 3] = [
+            &[0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9B],
+            &[0x01, 0x03, 0x02, 0x12, 0x34, 0xB5, 0x33],
+            &[0x01, 0x83, 0x02, 0xC0, 0xF1],
+        ];
+
+        for frame in frames {
+            let adu = RtuFrameHandler::reencode(frame).unwrap();
+            assert_eq!(adu.as_slice(), frame);
+        }
+
+        assert!(
+            RtuFrameHandler::reencode(&[0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9C]).is_err()
+        );
     }
 
     #[test]
-    fn test_frame_rtu_calc_crc_with_single_byte() {
-        let data = [0x01];
-        let expected_crc = 0x807E;
-        assert_eq!(calc_crc(&data), expected_crc);
+    fn test_frame_rtu_sized_frame() {
+        let mut pdu = Pdu::<300>::new_sized(0x41).unwrap();
+        pdu.put_slice(&[0x5A; 280]).unwrap();
+
+        let mut adu = Adu::<303>::default();
+        let len = RtuFrameHandler::build_frame_sized(&mut adu, 0x11, &pdu).unwrap();
+        assert_eq!(len, 284);
+
+        let parsed = RtuFrameHandler::parse_frame_sized::<300>(adu.as_slice(), 0x11).unwrap();
+        assert_eq!(parsed, pdu);
+
+        // Beyond the standard frame
+        assert!(matches!(
+            RtuFrameHandler::parse_frame(adu.as_slice(), 0x11),
+            Err(ModbusFrameError::RtuError(
+                ModbusRtuError::InvalidFrameLength
+            ))
+        ));
     }
 
     #[test]
-    fn test_frame_rtu_calc_crc_with_multiple_bytes() {
-        let data = [0x01, 0x02, 0x03, 0x04];
-        let expected_crc = 0x2BA1;
-        assert_eq!(calc_crc(&data), expected_crc);
+    fn test_frame_rtu_strip_leading_nulls() {
+        let frame = [0x00, 0x00, 0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9B];
+        let stripped = RtuFrameHandler::strip_leading_nulls(&frame);
+        assert_eq!(stripped, &frame[2..]);
+        assert!(RtuFrameHandler::parse_frame(stripped, 0x11).is_ok());
+
+        assert_eq!(
+            RtuFrameHandler::strip_leading_nulls(&frame[2..]),
+            &frame[2..]
+        );
+        assert_eq!(
+            RtuFrameHandler::strip_leading_nulls(&[0x00, 0x00]),
+            &[] as &[u8]
+        );
     }
 
     #[test]
-    fn test_frame_rtu_calc_crc_with_edge_values() {
-        let data = [0xFF, 0x00, 0xFF, 0x00];
-        let expected_crc = 0xC071;
-        assert_eq!(calc_crc(&data), expected_crc);
+    fn test_frame_rtu_peek_header() {
+        assert_eq!(RtuFrameHandler::peek_header(&[0x11]), None);
+        assert_eq!(
+            RtuFrameHandler::peek_header(&[0x11, 0x03]),
+            Some((0x11, 0x03))
+        );
+        assert_eq!(
+            RtuFrameHandler::peek_header(&[0x11, 0x83, 0x02]),
+            Some((0x11, 0x83))
+        );
     }
 }
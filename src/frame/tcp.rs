@@ -1 +1,246 @@
+use super::{
+    pdu::{Pdu, MAX_PDU_SIZE},
+    DataUnit,
+};
+use crate::error::{ModbusFrameError, ModbusTcpError};
+use crate::lib::*;
 
+const MAX_ADU_SIZE: usize = MBAP_HEADER_SIZE + MAX_PDU_SIZE;
+const MBAP_HEADER_SIZE: usize = 7;
+const PROTOCOL_ID: u16 = 0x0000;
+/// Largest valid length field, the unit identifier and a full PDU
+const MAX_LENGTH: u16 = (MAX_ADU_SIZE - MBAP_HEADER_SIZE + 1) as u16;
+
+/// Modbus TCP Application Data Unit
+/// # Structure
+/// * MBAP Header : `[u8; 7]`
+/// * PDU : `FunctionCode` + `Data` (MAX : 253 bytes)
+///
+/// `N` defaults to the frame of the largest standard PDU, see [`Pdu`] for larger sizes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Adu<const N: usize = MAX_ADU_SIZE>(DataUnit<N>);
+
+impl<const N: usize> Deref for Adu<N> {
+    type Target = DataUnit<N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for Adu<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Modbus Application Protocol header
+/// # Structure
+/// * Transaction Identifier : `u16`
+/// * Protocol Identifier : `u16`
+/// * Length : `u16` (Unit Identifier + PDU)
+/// * Unit Identifier : `u8`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MbapHeader {
+    pub transaction_id: u16,
+    pub protocol_id: u16,
+    pub length: u16,
+    pub unit_id: u8,
+}
+
+impl MbapHeader {
+    pub const SIZE: usize = MBAP_HEADER_SIZE;
+
+    pub fn parse(frame: &[u8]) -> Result<Self, ModbusTcpError> {
+        if frame.len() < MBAP_HEADER_SIZE {
+            return Err(ModbusTcpError::InvalidFrameLength);
+        }
+
+        Ok(Self {
+            transaction_id: u16::from_be_bytes([frame[0], frame[1]]),
+            protocol_id: u16::from_be_bytes([frame[2], frame[3]]),
+            length: u16::from_be_bytes([frame[4], frame[5]]),
+            unit_id: frame[6],
+        })
+    }
+
+    /// Check the length field against the largest ADU
+    ///
+    /// The length field comes from the peer, so it must be checked before waiting for that
+    /// many bytes.
+    pub fn validate_length(&self) -> Result<(), ModbusTcpError> {
+        if (1..=MAX_LENGTH).contains(&self.length) {
+            Ok(())
+        } else {
+            Err(ModbusTcpError::InvalidLength(self.length))
+        }
+    }
+
+    /// Total length of the ADU described by this header
+    pub fn adu_length(&self) -> usize {
+        MBAP_HEADER_SIZE - 1 + self.length as usize
+    }
+}
+
+pub struct TcpFrameHandler;
+
+impl TcpFrameHandler {
+    pub fn build_frame(
+        adu: &mut Adu,
+        transaction_id: u16,
+        unit_id: u8,
+        pdu: &Pdu,
+    ) -> Result<usize, ModbusFrameError> {
+        Self::build_frame_sized(adu, transaction_id, unit_id, pdu)
+    }
+
+    /// Build a frame of a PDU of any size, see [`Pdu::new_sized`]
+    pub fn build_frame_sized<const A: usize, const P: usize>(
+        adu: &mut Adu<A>,
+        transaction_id: u16,
+        unit_id: u8,
+        pdu: &Pdu<P>,
+    ) -> Result<usize, ModbusFrameError> {
+        adu.clear();
+
+        adu.put_u16(transaction_id)?;
+        adu.put_u16(PROTOCOL_ID)?;
+        adu.put_u16(pdu.len() as u16 + 1)?;
+        adu.put_u8(unit_id)?;
+        adu.put_slice(pdu.as_slice())?;
+
+        Ok(adu.len())
+    }
+
+    pub fn parse_frame(frame: &[u8]) -> Result<(MbapHeader, Pdu), ModbusFrameError> {
+        Self::parse_frame_sized(frame)
+    }
+
+    /// Parse a PDU of up to `P` bytes from the given frame
+    pub fn parse_frame_sized<const P: usize>(
+        frame: &[u8],
+    ) -> Result<(MbapHeader, Pdu<P>), ModbusFrameError> {
+        let header = MbapHeader::parse(frame)?;
+        check_protocol_id(&header)?;
+        check_frame_length(frame, &header)?;
+
+        let mut pdu = Pdu::new_sized(frame[MBAP_HEADER_SIZE])?;
+        pdu.put_slice(&frame[MBAP_HEADER_SIZE + 1..])?;
+
+        Ok((header, pdu))
+    }
+
+    /// Peek the `(unit_id, function_code)` of a possibly incomplete frame
+    pub fn peek_header(frame: &[u8]) -> Option<(u8, u8)> {
+        let header = MbapHeader::parse(frame).ok()?;
+        let function_code = frame.get(MBAP_HEADER_SIZE)?;
+
+        Some((header.unit_id, *function_code))
+    }
+}
+
+/// Check the Modbus TCP protocol identifier of the given header
+fn check_protocol_id(header: &MbapHeader) -> Result<(), ModbusTcpError> {
+    if header.protocol_id == PROTOCOL_ID {
+        Ok(())
+    } else {
+        Err(ModbusTcpError::InvalidProtocolId(header.protocol_id))
+    }
+}
+
+/// Check the Modbus TCP frame length against the length field of the given header
+fn check_frame_length(frame: &[u8], header: &MbapHeader) -> Result<(), ModbusTcpError> {
+    match header.length {
+        0 => Err(ModbusTcpError::InvalidFrameLength),
+        // Unit identifier only, some gateways answer a failed transaction this way
+        1 => Err(ModbusTcpError::EmptyResponse),
+        _ if frame.len() != header.adu_length() => Err(ModbusTcpError::InvalidFrameLength),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_tcp_build_frame() {
+        let mut adu = Adu::default();
+        let mut pdu = Pdu::new(0x03).unwrap();
+        pdu.put_u16(0x0000).unwrap();
+        pdu.put_u16(0x0001).unwrap();
+
+        let len = TcpFrameHandler::build_frame(&mut adu, 0x0102, 0x11, &pdu).unwrap();
+        assert_eq!(len, 12);
+        assert_eq!(
+            adu.as_slice(),
+            &[0x01, 0x02, 0x00, 0x00, 0x00, 0x06, 0x11, 0x03, 0x00, 0x00, 0x00, 0x01]
+        );
+    }
+
+    #[test]
+    fn test_frame_tcp_parse_frame() {
+        let frame = [
+            0x01, 0x02, 0x00, 0x00, 0x00, 0x05, 0x11, 0x03, 0x02, 0x12, 0x34,
+        ];
+        let (header, pdu) = TcpFrameHandler::parse_frame(&frame).unwrap();
+        assert_eq!(header.transaction_id, 0x0102);
+        assert_eq!(header.unit_id, 0x11);
+        assert_eq!(pdu.function_code(), Some(0x03));
+        assert_eq!(pdu.data(), &[0x02, 0x12, 0x34]);
+    }
+
+    #[test]
+    fn test_frame_tcp_sized_frame() {
+        let mut pdu = Pdu::<1024>::new_sized(0x41).unwrap();
+        pdu.put_slice(&[0x5A; 1000]).unwrap();
+
+        let mut adu = Adu::<1031>::default();
+        let len = TcpFrameHandler::build_frame_sized(&mut adu, 0x0001, 0x11, &pdu).unwrap();
+        assert_eq!(len, 1008);
+
+        let (header, parsed) = TcpFrameHandler::parse_frame_sized::<1024>(adu.as_slice()).unwrap();
+        assert_eq!(header.length, 1002);
+        assert_eq!(parsed, pdu);
+
+        // The standard PDU cannot hold it
+        assert!(TcpFrameHandler::parse_frame(adu.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_frame_tcp_peek_header() {
+        let frame = [0x01, 0x02, 0x00, 0x00, 0x00, 0x05, 0x11, 0x03, 0x02];
+        assert_eq!(TcpFrameHandler::peek_header(&frame[..7]), None);
+        assert_eq!(
+            TcpFrameHandler::peek_header(&frame[..8]),
+            Some((0x11, 0x03))
+        );
+        assert_eq!(TcpFrameHandler::peek_header(&frame), Some((0x11, 0x03)));
+    }
+
+    #[test]
+    fn test_frame_tcp_parse_frame_empty_response() {
+        let frame = [0x01, 0x02, 0x00, 0x00, 0x00, 0x01, 0x11];
+        assert!(matches!(
+            TcpFrameHandler::parse_frame(&frame),
+            Err(ModbusFrameError::TcpError(ModbusTcpError::EmptyResponse))
+        ));
+    }
+
+    #[test]
+    fn test_frame_tcp_parse_frame_invalid_protocol_id() {
+        let frame = [0x01, 0x02, 0x00, 0x01, 0x00, 0x02, 0x11, 0x03];
+        assert!(matches!(
+            TcpFrameHandler::parse_frame(&frame),
+            Err(ModbusFrameError::TcpError(
+                ModbusTcpError::InvalidProtocolId(0x0001)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_frame_tcp_parse_frame_length_mismatch() {
+        let frame = [0x01, 0x02, 0x00, 0x00, 0x00, 0x06, 0x11, 0x03];
+        assert!(TcpFrameHandler::parse_frame(&frame).is_err());
+    }
+}
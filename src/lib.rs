@@ -23,6 +23,11 @@ mod lib {
     #[cfg(feature = "std")]
     pub use std::boxed::Box;
 
+    #[cfg(all(feature = "alloc", not(feature = "std")))]
+    pub use alloc::vec::Vec;
+    #[cfg(feature = "std")]
+    pub use std::vec::Vec;
+
     #[cfg(any(feature = "alloc", feature = "std"))]
     pub use self::core::error;
     #[cfg(any(feature = "alloc", feature = "std"))]
@@ -30,6 +35,10 @@ mod lib {
 }
 
 pub mod app;
+#[cfg(any(feature = "alloc", feature = "std"))]
+pub mod codec;
+#[cfg(any(feature = "alloc", feature = "std"))]
+pub mod conformance;
 pub mod error;
 pub mod frame;
 
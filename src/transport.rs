@@ -1,12 +1,42 @@
+use core::time::Duration;
+
 use crate::frame::pdu::Pdu;
 use crate::lib::*;
 
+pub mod fault;
+
+#[cfg(test)]
+pub(crate) mod mock;
+
+#[cfg(feature = "tokio")]
+pub mod multibus;
+
+#[cfg(feature = "ascii")]
+pub mod ascii;
+
 #[cfg(feature = "rtu")]
 pub mod rtu;
 
 #[cfg(feature = "tcp")]
 pub mod tcp;
 
+/// Timing of the latest request/response transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TransactionTiming {
+    /// Time to put the request on the wire
+    pub write: Duration,
+    /// Time from the end of the request to the first byte of the response
+    pub turnaround: Duration,
+    /// Time from the first byte of the response until it was complete
+    pub read: Duration,
+}
+
+impl TransactionTiming {
+    pub fn total(&self) -> Duration {
+        self.write + self.turnaround + self.read
+    }
+}
+
 /// Transport/DataLink layer abstraction
 pub trait Transport {
     /// Send a Protocol Data Unit
@@ -22,4 +52,44 @@ pub trait Transport {
     fn flush(
         &mut self,
     ) -> impl future::Future<Output = Result<(), Box<dyn error::Error + Send + Sync>>>;
+    /// Set the device addressed by the following requests
+    ///
+    /// This is the slave address on RTU and the unit identifier on TCP.
+    fn set_device_id(&mut self, _device_id: u8) {}
+    /// Device addressed by the following requests, if the transport reports it
+    fn device_id(&self) -> Option<u8> {
+        None
+    }
+    /// Shut the transport down gracefully
+    ///
+    /// Pending output is flushed and the underlying stream is shut down instead of just being
+    /// dropped. The default does nothing.
+    fn close(self) -> impl future::Future<Output = Result<(), Box<dyn error::Error + Send + Sync>>>
+    where
+        Self: Sized,
+    {
+        async { Ok(()) }
+    }
+    /// Timing of the latest completed transaction, if the transport measures it
+    fn last_timing(&self) -> Option<TransactionTiming> {
+        None
+    }
+    /// Transaction id of the latest request, if the protocol carries one
+    fn last_transaction_id(&self) -> Option<u16> {
+        None
+    }
+    /// Check that no other device transmits for `within` before sending
+    ///
+    /// The default reports an idle bus, for transports that cannot listen in.
+    fn check_bus_idle(&mut self, _within: Duration) -> impl future::Future<Output = bool> {
+        async { true }
+    }
+    /// Discard received bytes not yet read, e.g. stale traffic of another master
+    ///
+    /// The default does nothing.
+    fn clear_input(
+        &mut self,
+    ) -> impl future::Future<Output = Result<(), Box<dyn error::Error + Send + Sync>>> {
+        async { Ok(()) }
+    }
 }
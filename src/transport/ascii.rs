@@ -0,0 +1,195 @@
+use core::time::Duration;
+
+use crate::{
+    error::{ModbusAsciiError, ModbusFrameError, ModbusTransportError},
+    frame::{
+        ascii::{Adu, AsciiFrameHandler},
+        pdu::Pdu,
+    },
+    lib::*,
+};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_serial::{SerialPortBuilderExt, SerialStream};
+
+use super::Transport;
+
+/// Default time to wait for each chunk of a response
+///
+/// Note. 2.5.2.1 MODBUS Message ASCII Framing
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Longest ASCII frame, see `frame::ascii::Adu`
+const MAX_FRAME_LEN: usize = 1 + (1 + 253 + 1) * 2 + 2;
+
+/// Modbus ASCII transport over a serial line
+///
+/// Usually opened with `open`. Any other byte stream can be wrapped with `from_stream`.
+#[derive(Debug)]
+pub struct AsciiTransport<S = SerialStream> {
+    port: S,
+    slave_addr: u8,
+    timeout: Duration,
+    buffer: Adu,
+    /// Received characters not yet returned as a frame
+    received: Vec<u8>,
+}
+
+impl AsciiTransport {
+    /// Open a serial port with the ASCII mode defaults of 7 data bits, even parity and one
+    /// stop bit
+    pub fn open<P: AsRef<str>>(path: P, baud_rate: u32) -> Result<Self, ModbusTransportError> {
+        let port = tokio_serial::new(path.as_ref(), baud_rate)
+            .flow_control(tokio_serial::FlowControl::None)
+            .stop_bits(tokio_serial::StopBits::One)
+            .parity(tokio_serial::Parity::Even)
+            .data_bits(tokio_serial::DataBits::Seven)
+            .open_native_async()
+            .map_err(|err| ModbusTransportError::TransportError(err.into()))?;
+
+        Ok(Self::from_stream(port))
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> AsciiTransport<S> {
+    pub fn from_stream(port: S) -> Self {
+        Self {
+            port,
+            slave_addr: 0,
+            timeout: DEFAULT_TIMEOUT,
+            buffer: Adu::default(),
+            received: Vec::new(),
+        }
+    }
+
+    /// Set the slave address
+    pub fn set_slave_addr(&mut self, slave_addr: u8) {
+        self.slave_addr = slave_addr;
+    }
+
+    /// Set the longest silence while waiting for a response before giving up
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Take the first complete frame, from `:` to `LF`, out of the received characters
+    ///
+    /// Characters ahead of the start of a frame are dropped.
+    fn take_frame(&mut self) -> Option<Vec<u8>> {
+        let start = self.received.iter().position(|char| *char == b':');
+        self.received.drain(..start.unwrap_or(self.received.len()));
+
+        let end = self.received.iter().position(|char| *char == b'\n')?;
+        Some(self.received.drain(..=end).collect())
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> Transport for AsciiTransport<S> {
+    async fn send(
+        &mut self,
+        pdu: &Pdu,
+    ) -> core::result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        AsciiFrameHandler::build_frame(&mut self.buffer, self.slave_addr, pdu)?;
+        self.received.clear();
+
+        self.port.write_all(self.buffer.as_slice()).await?;
+        self.port.flush().await?;
+
+        Ok(())
+    }
+
+    async fn recv(&mut self) -> core::result::Result<Pdu, Box<dyn error::Error + Send + Sync>> {
+        loop {
+            while let Some(frame) = self.take_frame() {
+                match AsciiFrameHandler::parse_frame(&frame, self.slave_addr) {
+                    // A frame of another slave
+                    Err(ModbusFrameError::AsciiError(ModbusAsciiError::InvalidSlaveAddress(_))) => {
+                        continue
+                    }
+                    res => return Ok(res?),
+                }
+            }
+
+            // A start without an end in sight
+            if self.received.len() >= MAX_FRAME_LEN {
+                self.received.clear();
+                return Err(ModbusFrameError::from(ModbusAsciiError::InvalidFrameLength).into());
+            }
+
+            let mut chunk = [0; 64];
+            let n = tokio::time::timeout(self.timeout, self.port.read(&mut chunk))
+                .await
+                .map_err(|_| ModbusTransportError::Timeout)??;
+
+            // The stream was closed
+            if n == 0 {
+                return Err(ModbusTransportError::FrameIncomplete.into());
+            }
+            self.received.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    async fn flush(&mut self) -> core::result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.port.flush().await?;
+        Ok(())
+    }
+
+    fn set_device_id(&mut self, device_id: u8) {
+        self.set_slave_addr(device_id);
+    }
+
+    fn device_id(&self) -> Option<u8> {
+        Some(self.slave_addr)
+    }
+
+    async fn close(mut self) -> core::result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.port.flush().await?;
+        self.port.shutdown().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::client::Client;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_transport_ascii_transaction() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut client = Client::new(AsciiTransport::from_stream(master));
+        client.set_device_id(0x11);
+
+        // Noise, a response of another slave, then the expected response in pieces
+        let response = async {
+            let mut request = [0; 17];
+            slave.read_exact(&mut request).await.unwrap();
+            assert_eq!(&request, b":1103006B00037E\r\n");
+
+            slave
+                .write_all(b"\0:1203020001E8\r\n:110306")
+                .await
+                .unwrap();
+            slave.write_all(b"AE41565200430C\r\n").await.unwrap();
+        };
+        let (result, _) = tokio::join!(client.read_holding_registers(0x006B, 3), response);
+
+        let result = result.unwrap();
+        assert_eq!(result.register(0), Some(0xAE41));
+        assert_eq!(result.register(2), Some(0x0043));
+    }
+
+    #[tokio::test]
+    async fn test_transport_ascii_timeout() {
+        let (master, _slave) = tokio::io::duplex(256);
+        let mut transport = AsciiTransport::from_stream(master);
+        transport.set_timeout(Duration::from_millis(10));
+
+        let err = transport.recv().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ModbusTransportError>(),
+            Some(ModbusTransportError::Timeout)
+        ));
+    }
+}
@@ -0,0 +1,192 @@
+//! Fault injection for testing error handling
+//!
+//! `FaultInjector` wraps any transport and alters its responses by a script of `Fault`s, one
+//! per transaction, so that the timeout, exception and framing error paths of an application
+//! can be exercised deterministically.
+
+use crate::error::ModbusTransportError;
+#[cfg(feature = "rtu")]
+use crate::error::{ModbusFrameError, ModbusRtuError};
+use crate::frame::pdu::fcode::{ExceptionCode, EXCEPTION_FLAG};
+use crate::frame::pdu::Pdu;
+use crate::lib::*;
+use core::time::Duration;
+
+use super::{TransactionTiming, Transport};
+
+/// Fault applied to the response of one transaction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    /// Pass the response through unchanged
+    Pass,
+    /// Discard the response and report a timeout
+    DropResponse,
+    /// Report the response as failing its CRC check
+    #[cfg(feature = "rtu")]
+    CorruptCrc,
+    /// Replace the response with an exception response to the request
+    Exception(ExceptionCode),
+    /// Keep only the given number of data bytes of the response
+    Truncate(usize),
+}
+
+/// Transport applying a script of faults to the responses of the wrapped transport
+///
+/// The `n`-th transaction gets the `n`-th fault of the script; once the script is exhausted,
+/// responses pass through unchanged.
+#[derive(Debug)]
+pub struct FaultInjector<T> {
+    inner: T,
+    script: Vec<Fault>,
+    position: usize,
+    function_code: Option<u8>,
+}
+
+impl<T: Transport + Send> FaultInjector<T> {
+    pub fn new(inner: T, script: impl IntoIterator<Item = Fault>) -> Self {
+        Self {
+            inner,
+            script: script.into_iter().collect(),
+            position: 0,
+            function_code: None,
+        }
+    }
+
+    /// Number of transactions whose response has been received so far
+    pub fn transactions(&self) -> usize {
+        self.position
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Transport + Send> Transport for FaultInjector<T> {
+    async fn send(&mut self, pdu: &Pdu) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.function_code = pdu.function_code();
+        self.inner.send(pdu).await
+    }
+
+    async fn recv(&mut self) -> result::Result<Pdu, Box<dyn error::Error + Send + Sync>> {
+        let fault = self
+            .script
+            .get(self.position)
+            .copied()
+            .unwrap_or(Fault::Pass);
+        self.position += 1;
+
+        let response = self.inner.recv().await?;
+
+        match fault {
+            Fault::Pass => Ok(response),
+            Fault::DropResponse => Err(ModbusTransportError::Timeout.into()),
+            #[cfg(feature = "rtu")]
+            Fault::CorruptCrc => {
+                Err(ModbusFrameError::from(ModbusRtuError::CrcValidationFailure).into())
+            }
+            Fault::Exception(exception_code) => {
+                let function_code = self.function_code.or(response.function_code()).unwrap_or(0);
+                let mut pdu = Pdu::new(function_code | EXCEPTION_FLAG)?;
+                pdu.put_u8(exception_code.into())?;
+                Ok(pdu)
+            }
+            Fault::Truncate(len) => {
+                let mut pdu = Pdu::new(response.function_code().unwrap_or(0))?;
+                pdu.put_slice(&response.data()[..len.min(response.data().len())])?;
+                Ok(pdu)
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.inner.flush().await
+    }
+
+    async fn close(self) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.inner.close().await
+    }
+
+    fn set_device_id(&mut self, device_id: u8) {
+        self.inner.set_device_id(device_id);
+    }
+
+    fn device_id(&self) -> Option<u8> {
+        self.inner.device_id()
+    }
+
+    fn last_timing(&self) -> Option<TransactionTiming> {
+        self.inner.last_timing()
+    }
+
+    fn last_transaction_id(&self) -> Option<u16> {
+        self.inner.last_transaction_id()
+    }
+
+    async fn check_bus_idle(&mut self, within: Duration) -> bool {
+        self.inner.check_bus_idle(within).await
+    }
+
+    async fn clear_input(&mut self) -> Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.inner.clear_input().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::client::Client;
+    use crate::error::ModbusError;
+    use crate::transport::mock::echo;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_transport_fault_injector() {
+        let transport = FaultInjector::new(
+            echo(),
+            [
+                Fault::Pass,
+                Fault::DropResponse,
+                Fault::Exception(ExceptionCode::ServerDeviceBusy),
+                Fault::Truncate(2),
+            ],
+        );
+        let mut client = Client::new(transport);
+
+        let response = client.write_single_register(0x0001, 0x1234).await.unwrap();
+        assert_eq!(response.register_value(), Some(0x1234));
+
+        assert!(matches!(
+            client.write_single_register(0x0001, 0x1234).await,
+            Err(ModbusError::TransportError(ModbusTransportError::Timeout))
+        ));
+        assert!(matches!(
+            client.write_single_register(0x0001, 0x1234).await,
+            Err(ModbusError::Exception(ExceptionCode::ServerDeviceBusy))
+        ));
+
+        let response = client.write_single_register(0x0001, 0x1234).await.unwrap();
+        assert_eq!(response.as_pdu().data(), &[0x00, 0x01]);
+
+        // Script exhausted
+        let response = client.write_single_register(0x0001, 0x1234).await.unwrap();
+        assert_eq!(response.register_value(), Some(0x1234));
+    }
+
+    #[cfg(feature = "rtu")]
+    #[tokio::test]
+    async fn test_transport_fault_injector_corrupt_crc() {
+        let mut transport = FaultInjector::new(echo(), [Fault::CorruptCrc]);
+
+        let pdu = Pdu::new(0x07).unwrap();
+        transport.send(&pdu).await.unwrap();
+        let err = transport.recv().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ModbusFrameError>(),
+            Some(ModbusFrameError::RtuError(
+                ModbusRtuError::CrcValidationFailure
+            ))
+        ));
+        assert_eq!(transport.transactions(), 1);
+    }
+}
@@ -0,0 +1,83 @@
+//! Transports standing in for a server in the unit tests
+//!
+//! `MockTransport` keeps the addressed device and the pending response, and leaves the answer
+//! to each request to a `MockServer`, so a test only has to describe how its server behaves.
+
+use crate::error::ModbusTransportError;
+use crate::frame::pdu::Pdu;
+use crate::lib::*;
+
+use super::Transport;
+
+/// Response of a `MockServer`, `None` leaving the request unanswered
+pub(crate) type MockResponse = result::Result<Option<Pdu>, Box<dyn error::Error + Send + Sync>>;
+
+/// Server behind a `MockTransport`
+pub(crate) trait MockServer {
+    /// Answer `request` addressed to `device_id`
+    fn respond(&mut self, device_id: u8, request: &Pdu) -> MockResponse;
+}
+
+impl<F: FnMut(u8, &Pdu) -> MockResponse> MockServer for F {
+    fn respond(&mut self, device_id: u8, request: &Pdu) -> MockResponse {
+        self(device_id, request)
+    }
+}
+
+/// Transport answering each request through its `MockServer`
+///
+/// A silent server times out. Both directions yield to other tasks, so that transactions
+/// interleaved by concurrent callers mix up their responses.
+pub(crate) struct MockTransport<S> {
+    pub(crate) server: S,
+    pub(crate) device_id: u8,
+    pub(crate) pending: Option<Pdu>,
+}
+
+impl<S: MockServer> MockTransport<S> {
+    /// Address device `0x01` of `server`
+    pub(crate) fn new(server: S) -> Self {
+        Self {
+            server,
+            device_id: 0x01,
+            pending: None,
+        }
+    }
+}
+
+impl<S: MockServer + Send> Transport for MockTransport<S> {
+    async fn send(&mut self, pdu: &Pdu) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.pending = self.server.respond(self.device_id, pdu)?;
+        tokio::task::yield_now().await;
+        Ok(())
+    }
+
+    async fn recv(&mut self) -> result::Result<Pdu, Box<dyn error::Error + Send + Sync>> {
+        tokio::task::yield_now().await;
+        self.pending
+            .take()
+            .ok_or_else(|| ModbusTransportError::Timeout.into())
+    }
+
+    async fn flush(&mut self) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    fn set_device_id(&mut self, device_id: u8) {
+        self.device_id = device_id;
+    }
+
+    fn device_id(&self) -> Option<u8> {
+        Some(self.device_id)
+    }
+}
+
+/// Transport echoing every request back
+pub(crate) fn echo() -> MockTransport<impl MockServer + Send> {
+    MockTransport::new(|_, request: &Pdu| Ok(Some(request.clone())))
+}
+
+/// Transport answering every request with `response`
+pub(crate) fn fixed_response(response: Pdu) -> MockTransport<impl MockServer + Send> {
+    MockTransport::new(move |_, _: &Pdu| Ok(Some(response.clone())))
+}
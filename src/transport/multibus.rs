@@ -0,0 +1,342 @@
+//! Several independent buses behind one transport
+//!
+//! Slaves are addressed as `(bus, device id)`: `select_bus` picks the bus and `set_device_id`
+//! the slave on it. Clones of a `MultiBusTransport` share the buses, so transactions on
+//! different buses proceed concurrently when driven from separate tasks, while transactions
+//! on the same bus wait for each other.
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use core::time::Duration;
+
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::error::ModbusTransportError;
+use crate::frame::pdu::Pdu;
+use crate::lib::*;
+
+use super::{TransactionTiming, Transport};
+
+/// Transport routing each transaction to the currently selected bus
+#[derive(Debug)]
+pub struct MultiBusTransport<T> {
+    buses: Vec<(u8, Arc<Mutex<T>>)>,
+    selected: Option<u8>,
+    device_id: Option<u8>,
+    /// Bus held from `send` until the response has been received
+    active: Option<OwnedMutexGuard<T>>,
+}
+
+impl<T> Clone for MultiBusTransport<T> {
+    fn clone(&self) -> Self {
+        Self {
+            buses: self.buses.clone(),
+            selected: self.selected,
+            device_id: self.device_id,
+            active: None,
+        }
+    }
+}
+
+impl<T> Default for MultiBusTransport<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MultiBusTransport<T> {
+    pub fn new() -> Self {
+        Self {
+            buses: Vec::new(),
+            selected: None,
+            device_id: None,
+            active: None,
+        }
+    }
+
+    /// Add a bus, replacing any bus with the same id
+    ///
+    /// The first bus added is selected.
+    pub fn add_bus(&mut self, bus: u8, transport: T) {
+        let transport = Arc::new(Mutex::new(transport));
+
+        match self.buses.iter_mut().find(|(id, _)| *id == bus) {
+            Some((_, existing)) => *existing = transport,
+            None => self.buses.push((bus, transport)),
+        }
+        self.selected.get_or_insert(bus);
+    }
+
+    /// Select the bus of the following transactions
+    pub fn select_bus(&mut self, bus: u8) -> Result<(), ModbusTransportError> {
+        if self.bus(bus).is_none() {
+            return Err(ModbusTransportError::UnknownBus(bus));
+        }
+
+        self.selected = Some(bus);
+        Ok(())
+    }
+
+    pub fn selected_bus(&self) -> Option<u8> {
+        self.selected
+    }
+
+    fn bus(&self, bus: u8) -> Option<&Arc<Mutex<T>>> {
+        self.buses
+            .iter()
+            .find(|(id, _)| *id == bus)
+            .map(|(_, transport)| transport)
+    }
+
+    fn selected(&self) -> Result<Arc<Mutex<T>>, ModbusTransportError> {
+        let bus = self.selected.ok_or(ModbusTransportError::UnknownBus(0))?;
+        let transport = self.bus(bus).ok_or(ModbusTransportError::UnknownBus(bus))?;
+
+        Ok(Arc::clone(transport))
+    }
+}
+
+impl<T: Transport + Send + 'static> Transport for MultiBusTransport<T> {
+    async fn send(&mut self, pdu: &Pdu) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        // Release the bus of a transaction whose response was never received
+        self.active = None;
+
+        let mut transport = self.selected()?.lock_owned().await;
+        if let Some(device_id) = self.device_id {
+            transport.set_device_id(device_id);
+        }
+        transport.send(pdu).await?;
+        self.active = Some(transport);
+
+        Ok(())
+    }
+
+    async fn recv(&mut self) -> result::Result<Pdu, Box<dyn error::Error + Send + Sync>> {
+        let mut transport = match self.active.take() {
+            Some(transport) => transport,
+            None => self.selected()?.lock_owned().await,
+        };
+
+        transport.recv().await
+    }
+
+    async fn flush(&mut self) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        match self.active.as_mut() {
+            Some(transport) => transport.flush().await,
+            None => self.selected()?.lock_owned().await.flush().await,
+        }
+    }
+
+    fn set_device_id(&mut self, device_id: u8) {
+        self.device_id = Some(device_id);
+    }
+
+    fn device_id(&self) -> Option<u8> {
+        self.device_id
+    }
+
+    /// Close the buses no other clone shares any more
+    async fn close(mut self) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.active = None;
+
+        for (_, bus) in self.buses {
+            if let Ok(transport) = Arc::try_unwrap(bus) {
+                transport.into_inner().close().await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Timing of the latest transaction on the selected bus, by any clone
+    ///
+    /// `None` while another clone holds the bus.
+    fn last_timing(&self) -> Option<TransactionTiming> {
+        if let Some(transport) = self.active.as_ref() {
+            return transport.last_timing();
+        }
+
+        let bus = self.selected().ok()?;
+        let transport = bus.try_lock().ok()?;
+        transport.last_timing()
+    }
+
+    /// Transaction id of the latest request on the selected bus, by any clone
+    ///
+    /// `None` while another clone holds the bus.
+    fn last_transaction_id(&self) -> Option<u16> {
+        if let Some(transport) = self.active.as_ref() {
+            return transport.last_transaction_id();
+        }
+
+        let bus = self.selected().ok()?;
+        let transport = bus.try_lock().ok()?;
+        transport.last_transaction_id()
+    }
+
+    async fn check_bus_idle(&mut self, within: Duration) -> bool {
+        self.active = None;
+
+        match self.selected() {
+            Ok(bus) => bus.lock_owned().await.check_bus_idle(within).await,
+            Err(_) => false,
+        }
+    }
+
+    async fn clear_input(&mut self) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.active = None;
+
+        self.selected()?.lock_owned().await.clear_input().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::app::client::Client;
+    use crate::error::ModbusError;
+    use crate::frame::pdu::function::response::ReadHoldingRegistersResponse;
+
+    use super::*;
+
+    /// Answers every request with a register holding the bus and device id
+    struct BusTransport {
+        bus: u8,
+        device_id: u8,
+        pending: bool,
+        /// Whether the bus reports foreign traffic
+        busy: bool,
+        cleared: u8,
+    }
+
+    impl BusTransport {
+        fn new(bus: u8) -> Self {
+            Self {
+                bus,
+                device_id: 0,
+                pending: false,
+                busy: false,
+                cleared: 0,
+            }
+        }
+    }
+
+    impl Transport for BusTransport {
+        async fn send(
+            &mut self,
+            _pdu: &Pdu,
+        ) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+            self.pending = true;
+            Ok(())
+        }
+
+        async fn recv(&mut self) -> result::Result<Pdu, Box<dyn error::Error + Send + Sync>> {
+            assert!(
+                core::mem::take(&mut self.pending),
+                "response without request"
+            );
+            let register = u16::from_be_bytes([self.bus, self.device_id]);
+            Ok(ReadHoldingRegistersResponse::from_registers(&[register])?.into_inner())
+        }
+
+        async fn flush(&mut self) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+            Ok(())
+        }
+
+        fn set_device_id(&mut self, device_id: u8) {
+            self.device_id = device_id;
+        }
+
+        fn last_transaction_id(&self) -> Option<u16> {
+            Some(self.bus as u16)
+        }
+
+        async fn check_bus_idle(&mut self, _within: Duration) -> bool {
+            !self.busy
+        }
+
+        async fn clear_input(&mut self) -> result::Result<(), Box<dyn error::Error + Send + Sync>> {
+            self.cleared += 1;
+            Ok(())
+        }
+    }
+
+    fn multi_bus() -> MultiBusTransport<BusTransport> {
+        let mut transport = MultiBusTransport::new();
+        transport.add_bus(1, BusTransport::new(1));
+        transport.add_bus(2, BusTransport::new(2));
+        transport
+    }
+
+    #[tokio::test]
+    async fn test_transport_multibus_select_bus() {
+        let mut client = Client::new(multi_bus());
+
+        client.set_device_id(0x11);
+        let response = client.read_holding_registers(0x0000, 1).await.unwrap();
+        assert_eq!(response.register(0), Some(0x0111));
+
+        client.select_bus(2).unwrap();
+        client.set_device_id(0x22);
+        let response = client.read_holding_registers(0x0000, 1).await.unwrap();
+        assert_eq!(response.register(0), Some(0x0222));
+
+        assert!(matches!(
+            client.select_bus(3),
+            Err(ModbusError::TransportError(
+                ModbusTransportError::UnknownBus(3)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_transport_multibus_concurrent_buses() {
+        let mut first = multi_bus();
+        let mut second = first.clone();
+        let mut third = first.clone();
+        second.select_bus(2).unwrap();
+
+        // Bus 1 stays busy until the response of `first` has been received
+        let request = Pdu::new(0x03).unwrap();
+        first.send(&request).await.unwrap();
+
+        second.send(&request).await.unwrap();
+        assert!(second.recv().await.is_ok());
+
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), third.send(&request))
+                .await
+                .is_err()
+        );
+
+        assert!(first.recv().await.is_ok());
+        third.send(&request).await.unwrap();
+        assert!(third.recv().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_transport_multibus_forwards_to_selected_bus() {
+        let transport = multi_bus();
+        transport.buses[1].1.lock().await.busy = true;
+        let mut client = Client::new(transport.clone());
+        client.set_purge_on_request(true);
+        client.set_idle_bus_check(Some(Duration::from_millis(1)));
+
+        assert!(client.read_holding_registers(0x0000, 1).await.is_ok());
+        assert_eq!(client.last_transaction_id(), Some(1));
+
+        client.select_bus(2).unwrap();
+        assert!(matches!(
+            client.read_holding_registers(0x0000, 1).await,
+            Err(ModbusError::TransportError(ModbusTransportError::BusBusy))
+        ));
+        assert_eq!(client.last_transaction_id(), Some(2));
+
+        assert_eq!(transport.buses[0].1.lock().await.cleared, 1);
+        assert_eq!(transport.buses[1].1.lock().await.cleared, 1);
+    }
+}
@@ -1,31 +1,50 @@
 use core::time::Duration;
 
 use crate::{
-    error::ModbusTransportError,
+    error::{ModbusFrameError, ModbusRtuError, ModbusTransportError},
     frame::{
-        pdu::Pdu,
+        pdu::{fcode::PublicFunctionCode, Pdu, MAX_PDU_SIZE},
         rtu::{Adu, RtuFrameHandler},
     },
     lib::*,
 };
 
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
-    time::{sleep, Instant},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    time::{sleep, sleep_until, Instant},
 };
 pub use tokio_serial::Parity;
 use tokio_serial::{SerialPortBuilder, SerialPortBuilderExt, SerialStream};
 
-use super::Transport;
+use super::{TransactionTiming, Transport};
 
 const RTU_BITS_PER_CHAR: u8 = 11;
 
+/// Default delay after a broadcast for every slave to process it
+const DEFAULT_BROADCAST_TURNAROUND: Duration = Duration::from_millis(100);
+
+/// Longest time spent discarding input on a line that keeps delivering bytes
+const CLEAR_INPUT_DEADLINE: Duration = Duration::from_millis(100);
+
+/// Slave address + CRC
+const RTU_ADU_OVERHEAD: usize = 3;
+
 #[derive(Debug)]
 pub(crate) struct RtuContext {
     slave_addr: u8,
     latest_time: Instant,
+    char_time: Duration,
     t1_5: Duration,
     t3_5: Duration,
+    /// t1.5 and t3.5 are set by the user rather than derived from the baud rate
+    frame_gap_override: bool,
+    /// Hold slave addresses to the Modbus addressing rules
+    strict_addressing: bool,
+    crc_retry: u8,
+    strip_leading_nulls: bool,
+    strict_recv: bool,
+    broadcast_turnaround: Duration,
+    response_timeout: Option<Duration>,
 }
 
 impl Default for RtuContext {
@@ -33,17 +52,30 @@ impl Default for RtuContext {
         Self {
             slave_addr: 0,
             latest_time: Instant::now(),
+            char_time: Duration::from_secs(86400),
             t1_5: Duration::from_secs(86400),
             t3_5: Duration::from_secs(86400),
+            frame_gap_override: false,
+            strict_addressing: false,
+            crc_retry: 0,
+            strip_leading_nulls: false,
+            strict_recv: false,
+            broadcast_turnaround: DEFAULT_BROADCAST_TURNAROUND,
+            response_timeout: None,
         }
     }
 }
 
 impl RtuContext {
     pub fn set_interval(&mut self, baud_rate: u32) {
-        if baud_rate <= 19200 {
-            let sec_per_char = RTU_BITS_PER_CHAR as f64 / baud_rate as f64;
+        let sec_per_char = RTU_BITS_PER_CHAR as f64 / baud_rate as f64;
+        self.char_time = Duration::from_secs_f64(sec_per_char);
+
+        if self.frame_gap_override {
+            return;
+        }
 
+        if baud_rate <= 19200 {
             self.t1_5 = Duration::from_secs_f64(sec_per_char * 1.5);
             self.t3_5 = Duration::from_secs_f64(sec_per_char * 3.5);
         } else {
@@ -51,74 +83,379 @@ impl RtuContext {
             self.t3_5 = Duration::from_micros(1750);
         }
     }
+
+    /// Use fixed t1.5 and t3.5 intervals instead of deriving them from the baud rate
+    pub fn set_frame_gap(&mut self, t1_5: Duration, t3_5: Duration) {
+        self.t1_5 = t1_5;
+        self.t3_5 = t3_5;
+        self.frame_gap_override = true;
+    }
+
+    /// Refuse a reserved slave address (`248..=255`) when strict
+    pub fn check_slave_addr(&self, slave_addr: u8) -> Result<(), ModbusRtuError> {
+        if self.strict_addressing && slave_addr > 247 {
+            Err(ModbusRtuError::InvalidSlaveAddress(slave_addr))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Refuse to send `request` to the configured slave address when strict
+    ///
+    /// Besides the reserved addresses, only write requests may be broadcast to address `0`.
+    pub fn check_request_addr(&self, request: &Pdu) -> Result<(), ModbusRtuError> {
+        self.check_slave_addr(self.slave_addr)?;
+
+        let is_write = request
+            .function_code()
+            .and_then(|code| PublicFunctionCode::try_from(code).ok())
+            .is_some_and(PublicFunctionCode::is_write);
+        if self.strict_addressing && self.slave_addr == 0 && !is_write {
+            return Err(ModbusRtuError::InvalidSlaveAddress(0));
+        }
+
+        Ok(())
+    }
+
+    /// Parse a received frame addressed to the configured slave
+    ///
+    /// With `strip_leading_nulls` set, a frame that fails to parse is retried from its first
+    /// non-`0x00` byte.
+    pub fn parse_frame(&self, frame: &[u8]) -> Result<Pdu, ModbusFrameError> {
+        match RtuFrameHandler::parse_frame(frame, self.slave_addr) {
+            Err(err) if self.strip_leading_nulls => {
+                let stripped = RtuFrameHandler::strip_leading_nulls(frame);
+                if stripped.len() == frame.len() {
+                    Err(err)
+                } else {
+                    RtuFrameHandler::parse_frame(stripped, self.slave_addr)
+                }
+            }
+            res => res,
+        }
+    }
+
+    /// Time to listen for a frame sent again after a corrupted one, without a response timeout
+    ///
+    /// Covers the t3.5 gap before the retransmission and a frame of the maximum size.
+    pub fn retransmission_window(&self) -> Duration {
+        self.t3_5 + self.char_time * (MAX_PDU_SIZE + RTU_ADU_OVERHEAD) as u32
+    }
+
+    /// Estimate the bus time of a request, its response and the t3.5 gap after each frame
+    pub fn transaction_time(&self, request: &Pdu) -> Duration {
+        let request_len = request.len() + RTU_ADU_OVERHEAD;
+        let response_len = expected_response_len(request) + RTU_ADU_OVERHEAD;
+
+        self.char_time * (request_len + response_len) as u32 + self.t3_5 * 2
+    }
+}
+
+/// Expected length of the response PDU to the given request PDU
+///
+/// Function codes whose response size cannot be derived from the request are assumed to
+/// answer with a PDU of the same length as the request.
+fn expected_response_len(request: &Pdu) -> usize {
+    match request.function_code() {
+        // Byte Count + Coil/Input Status
+        Some(0x01) | Some(0x02) => request.read_u16(2).map_or(request.len(), |quantity| {
+            2 + (quantity as usize).div_ceil(8)
+        }),
+        // Byte Count + Register Value
+        Some(0x03) | Some(0x04) => request
+            .read_u16(2)
+            .map_or(request.len(), |quantity| 2 + quantity as usize * 2),
+        // Address + Value/Quantity
+        Some(0x0F) | Some(0x10) => 5,
+        // Byte Count + Read Register Value
+        Some(0x17) => request
+            .read_u16(2)
+            .map_or(request.len(), |quantity| 2 + quantity as usize * 2),
+        _ => request.len(),
+    }
+}
+
+/// Instants of the latest transaction, see `TransactionTiming`
+#[derive(Debug, Default)]
+struct TimingMarks {
+    write_start: Option<Instant>,
+    write_end: Option<Instant>,
+    first_byte: Option<Instant>,
+    complete: Option<Instant>,
+}
+
+impl TimingMarks {
+    fn timing(&self) -> Option<TransactionTiming> {
+        let (write_start, write_end) = (self.write_start?, self.write_end?);
+        let (first_byte, complete) = (self.first_byte?, self.complete?);
+
+        Some(TransactionTiming {
+            write: write_end.duration_since(write_start),
+            turnaround: first_byte.saturating_duration_since(write_end),
+            read: complete.saturating_duration_since(first_byte),
+        })
+    }
 }
 
+/// Modbus RTU transport over a serial line
+///
+/// Usually opened with `builder`. Any other byte stream, e.g. a virtual serial pair, can be
+/// wrapped with `from_stream`.
 #[derive(Debug)]
-pub struct SerialTransport {
-    port: SerialStream,
+pub struct SerialTransport<S = SerialStream> {
+    port: S,
     ctx: RtuContext,
     buffer: Adu,
+    peeked: bool,
+    marks: TimingMarks,
 }
 
 impl SerialTransport {
     pub fn builder<P: AsRef<str>>(path: P, baud_rate: u32) -> SerialTransportBuilder {
         SerialTransportBuilder::new(path, baud_rate)
     }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> SerialTransport<S> {
+    /// Use an already opened byte stream, timing frames for the given baud rate
+    pub fn from_stream(port: S, baud_rate: u32) -> Self {
+        let mut ctx = RtuContext::default();
+        ctx.set_interval(baud_rate);
+
+        Self {
+            port,
+            ctx,
+            buffer: Adu::default(),
+            peeked: false,
+            marks: TimingMarks::default(),
+        }
+    }
 
     /// Set the slave address
     ///
+    /// Any address is taken here. With strict addressing enabled, a reserved address
+    /// (`248..=255`) is refused by the following `send`, as is one set by `set_device_id`.
+    ///
     /// Note. 2.2 MODBUS Addressing rules
     pub fn set_slave_addr(&mut self, slave_addr: u8) {
         self.ctx.slave_addr = slave_addr;
-        // if (1..=247).contains(&slave_addr) {
-        //     Ok(())
-        // } else {
-        //     Err(ModbusRtuError::InvalidSlaveAddress(slave_addr).into())
-        // }
+    }
+
+    /// Set the slave address, refusing a reserved one right away when strict addressing is
+    /// enabled
+    pub fn try_set_slave_addr(&mut self, slave_addr: u8) -> Result<(), ModbusRtuError> {
+        self.ctx.check_slave_addr(slave_addr)?;
+        self.set_slave_addr(slave_addr);
+
+        Ok(())
+    }
+
+    pub fn slave_addr(&self) -> u8 {
+        self.ctx.slave_addr
+    }
+
+    /// Whether requests go to every slave, which do not answer
+    pub fn is_broadcast(&self) -> bool {
+        self.ctx.slave_addr == 0
+    }
+
+    /// Read just enough of the incoming frame to route it
+    ///
+    /// Returns the `(slave_address, function_code)` of the frame. The bytes read stay buffered,
+    /// so the following `recv` still returns the complete frame.
+    pub async fn peek_header(
+        &mut self,
+    ) -> core::result::Result<(u8, u8), Box<dyn error::Error + Send + Sync>> {
+        if !self.peeked {
+            self.buffer.clear();
+            self.peeked = true;
+        }
+
+        loop {
+            if let Some(header) = RtuFrameHandler::peek_header(self.buffer.as_slice()) {
+                return Ok(header);
+            }
+
+            let n = self.port.read(self.buffer.spare_capacity_mut()).await?;
+            if n == 0 {
+                return Err(ModbusTransportError::FrameIncomplete.into());
+            }
+
+            self.buffer.advance(n);
+            self.ctx.latest_time = Instant::now();
+            self.marks.first_byte.get_or_insert(self.ctx.latest_time);
+        }
+    }
+
+    /// Check that the bus stays silent for `within`
+    ///
+    /// Returns `false` as soon as a byte is received, e.g. from a slave that keeps transmitting
+    /// and would collide with a request. Bytes received meanwhile are discarded.
+    pub async fn check_bus_idle(&mut self, within: Duration) -> bool {
+        let mut buf = [0; 64];
+
+        match tokio::time::timeout(within, self.port.read(&mut buf)).await {
+            Err(_) => true,
+            Ok(Ok(n)) if n > 0 => {
+                self.ctx.latest_time = Instant::now();
+                false
+            }
+            // A closed or failing line is no idle bus either
+            Ok(_) => false,
+        }
+    }
+
+    /// Discard bytes waiting in the receive buffer
+    ///
+    /// On a bus shared with other masters, the tail of a foreign transaction may still be
+    /// buffered and would be read as the start of the next response. The buffer is read without
+    /// waiting until it is empty, for at most 100 ms on a line that keeps delivering bytes.
+    pub async fn clear_input(
+        &mut self,
+    ) -> core::result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        let deadline = Instant::now() + CLEAR_INPUT_DEADLINE;
+        let mut buf = [0; 64];
+        self.buffer.clear();
+        self.peeked = false;
+
+        while Instant::now() < deadline {
+            // A zero timeout still polls the read once
+            match tokio::time::timeout(Duration::ZERO, self.port.read(&mut buf)).await {
+                Ok(Ok(n)) if n > 0 => self.ctx.latest_time = Instant::now(),
+                Ok(Ok(_)) | Err(_) => break,
+                Ok(Err(err)) => return Err(err.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a pre-encoded ADU verbatim, e.g. to replay captured traffic
+    ///
+    /// The bytes are neither re-framed nor checked, and the CRC is not recomputed, so a frame
+    /// with a deliberately wrong CRC goes out as is. Only the t3.5 silent interval since the
+    /// last bus activity is respected. This bypasses every framing guarantee of the transport
+    /// and is intended for testing and replay only; a response can be read with `recv`.
+    pub async fn send_raw_adu(
+        &mut self,
+        adu: &[u8],
+    ) -> core::result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.peeked = false;
+        sleep_until(self.ctx.latest_time + self.ctx.t3_5).await;
+
+        self.marks = TimingMarks {
+            write_start: Some(Instant::now()),
+            ..Default::default()
+        };
+        self.port.write_all(adu).await?;
+        self.port.flush().await?;
+        self.ctx.latest_time = Instant::now();
+        self.marks.write_end = Some(self.ctx.latest_time);
+
+        Ok(())
+    }
+
+    /// Estimate the bus time of one poll cycle over the given requests
+    ///
+    /// Sums the transmission time of every request and its expected response plus the
+    /// mandatory t3.5 silent interval after each frame. The processing time of the slave is
+    /// not included.
+    pub fn estimate_cycle_time(&self, requests: &[Pdu]) -> Duration {
+        requests
+            .iter()
+            .map(|request| self.ctx.transaction_time(request))
+            .sum()
     }
 }
 
-impl Transport for SerialTransport {
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> Transport for SerialTransport<S> {
     async fn send(
         &mut self,
         pdu: &Pdu,
     ) -> core::result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.peeked = false;
+        self.ctx
+            .check_request_addr(pdu)
+            .map_err(ModbusFrameError::from)?;
         RtuFrameHandler::build_frame(&mut self.buffer, self.ctx.slave_addr, pdu)?;
 
+        // Keep the bus silent for t3.5 since the last byte sent or received, so that a
+        // request right after a response does not collide with the slave releasing the line
+        sleep_until(self.ctx.latest_time + self.ctx.t3_5).await;
+
+        self.marks = TimingMarks {
+            write_start: Some(Instant::now()),
+            ..Default::default()
+        };
         self.port.write_all(self.buffer.as_slice()).await?;
+        // Make sure the frame is on the wire before timing the turnaround
+        self.port.flush().await?;
+        self.ctx.latest_time = Instant::now();
+        self.marks.write_end = Some(self.ctx.latest_time);
+
+        // No slave answers a broadcast, give them all time to process it instead
+        if self.is_broadcast() {
+            sleep(self.ctx.broadcast_turnaround).await;
+            self.ctx.latest_time = Instant::now();
+        }
 
         Ok(())
     }
 
     async fn recv(&mut self) -> core::result::Result<Pdu, Box<dyn error::Error + Send + Sync>> {
-        self.buffer.clear();
         let t3_5_timer = sleep(Duration::from_secs(86400));
         tokio::pin!(t3_5_timer);
-        let mut len = 0;
+        // Cancelled once the first byte is received
+        let response_timer = sleep(
+            self.ctx
+                .response_timeout
+                .unwrap_or(Duration::from_secs(86400)),
+        );
+        tokio::pin!(response_timer);
+        let mut awaiting_response = self.ctx.response_timeout.is_some() && !self.peeked;
+        let mut crc_retry = self.ctx.crc_retry;
+        // The gap after a header read by `peek_header` is always checked, any other gap within
+        // the frame only when strict
+        let mut continues_peek = self.peeked;
+
+        // Continue the frame whose header was already read by `peek_header`
+        if self.peeked {
+            self.peeked = false;
+            t3_5_timer
+                .as_mut()
+                .reset(self.ctx.latest_time + self.ctx.t3_5);
+        } else {
+            self.buffer.clear();
+        }
 
         loop {
             tokio::select! {
-                res = self.port.read(&mut self.buffer.as_slice_mut()[len..]) => {
+                res = self.port.read(self.buffer.spare_capacity_mut()) => {
                     let current_time = Instant::now();
 
                     match res {
+                        // The stream was closed
+                        Ok(0) => return Err(ModbusTransportError::FrameIncomplete.into()),
                         Ok(n) => {
+                            self.marks.first_byte.get_or_insert(current_time);
+                            awaiting_response = false;
+
                             // Check if a silent interval of more than 1.5 character times occurs between two characters
-                            if !self.buffer.is_empty() {
+                            let check_gap = core::mem::take(&mut continues_peek) || self.ctx.strict_recv;
+                            if check_gap && !self.buffer.is_empty() {
                                 let elapsed = current_time.duration_since(self.ctx.latest_time);
                                 if elapsed > self.ctx.t1_5 {
                                     return Err(ModbusTransportError::FrameIncomplete.into());
                                 }
                             }
 
-                            len += n;
-                            self.buffer.advance(len);
+                            self.buffer.advance(n);
+                            self.ctx.latest_time = current_time;
 
-                            if let Ok(pdu) = RtuFrameHandler::parse_frame(self.buffer.as_slice(), self.ctx.slave_addr) {
+                            // Otherwise the frame is incomplete so far
+                            if let Ok(pdu) = self.ctx.parse_frame(self.buffer.as_slice()) {
+                                self.marks.complete = Some(current_time);
                                 return Ok(pdu);
-                            } else {
-                                // Ignore the frame
-                                self.buffer.clear();
                             }
                         }
                         // Err(ref err) if err.kind() == std::io::ErrorKind::TimedOut && self.buffer.is_empty() => {}
@@ -129,13 +466,35 @@ impl Transport for SerialTransport {
                     t3_5_timer.as_mut().reset(current_time + self.ctx.t3_5);
                     continue;
                 }
+                _ = &mut response_timer, if awaiting_response => {
+                    return Err(ModbusTransportError::Timeout.into());
+                }
                 _ = &mut t3_5_timer => {
-                    if let Ok(pdu) = RtuFrameHandler::parse_frame(self.buffer.as_slice(), self.ctx.slave_addr) {
-                        return Ok(pdu);
-                    } else {
-                        return Err(ModbusTransportError::Timeout.into());
-                    }
+                    match self.ctx.parse_frame(self.buffer.as_slice()) {
+                        Ok(pdu) => {
+                            self.marks.complete = Some(Instant::now());
+                            return Ok(pdu);
+                        }
+                        Err(ModbusFrameError::RtuError(ModbusRtuError::CrcValidationFailure)) if crc_retry > 0 => {
+                            // Discard the corrupted frame and keep listening for a clean one
+                            crc_retry -= 1;
+                            self.buffer.clear();
 
+                            // Wait for the retransmission no longer than for a response, or
+                            // without a response timeout for as long as a whole frame takes
+                            let now = Instant::now();
+                            match self.ctx.response_timeout {
+                                Some(timeout) => {
+                                    response_timer.as_mut().reset(now + timeout);
+                                    awaiting_response = true;
+                                    t3_5_timer.as_mut().reset(now + Duration::from_secs(86400));
+                                }
+                                None => t3_5_timer.as_mut().reset(now + self.ctx.retransmission_window()),
+                            }
+                        }
+                        Err(err) if self.ctx.strict_recv && !self.buffer.is_empty() => return Err(err.into()),
+                        Err(_) => return Err(ModbusTransportError::Timeout.into()),
+                    }
                 }
             }
         }
@@ -145,6 +504,32 @@ impl Transport for SerialTransport {
         self.port.flush().await?;
         Ok(())
     }
+
+    fn set_device_id(&mut self, device_id: u8) {
+        self.set_slave_addr(device_id);
+    }
+
+    fn device_id(&self) -> Option<u8> {
+        Some(self.ctx.slave_addr)
+    }
+
+    async fn close(mut self) -> Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.port.flush().await?;
+        self.port.shutdown().await?;
+        Ok(())
+    }
+
+    fn last_timing(&self) -> Option<TransactionTiming> {
+        self.marks.timing()
+    }
+
+    async fn check_bus_idle(&mut self, within: Duration) -> bool {
+        SerialTransport::check_bus_idle(self, within).await
+    }
+
+    async fn clear_input(&mut self) -> Result<(), Box<dyn error::Error + Send + Sync>> {
+        SerialTransport::clear_input(self).await
+    }
 }
 
 pub struct SerialTransportBuilder {
@@ -179,6 +564,20 @@ impl SerialTransportBuilder {
         Self { inner, ctx }
     }
 
+    /// Override the inter-character (t1.5) and inter-frame (t3.5) intervals
+    ///
+    /// USB-serial adapters deliver bytes in bursts with their own latency, so that gaps computed
+    /// from the baud rate split a frame and `recv` fails with `FrameIncomplete`. This disables
+    /// the automatic baud rate based calculation, also for a later `set_baud_rate`.
+    pub fn set_frame_gap(self, t1_5: Duration, t3_5: Duration) -> Self {
+        let mut ctx = self.ctx;
+        ctx.set_frame_gap(t1_5, t3_5);
+
+        let inner = self.inner.timeout(ctx.t3_5);
+
+        Self { inner, ctx }
+    }
+
     /// Set the number of data bits
     ///
     /// Note. 2.5.1 RTU Transmission Mode
@@ -200,6 +599,95 @@ impl SerialTransportBuilder {
         }
     }
 
+    /// Set the number of frames failing CRC that `recv` discards before giving up
+    ///
+    /// Unlike a client retry, nothing is re-sent: `recv` keeps listening for a clean frame,
+    /// e.g. from a slave that sends its response twice. After a corrupted frame `recv` waits for
+    /// the next one as long as for a response, or for the time a frame of the maximum size takes
+    /// when no response timeout is set.
+    pub fn set_crc_retry(self, count: u8) -> Self {
+        let mut ctx = self.ctx;
+        ctx.crc_retry = count;
+
+        Self {
+            inner: self.inner,
+            ctx,
+        }
+    }
+
+    /// Skip spurious leading `0x00` bytes before parsing a received frame
+    ///
+    /// Some USB-serial adapters (e.g. CH340 based) emit a `0x00` at line-idle transitions,
+    /// which would otherwise be read as the slave address.
+    pub fn set_strip_leading_nulls(self, strip: bool) -> Self {
+        let mut ctx = self.ctx;
+        ctx.strip_leading_nulls = strip;
+
+        Self {
+            inner: self.inner,
+            ctx,
+        }
+    }
+
+    /// Report a received frame that is rejected, e.g. for another slave or failing CRC
+    ///
+    /// By default such a frame is dropped and `recv` times out. When strict, `recv` returns
+    /// the reason the frame was rejected instead, and a silent interval of more than t1.5
+    /// between two reads of a frame fails it with `FrameIncomplete`.
+    pub fn set_strict_recv(self, strict: bool) -> Self {
+        let mut ctx = self.ctx;
+        ctx.strict_recv = strict;
+
+        Self {
+            inner: self.inner,
+            ctx,
+        }
+    }
+
+    /// Hold slave addresses to the Modbus addressing rules
+    ///
+    /// When strict, `send` refuses the reserved addresses `248..=255` with `InvalidSlaveAddress`
+    /// and a request other than a write to the broadcast address `0`. `try_set_slave_addr`
+    /// refuses a reserved address up front. The default
+    /// is permissive for devices that do not follow the rules.
+    pub fn set_strict_addressing(self, strict: bool) -> Self {
+        let mut ctx = self.ctx;
+        ctx.strict_addressing = strict;
+
+        Self {
+            inner: self.inner,
+            ctx,
+        }
+    }
+
+    /// Set the delay after a broadcast (slave address `0`) before the next request
+    ///
+    /// No slave answers a broadcast, so `send` waits this long for every slave to process it
+    /// instead of a response. Requests to a single slave are not affected.
+    pub fn set_broadcast_turnaround(self, turnaround: Duration) -> Self {
+        let mut ctx = self.ctx;
+        ctx.broadcast_turnaround = turnaround;
+
+        Self {
+            inner: self.inner,
+            ctx,
+        }
+    }
+
+    /// Set how long `recv` waits for the first byte of a response
+    ///
+    /// `recv` gives up with `Timeout` when the slave stays silent. Once the response started,
+    /// its end is detected by the inter-frame delay alone. By default `recv` waits forever.
+    pub fn set_response_timeout(self, timeout: Duration) -> Self {
+        let mut ctx = self.ctx;
+        ctx.response_timeout = Some(timeout);
+
+        Self {
+            inner: self.inner,
+            ctx,
+        }
+    }
+
     pub fn build(self) -> Result<SerialTransport, ModbusTransportError> {
         let port = self
             .inner
@@ -210,18 +698,630 @@ impl SerialTransportBuilder {
             port,
             ctx: self.ctx,
             buffer: Adu::default(),
+            peeked: false,
+            marks: TimingMarks::default(),
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::app::client::{Client, PollItem, PollOutcome};
+    use crate::error::{ModbusApplicationError, ModbusError};
+    use crate::frame::pdu::fcode::ExceptionCode;
     use crate::frame::pdu::function::{
-        request::ReadHoldingRegistersRequest, response::ReadHoldingRegistersResponse,
+        request::{ReadHoldingRegistersRequest, WriteSingleRegisterRequest},
+        response::{GetCommEventCounterResponse, ReadCoilsResponse, ReadHoldingRegistersResponse},
     };
 
     use super::*;
 
+    #[test]
+    fn test_transport_rtu_transaction_time() {
+        let mut ctx = RtuContext::default();
+        ctx.set_interval(19200);
+
+        // 8 bytes request, 5 + 2 * 10 bytes response, 2 * 3.5 characters of silence
+        let request = ReadHoldingRegistersRequest::new(0x0000, 10).unwrap();
+        let expected = ctx.char_time * (8 + 25) + ctx.char_time.mul_f64(7.0);
+        let elapsed = ctx.transaction_time(&request.into_inner());
+
+        assert!(elapsed.abs_diff(expected) < Duration::from_micros(1));
+    }
+
+    #[test]
+    fn test_transport_rtu_frame_gap_override() {
+        let builder = SerialTransportBuilder::new("/dev/null", 9600)
+            .set_frame_gap(Duration::from_millis(5), Duration::from_millis(20))
+            .set_baud_rate(19200);
+
+        // The baud rate still sets the character time
+        assert_eq!(builder.ctx.t1_5, Duration::from_millis(5));
+        assert_eq!(builder.ctx.t3_5, Duration::from_millis(20));
+        assert!(builder.ctx.char_time < Duration::from_micros(600));
+
+        let mut ctx = RtuContext::default();
+        ctx.set_interval(115_200);
+        assert_eq!(ctx.t3_5, Duration::from_micros(1750));
+    }
+
+    #[test]
+    fn test_transport_rtu_transaction_time_write() {
+        let mut ctx = RtuContext::default();
+        ctx.set_interval(115_200);
+
+        let request = WriteSingleRegisterRequest::new(0x0000, 0x1234).unwrap();
+        let expected = ctx.char_time * 16 + Duration::from_micros(3500);
+
+        assert_eq!(ctx.transaction_time(&request.into_inner()), expected);
+    }
+
+    #[test]
+    fn test_transport_rtu_parse_frame_strip_leading_nulls() {
+        let frame = [0x00, 0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9B];
+        let mut ctx = RtuContext {
+            slave_addr: 0x11,
+            ..Default::default()
+        };
+        assert!(ctx.parse_frame(&frame).is_err());
+
+        ctx.strip_leading_nulls = true;
+        let pdu = ctx.parse_frame(&frame).unwrap();
+        assert_eq!(pdu.as_slice(), &frame[2..7]);
+    }
+
+    /// Slave answering requests on the other end of an in-memory serial line
+    ///
+    /// Accepts requests for any address and answers from `slave_addr`. Holds 16 coils and 16
+    /// registers; any other address is answered with an exception.
+    async fn run_slave(mut port: tokio::io::DuplexStream, slave_addr: u8) {
+        let mut coils = [false; 16];
+        let mut registers = [0u16; 16];
+        let mut buf = [0; 256];
+
+        loop {
+            let n = port.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+
+            let request = RtuFrameHandler::parse_frame(&buf[..n], 0).unwrap();
+            let function_code = request.function_code().unwrap();
+            let address = request.read_u16(0).unwrap() as usize;
+            let value = request.read_u16(2).unwrap_or_default();
+
+            let response = match function_code {
+                0x01..=0x04 if address + value as usize > 16 => None,
+                0x01 | 0x02 => Some(
+                    ReadCoilsResponse::from_coils(&coils[address..address + value as usize])
+                        .unwrap()
+                        .into_inner(),
+                ),
+                0x03 | 0x04 => Some(
+                    ReadHoldingRegistersResponse::from_registers(
+                        &registers[address..address + value as usize],
+                    )
+                    .unwrap()
+                    .into_inner(),
+                ),
+                0x05 | 0x06 if address >= 16 => None,
+                0x05 => {
+                    coils[address] = value == 0xFF00;
+                    Some(request.clone())
+                }
+                0x06 => {
+                    registers[address] = value;
+                    Some(request.clone())
+                }
+                0x08 => Some(request.clone()),
+                0x0B => Some(
+                    GetCommEventCounterResponse::new(0x0000, 0x0010)
+                        .unwrap()
+                        .into_inner(),
+                ),
+                _ => None,
+            };
+
+            // Answer under the request code, so that 0x02/0x04 reuse the 0x01/0x03 builders
+            let response = match response {
+                Some(response) => {
+                    let mut pdu = Pdu::new(function_code).unwrap();
+                    pdu.put_slice(response.data()).unwrap();
+                    pdu
+                }
+                None => {
+                    let mut pdu = Pdu::new(function_code | 0x80).unwrap();
+                    pdu.put_u8(0x02).unwrap();
+                    pdu
+                }
+            };
+
+            let mut adu = Adu::default();
+            RtuFrameHandler::build_frame(&mut adu, slave_addr, &response).unwrap();
+            port.write_all(adu.as_slice()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_client_round_trip() {
+        let (master, slave) = tokio::io::duplex(256);
+        tokio::spawn(run_slave(slave, 0x11));
+
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+        transport.set_slave_addr(0x11);
+        let mut client = Client::new(transport);
+
+        let response = client.write_single_coil(0x0003, true).await.unwrap();
+        assert_eq!(response.output_value(), Some(true));
+        let response = client.read_coils(0x0000, 5).await.unwrap();
+        assert!(response
+            .coil_status()
+            .unwrap()
+            .take(5)
+            .eq([false, false, false, true, false]));
+        let response = client.read_discrete_inputs(0x0003, 1).await.unwrap();
+        assert_eq!(response.input_status().unwrap().next(), Some(true));
+
+        let response = client.write_single_register(0x0002, 0x1234).await.unwrap();
+        assert_eq!(response.register_value(), Some(0x1234));
+        let response = client.read_holding_registers(0x0001, 2).await.unwrap();
+        assert!(response.register_value().unwrap().eq([0x0000, 0x1234]));
+        let response = client.read_input_registers(0x0002, 1).await.unwrap();
+        assert_eq!(response.register(0), Some(0x1234));
+
+        assert!(client
+            .restart_communications(false)
+            .await
+            .unwrap()
+            .is_some());
+        let response = client.get_comm_event_counter().await.unwrap();
+        assert_eq!(response.event_count(), Some(0x0010));
+
+        assert!(matches!(
+            client.read_holding_registers(0x000F, 2).await,
+            Err(ModbusError::Exception(ExceptionCode::IllegalDataAddress))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_client_poll_crc_error() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+        transport.ctx.strict_recv = true;
+        let mut client = Client::new(transport);
+
+        let items = [PollItem::new(
+            0x11,
+            ReadHoldingRegistersRequest::new(0x006B, 2).unwrap(),
+        )];
+        let response = async {
+            let mut request = [0; 8];
+            slave.read_exact(&mut request).await.unwrap();
+            // Last byte of the CRC flipped
+            slave
+                .write_all(&[0x11, 0x03, 0x04, 0xAE, 0x41, 0x56, 0x52, 0x25, 0x54])
+                .await
+                .unwrap();
+        };
+        let (outcomes, _) = tokio::join!(client.poll_once(&items), response);
+
+        assert!(matches!(outcomes[0], PollOutcome::CrcError));
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_client_other_slave() {
+        let (master, slave) = tokio::io::duplex(256);
+        tokio::spawn(run_slave(slave, 0x12));
+
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+        transport.set_slave_addr(0x11);
+        let mut client = Client::new(transport);
+
+        // The slave answers from its own address, which the master does not accept
+        assert!(matches!(
+            client.read_holding_registers(0x0000, 1).await,
+            Err(ModbusError::TransportError(ModbusTransportError::Timeout))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_send_raw_adu() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+
+        // Deliberately wrong CRC
+        let adu = [0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9C];
+        transport.send_raw_adu(&adu).await.unwrap();
+
+        let previous = transport.ctx.latest_time;
+        transport.send_raw_adu(&adu).await.unwrap();
+        assert!(transport.marks.write_start.unwrap() >= previous + transport.ctx.t3_5);
+
+        let mut buf = [0; 16];
+        slave.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf[..8], adu);
+        assert_eq!(buf[8..], adu);
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_recv_chunked_frame() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 9600);
+        transport.set_slave_addr(0x11);
+
+        // The second read must append to the first instead of overwriting it
+        let frame = [0x11, 0x03, 0x04, 0xAE, 0x41, 0x56, 0x52, 0x25, 0x53];
+        let response = async {
+            slave.write_all(&frame[..4]).await.unwrap();
+            tokio::task::yield_now().await;
+            slave.write_all(&frame[4..]).await.unwrap();
+        };
+        let (pdu, _) = tokio::join!(transport.recv(), response);
+
+        let pdu = pdu.unwrap();
+        assert_eq!(pdu.as_slice(), &frame[1..7]);
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_recv_strict_char_gap() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 9600);
+        transport
+            .ctx
+            .set_frame_gap(Duration::from_millis(1), Duration::from_millis(50));
+        transport.set_slave_addr(0x11);
+
+        // Split by a gap longer than t1.5 but shorter than t3.5
+        let frame = [0x11, 0x03, 0x04, 0xAE, 0x41, 0x56, 0x52, 0x25, 0x53];
+        let response = async {
+            slave.write_all(&frame[..4]).await.unwrap();
+            sleep(Duration::from_millis(10)).await;
+            slave.write_all(&frame[4..]).await.unwrap();
+        };
+        let (pdu, _) = tokio::join!(transport.recv(), response);
+        assert_eq!(pdu.unwrap().as_slice(), &frame[1..7]);
+
+        transport.ctx.strict_recv = true;
+        let response = async {
+            slave.write_all(&frame[..4]).await.unwrap();
+            sleep(Duration::from_millis(10)).await;
+            slave.write_all(&frame[4..]).await.unwrap();
+        };
+        let (err, _) = tokio::join!(transport.recv(), response);
+        assert!(matches!(
+            err.unwrap_err().downcast_ref::<ModbusTransportError>(),
+            Some(ModbusTransportError::FrameIncomplete)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_response_timeout() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        // t3.5 is 32 ms at 1200 baud
+        let mut transport = SerialTransport::from_stream(master, 1200);
+        transport.ctx.response_timeout = Some(Duration::from_millis(20));
+        transport.set_slave_addr(0x11);
+
+        let started = Instant::now();
+        let err = transport.recv().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ModbusTransportError>(),
+            Some(ModbusTransportError::Timeout)
+        ));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+
+        // A response started in time is completed past the deadline
+        let frame = [0x11, 0x03, 0x04, 0xAE, 0x41, 0x56, 0x52, 0x25, 0x53];
+        let response = async {
+            slave.write_all(&frame[..4]).await.unwrap();
+            sleep(Duration::from_millis(5)).await;
+            slave.write_all(&frame[4..]).await.unwrap();
+        };
+        transport.ctx.response_timeout = Some(Duration::from_millis(1));
+        let (pdu, _) = tokio::join!(transport.recv(), response);
+        assert_eq!(pdu.unwrap().as_slice(), &frame[1..7]);
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_crc_retry_timeout() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+        transport.ctx.crc_retry = 1;
+        transport.set_slave_addr(0x11);
+
+        // A corrupted frame and no retransmission
+        let frame = [0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9C];
+        slave.write_all(&frame).await.unwrap();
+
+        transport.ctx.response_timeout = Some(Duration::from_millis(20));
+        let started = Instant::now();
+        let err = tokio::time::timeout(Duration::from_secs(1), transport.recv())
+            .await
+            .expect("recv must give up on the retransmission")
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ModbusTransportError>(),
+            Some(ModbusTransportError::Timeout)
+        ));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+
+        // Without a response timeout the retransmission must follow within one frame time
+        slave.write_all(&frame).await.unwrap();
+        transport.ctx.response_timeout = None;
+        let err = tokio::time::timeout(Duration::from_secs(1), transport.recv())
+            .await
+            .expect("recv must give up on the retransmission")
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ModbusTransportError>(),
+            Some(ModbusTransportError::Timeout)
+        ));
+
+        // A clean retransmission is still taken
+        let clean = [0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9B];
+        transport.ctx.response_timeout = Some(Duration::from_millis(200));
+        let response = async {
+            slave.write_all(&frame).await.unwrap();
+            sleep(Duration::from_millis(10)).await;
+            slave.write_all(&clean).await.unwrap();
+        };
+        let (pdu, _) = tokio::join!(transport.recv(), response);
+        assert_eq!(pdu.unwrap().as_slice(), &clean[1..6]);
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_crc_retry_retransmission() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+        transport.ctx.crc_retry = 1;
+        transport.set_slave_addr(0x11);
+
+        // The slave sends its response twice, the first copy corrupted, without a response
+        // timeout configured
+        let frame = [0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9C];
+        let clean = [0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9B];
+        let t3_5 = transport.ctx.t3_5;
+        let response = async {
+            slave.write_all(&frame).await.unwrap();
+            sleep(t3_5 * 2).await;
+            slave.write_all(&clean).await.unwrap();
+        };
+        let (pdu, _) = tokio::join!(transport.recv(), response);
+        assert_eq!(pdu.unwrap().as_slice(), &clean[1..6]);
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_send_waits_after_recv() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 9600);
+        transport.set_slave_addr(0x11);
+
+        let frame = [0x11, 0x06, 0x00, 0x01, 0x00, 0x03, 0x9A, 0x9B];
+        slave.write_all(&frame).await.unwrap();
+        transport.recv().await.unwrap();
+        let last_byte = transport.ctx.latest_time;
+        assert_eq!(transport.marks.complete, Some(last_byte));
+
+        // Write right after the response, the frame must still wait for t3.5
+        let request = WriteSingleRegisterRequest::new(0x0001, 0x0003).unwrap();
+        transport.send(&request.into_inner()).await.unwrap();
+        assert!(transport.marks.write_start.unwrap() >= last_byte + transport.ctx.t3_5);
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_broadcast_turnaround() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+        transport.ctx.broadcast_turnaround = Duration::from_millis(50);
+        let request = WriteSingleRegisterRequest::new(0x0001, 0x0003)
+            .unwrap()
+            .into_inner();
+
+        transport.set_slave_addr(0);
+        assert!(transport.is_broadcast());
+        transport.send(&request).await.unwrap();
+        let write_end = transport.marks.write_end.unwrap();
+        assert!(write_end.elapsed() >= Duration::from_millis(50));
+
+        transport.set_slave_addr(0x11);
+        transport.send(&request).await.unwrap();
+        assert!(transport.marks.write_end.unwrap().elapsed() < Duration::from_millis(50));
+
+        let mut buf = [0; 16];
+        slave.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf[0], 0x00);
+        assert_eq!(buf[8], 0x11);
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_client_broadcast_write() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+        transport.ctx.broadcast_turnaround = Duration::from_millis(20);
+        transport.set_slave_addr(0x11);
+        let mut client = Client::new(transport);
+
+        let started = Instant::now();
+        client
+            .write_multiple_registers_broadcast(0x0001, &[0x000A, 0x0102])
+            .await
+            .unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(20));
+
+        let mut buf = [0; 13];
+        slave.read_exact(&mut buf).await.unwrap();
+        let pdu = RtuFrameHandler::parse_frame(&buf, 0).unwrap();
+        assert_eq!(buf[0], 0x00);
+        assert_eq!(
+            pdu.as_slice(),
+            &[0x10, 0x00, 0x01, 0x00, 0x02, 0x04, 0x00, 0x0A, 0x01, 0x02]
+        );
+
+        client
+            .write_multiple_coils_broadcast(0x0000, &[true])
+            .await
+            .unwrap();
+        slave.read_exact(&mut buf[..10]).await.unwrap();
+        assert_eq!(buf[0], 0x00);
+        client
+            .write_single_register_broadcast(0x0001, 0x0003)
+            .await
+            .unwrap();
+        slave.read_exact(&mut buf[..8]).await.unwrap();
+        assert_eq!(buf[..6], [0x00, 0x06, 0x00, 0x01, 0x00, 0x03]);
+        client
+            .write_single_coil_broadcast(0x0002, true)
+            .await
+            .unwrap();
+        slave.read_exact(&mut buf[..8]).await.unwrap();
+        assert_eq!(buf[..6], [0x00, 0x05, 0x00, 0x02, 0xFF, 0x00]);
+        client
+            .broadcast_write_multiple_registers(0x0001, &[0x000A, 0x0102])
+            .await
+            .unwrap();
+        slave.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf[..2], [0x00, 0x10]);
+
+        // The slave addressed before is addressed again
+        let request = WriteSingleRegisterRequest::new(0x0001, 0x0003).unwrap();
+        client.broadcast(request).await.unwrap();
+        slave.read_exact(&mut buf[..8]).await.unwrap();
+        assert_eq!(buf[0], 0x11);
+
+        assert!(matches!(
+            client
+                .broadcast(ReadHoldingRegistersRequest::new(0x0000, 1).unwrap())
+                .await,
+            Err(ModbusError::ApplicationError(
+                ModbusApplicationError::NotBroadcastable(0x03)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_check_bus_idle() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut client = Client::new(SerialTransport::from_stream(master, 115_200));
+        client.set_idle_bus_check(Some(Duration::from_millis(10)));
+
+        // A slave babbling on the bus
+        slave.write_all(&[0x55; 8]).await.unwrap();
+        assert!(matches!(
+            client.write_single_register(0x0001, 0x0003).await,
+            Err(ModbusError::TransportError(ModbusTransportError::BusBusy))
+        ));
+
+        let (master, _slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+        assert!(transport.check_bus_idle(Duration::from_millis(10)).await);
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_purge_on_request() {
+        let (master, mut slave) = tokio::io::duplex(256);
+        let mut client = Client::new(SerialTransport::from_stream(master, 115_200));
+        client.set_purge_on_request(true);
+
+        // Tail of another master's transaction, still buffered
+        slave.write_all(&[0x11, 0x06, 0x00]).await.unwrap();
+
+        let slave = tokio::spawn(async move {
+            let mut request = [0; 8];
+            slave.read_exact(&mut request).await.unwrap();
+            // Write Single Register echoes the request
+            slave.write_all(&request).await.unwrap();
+            slave
+        });
+
+        client.set_device_id(0x11);
+        client.write_single_register(0x0001, 0x0003).await.unwrap();
+        slave.await.unwrap();
+
+        let (master, _slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+        transport.clear_input().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_strict_addressing() {
+        let (master, _slave) = tokio::io::duplex(256);
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+
+        // Permissive by default
+        transport.set_slave_addr(248);
+
+        assert!(transport.try_set_slave_addr(248).is_ok());
+
+        transport.ctx.strict_addressing = true;
+        assert!(matches!(
+            transport.try_set_slave_addr(248),
+            Err(ModbusRtuError::InvalidSlaveAddress(248))
+        ));
+        // Taken as is, but refused when sending
+        transport.set_slave_addr(248);
+        let write = WriteSingleRegisterRequest::new(0x0001, 0x0003)
+            .unwrap()
+            .into_inner();
+        assert!(matches!(
+            transport
+                .send(&write)
+                .await
+                .unwrap_err()
+                .downcast_ref::<ModbusFrameError>(),
+            Some(ModbusFrameError::RtuError(
+                ModbusRtuError::InvalidSlaveAddress(248)
+            ))
+        ));
+        assert!(transport.try_set_slave_addr(247).is_ok());
+        assert!(transport.try_set_slave_addr(0).is_ok());
+
+        // Only writes are broadcast
+        let read = ReadHoldingRegistersRequest::new(0x0000, 1)
+            .unwrap()
+            .into_inner();
+        assert!(transport.send(&read).await.is_err());
+        transport.ctx.broadcast_turnaround = Duration::ZERO;
+        transport.send(&write).await.unwrap();
+
+        // Set through the client, refused when sending
+        let mut client = Client::new(transport);
+        client.set_device_id(0xFF);
+        assert!(client.write_single_register(0x0001, 0x0003).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_close() {
+        let (master, mut slave) = tokio::io::duplex(256);
+
+        let transport = SerialTransport::from_stream(master, 115_200);
+        transport.close().await.unwrap();
+
+        // The other end sees the stream closed
+        let mut buf = [0; 8];
+        assert_eq!(slave.read(&mut buf).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_transport_rtu_last_timing() {
+        let (master, slave) = tokio::io::duplex(256);
+        tokio::spawn(run_slave(slave, 0x11));
+
+        let mut transport = SerialTransport::from_stream(master, 115_200);
+        transport.set_slave_addr(0x11);
+        assert!(transport.last_timing().is_none());
+
+        let start = Instant::now();
+        let request = ReadHoldingRegistersRequest::new(0x0000, 2).unwrap();
+        transport.send(&request.into_inner()).await.unwrap();
+        // Not complete until the response is received
+        assert!(transport.last_timing().is_none());
+
+        transport.recv().await.unwrap();
+        let timing = transport.last_timing().unwrap();
+        assert!(timing.total() <= start.elapsed());
+    }
+
+    #[cfg(feature = "hardware-tests")]
     #[tokio::test]
     async fn test_transport_rtu_session() {
         let mut transport = SerialTransport::builder("/dev/ttyCH341USB0", 115_200)
@@ -1,2 +1,303 @@
-// ToDo. Modbus TCPの実装を追加する
-// const MAX_ADU_SIZE: usize = 260;
+use crate::{
+    error::{ModbusFrameError, ModbusTcpError, ModbusTransportError},
+    frame::{
+        pdu::Pdu,
+        tcp::{Adu, MbapHeader, TcpFrameHandler},
+    },
+    lib::*,
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpStream, ToSocketAddrs},
+};
+
+use super::Transport;
+
+/// Default number of bytes requested from the stream per read, the size of an RTU ADU
+const DEFAULT_READ_CHUNK_SIZE: usize = 256;
+
+/// Modbus TCP transport
+///
+/// Any other byte stream, e.g. an in-memory pipe, can be wrapped with `from_stream`.
+#[derive(Debug)]
+pub struct TcpTransport<S = TcpStream> {
+    stream: S,
+    unit_id: u8,
+    /// Transaction id of the latest request
+    transaction_id: Option<u16>,
+    buffer: Adu,
+    /// Received bytes not yet returned as a frame
+    received: Vec<u8>,
+    read_chunk_size: usize,
+}
+
+impl TcpTransport {
+    /// Connect to a Modbus TCP server or gateway
+    pub async fn connect<A: ToSocketAddrs>(
+        addr: A,
+        unit_id: u8,
+    ) -> Result<Self, ModbusTransportError> {
+        let stream = TcpStream::connect(addr)
+            .await
+            .map_err(|err| ModbusTransportError::TransportError(err.into()))?;
+        // Requests are small and latency bound
+        stream
+            .set_nodelay(true)
+            .map_err(|err| ModbusTransportError::TransportError(err.into()))?;
+
+        Ok(Self::from_stream(stream, unit_id))
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> TcpTransport<S> {
+    pub fn from_stream(stream: S, unit_id: u8) -> Self {
+        Self {
+            stream,
+            unit_id,
+            transaction_id: None,
+            buffer: Adu::default(),
+            received: Vec::new(),
+            read_chunk_size: DEFAULT_READ_CHUNK_SIZE,
+        }
+    }
+
+    /// Set the unit identifier
+    pub fn set_unit_id(&mut self, unit_id: u8) {
+        self.unit_id = unit_id;
+    }
+
+    /// Set the number of bytes requested from the stream per read
+    ///
+    /// A larger chunk takes several queued responses in one syscall. `recv` still returns one
+    /// frame per call and keeps the surplus for the following calls.
+    pub fn set_read_chunk_size(&mut self, read_chunk_size: usize) {
+        self.read_chunk_size = read_chunk_size.max(1);
+    }
+
+    /// Transaction id the next request will be sent with
+    pub fn next_transaction_id(&self) -> u16 {
+        self.transaction_id.map_or(1, |id| id.wrapping_add(1))
+    }
+
+    /// Length of the first frame in the received bytes, once it is complete
+    fn complete_frame_len(&self) -> Result<Option<usize>, ModbusTcpError> {
+        let Ok(header) = MbapHeader::parse(&self.received) else {
+            return Ok(None);
+        };
+        header.validate_length()?;
+
+        let adu_length = header.adu_length();
+        Ok((self.received.len() >= adu_length).then_some(adu_length))
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> Transport for TcpTransport<S> {
+    async fn send(
+        &mut self,
+        pdu: &Pdu,
+    ) -> core::result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        let transaction_id = self.next_transaction_id();
+        self.transaction_id = Some(transaction_id);
+        TcpFrameHandler::build_frame(&mut self.buffer, transaction_id, self.unit_id, pdu)?;
+
+        self.stream.write_all(self.buffer.as_slice()).await?;
+        self.stream.flush().await?;
+
+        Ok(())
+    }
+
+    async fn recv(&mut self) -> core::result::Result<Pdu, Box<dyn error::Error + Send + Sync>> {
+        loop {
+            let complete_frame_len = self.complete_frame_len().map_err(|err| {
+                // The stream can not be resynchronised after a bogus header
+                self.received.clear();
+                ModbusFrameError::from(err)
+            })?;
+            if let Some(len) = complete_frame_len {
+                let (header, pdu) = TcpFrameHandler::parse_frame(&self.received[..len])?;
+                self.received.drain(..len);
+
+                // e.g. a late response to a request that already timed out
+                if Some(header.transaction_id) != self.transaction_id {
+                    return Err(ModbusTransportError::FrameIncomplete.into());
+                }
+
+                return Ok(pdu);
+            }
+
+            let start = self.received.len();
+            self.received.resize(start + self.read_chunk_size, 0);
+            let n = self.stream.read(&mut self.received[start..]).await?;
+            self.received.truncate(start + n);
+
+            // The stream was closed
+            if n == 0 {
+                return Err(ModbusTransportError::FrameIncomplete.into());
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> core::result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    fn set_device_id(&mut self, device_id: u8) {
+        self.set_unit_id(device_id);
+    }
+
+    fn device_id(&self) -> Option<u8> {
+        Some(self.unit_id)
+    }
+
+    fn last_transaction_id(&self) -> Option<u16> {
+        self.transaction_id
+    }
+
+    async fn close(mut self) -> core::result::Result<(), Box<dyn error::Error + Send + Sync>> {
+        self.stream.flush().await?;
+        self.stream.shutdown().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_transport_tcp_recv_queued_frames() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut transport = TcpTransport::from_stream(client, 0x11);
+        transport.set_read_chunk_size(1024);
+
+        // Two responses arriving in one segment, followed by the start of a third
+        server
+            .write_all(&[
+                0x00, 0x01, 0x00, 0x00, 0x00, 0x05, 0x11, 0x03, 0x02, 0x12, 0x34, //
+                0x00, 0x02, 0x00, 0x00, 0x00, 0x06, 0x11, 0x06, 0x00, 0x01, 0x00, 0x03, //
+                0x00, 0x03, 0x00, 0x00,
+            ])
+            .await
+            .unwrap();
+
+        let request = Pdu::new(0x03).unwrap();
+        transport.send(&request).await.unwrap();
+        let pdu = transport.recv().await.unwrap();
+        assert_eq!(pdu.as_slice(), &[0x03, 0x02, 0x12, 0x34]);
+        transport.send(&request).await.unwrap();
+        let pdu = transport.recv().await.unwrap();
+        assert_eq!(pdu.as_slice(), &[0x06, 0x00, 0x01, 0x00, 0x03]);
+
+        server
+            .write_all(&[0x00, 0x03, 0x11, 0x06, 0x00, 0x02])
+            .await
+            .unwrap();
+        transport.send(&request).await.unwrap();
+        let pdu = transport.recv().await.unwrap();
+        assert_eq!(pdu.as_slice(), &[0x06, 0x00]);
+    }
+
+    #[tokio::test]
+    async fn test_transport_tcp_recv_small_chunks() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut transport = TcpTransport::from_stream(client, 0x11);
+        transport.set_read_chunk_size(3);
+
+        server
+            .write_all(&[
+                0x00, 0x01, 0x00, 0x00, 0x00, 0x05, 0x11, 0x03, 0x02, 0x12, 0x34,
+            ])
+            .await
+            .unwrap();
+
+        transport.send(&Pdu::new(0x03).unwrap()).await.unwrap();
+        let pdu = transport.recv().await.unwrap();
+        assert_eq!(pdu.as_slice(), &[0x03, 0x02, 0x12, 0x34]);
+
+        drop(server);
+        assert!(transport.recv().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transport_tcp_recv_invalid_length() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut transport = TcpTransport::from_stream(client, 0x11);
+
+        // Header claiming a 64 KiB frame, the body never arrives
+        server
+            .write_all(&[0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x11])
+            .await
+            .unwrap();
+
+        let err = tokio::time::timeout(core::time::Duration::from_secs(1), transport.recv())
+            .await
+            .expect("recv waited for the claimed body")
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ModbusFrameError>(),
+            Some(ModbusFrameError::TcpError(ModbusTcpError::InvalidLength(
+                0xFFFF
+            )))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_transport_tcp_transaction_id() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut transport = TcpTransport::from_stream(client, 0x11);
+        let request = Pdu::new(0x07).unwrap();
+        assert_eq!(transport.next_transaction_id(), 1);
+        assert_eq!(transport.last_transaction_id(), None);
+
+        transport.send(&request).await.unwrap();
+        transport.send(&request).await.unwrap();
+        assert_eq!(transport.last_transaction_id(), Some(2));
+        assert_eq!(transport.next_transaction_id(), 3);
+        let mut buf = [0; 16];
+        server.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf[..8], &[0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x11, 0x07]);
+        assert_eq!(&buf[8..], &[0x00, 0x02, 0x00, 0x00, 0x00, 0x02, 0x11, 0x07]);
+
+        // Late response to the first request, then the expected one
+        server
+            .write_all(&[
+                0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x11, 0x07, 0x6D, //
+                0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x11, 0x07, 0x6E,
+            ])
+            .await
+            .unwrap();
+
+        let err = transport.recv().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ModbusTransportError>(),
+            Some(ModbusTransportError::FrameIncomplete)
+        ));
+        let pdu = transport.recv().await.unwrap();
+        assert_eq!(pdu.as_slice(), &[0x07, 0x6E]);
+    }
+
+    #[tokio::test]
+    async fn test_transport_tcp_connect() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (transport, accepted) =
+            tokio::join!(TcpTransport::connect(addr, 0x11), listener.accept());
+        let mut transport = transport.unwrap();
+        let (mut server, _) = accepted.unwrap();
+
+        transport.send(&Pdu::new(0x07).unwrap()).await.unwrap();
+        let mut buf = [0; 8];
+        server.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, [0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x11, 0x07]);
+
+        server
+            .write_all(&[0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x11, 0x07, 0x00])
+            .await
+            .unwrap();
+        let pdu = transport.recv().await.unwrap();
+        assert_eq!(pdu.as_slice(), &[0x07, 0x00]);
+    }
+}